@@ -0,0 +1,133 @@
+//! One interface over the AEADs offered for file encryption, so the container and
+//! STREAM code do not care which cipher seals the data.
+//!
+//! ChaCha20-Poly1305 is fast in software on machines without AES instructions, and
+//! XChaCha20-Poly1305's 192-bit nonces are safe to pick at random for any number of
+//! messages under one key.
+
+use aes_gcm::Aes256Gcm;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use anyhow::{Result, anyhow, bail};
+use chacha20poly1305::{ChaCha20Poly1305, XChaCha20Poly1305};
+
+pub const KEY_LEN: usize = 32;
+
+/// Authentication tag length, the same for all three ciphers.
+pub const TAG_LEN: usize = 16;
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AeadAlgorithm {
+    #[default]
+    Aes256Gcm,
+    ChaCha20Poly1305,
+    XChaCha20Poly1305,
+}
+
+impl AeadAlgorithm {
+    pub const ALL: [AeadAlgorithm; 3] = [
+        AeadAlgorithm::Aes256Gcm,
+        AeadAlgorithm::ChaCha20Poly1305,
+        AeadAlgorithm::XChaCha20Poly1305,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AeadAlgorithm::Aes256Gcm => "AES-256-GCM",
+            AeadAlgorithm::ChaCha20Poly1305 => "ChaCha20-Poly1305",
+            AeadAlgorithm::XChaCha20Poly1305 => "XChaCha20-Poly1305",
+        }
+    }
+
+    pub fn nonce_len(self) -> usize {
+        match self {
+            AeadAlgorithm::Aes256Gcm | AeadAlgorithm::ChaCha20Poly1305 => 12,
+            AeadAlgorithm::XChaCha20Poly1305 => 24,
+        }
+    }
+
+    /// A random nonce of the right length.
+    pub fn generate_nonce(self) -> Vec<u8> {
+        let mut nonce = vec![0u8; self.nonce_len()];
+        OsRng.fill_bytes(&mut nonce);
+        nonce
+    }
+
+    pub fn new_cipher(self, key: &[u8]) -> Result<AeadCipher> {
+        let invalid = |_| {
+            anyhow!(
+                "Invalid key length: {} needs {} bytes",
+                self.name(),
+                KEY_LEN
+            )
+        };
+        Ok(match self {
+            AeadAlgorithm::Aes256Gcm => {
+                AeadCipher::Aes256Gcm(Box::new(Aes256Gcm::new_from_slice(key).map_err(invalid)?))
+            }
+            AeadAlgorithm::ChaCha20Poly1305 => AeadCipher::ChaCha20Poly1305(
+                ChaCha20Poly1305::new_from_slice(key).map_err(invalid)?,
+            ),
+            AeadAlgorithm::XChaCha20Poly1305 => AeadCipher::XChaCha20Poly1305(
+                XChaCha20Poly1305::new_from_slice(key).map_err(invalid)?,
+            ),
+        })
+    }
+}
+
+/// A keyed AEAD of any of the supported algorithms.
+pub enum AeadCipher {
+    /// Boxed for its expanded key schedule, which dwarfs the ChaCha keys
+    Aes256Gcm(Box<Aes256Gcm>),
+    ChaCha20Poly1305(ChaCha20Poly1305),
+    XChaCha20Poly1305(XChaCha20Poly1305),
+}
+
+impl AeadCipher {
+    pub fn algorithm(&self) -> AeadAlgorithm {
+        match self {
+            AeadCipher::Aes256Gcm(_) => AeadAlgorithm::Aes256Gcm,
+            AeadCipher::ChaCha20Poly1305(_) => AeadAlgorithm::ChaCha20Poly1305,
+            AeadCipher::XChaCha20Poly1305(_) => AeadAlgorithm::XChaCha20Poly1305,
+        }
+    }
+
+    fn check_nonce(&self, nonce: &[u8]) -> Result<()> {
+        let algorithm = self.algorithm();
+        if nonce.len() != algorithm.nonce_len() {
+            bail!(
+                "{} nonce must be {} bytes, got {}",
+                algorithm.name(),
+                algorithm.nonce_len(),
+                nonce.len()
+            );
+        }
+        Ok(())
+    }
+
+    /// Ciphertext followed by the tag.
+    pub fn encrypt(&self, nonce: &[u8], msg: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        self.check_nonce(nonce)?;
+        let payload = Payload { msg, aad };
+        match self {
+            AeadCipher::Aes256Gcm(c) => c.encrypt(nonce.into(), payload),
+            AeadCipher::ChaCha20Poly1305(c) => c.encrypt(nonce.into(), payload),
+            AeadCipher::XChaCha20Poly1305(c) => c.encrypt(nonce.into(), payload),
+        }
+        .map_err(|e| anyhow!("{} Encryption Failed {:?}", self.algorithm().name(), e))
+    }
+
+    pub fn decrypt(&self, nonce: &[u8], ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        self.check_nonce(nonce)?;
+        let payload = Payload {
+            msg: ciphertext,
+            aad,
+        };
+        match self {
+            AeadCipher::Aes256Gcm(c) => c.decrypt(nonce.into(), payload),
+            AeadCipher::ChaCha20Poly1305(c) => c.decrypt(nonce.into(), payload),
+            AeadCipher::XChaCha20Poly1305(c) => c.decrypt(nonce.into(), payload),
+        }
+        .map_err(|e| anyhow!("{} Decryption Failed: {e}", self.algorithm().name()))
+    }
+}
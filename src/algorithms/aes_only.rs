@@ -1,51 +1,218 @@
-// This function and file is for encrypting using AES ONLY. No RSA included. This is symmetric key
-// cryptography
+// Symmetric file encryption only, with a random key file or a passphrase and any of the
+// AEADs. No RSA included; see rsa_hybrid for that.
 
+use crate::algorithms::aead::{AeadAlgorithm, AeadCipher, KEY_LEN, TAG_LEN};
+use crate::algorithms::envelope::{
+    Algorithm, Header, Kdf, MAGIC, Recipient, armor, dearmor, is_armored,
+};
+use crate::algorithms::kdf::{KdfAlgorithm, derive_key};
 use aes::cipher::generic_array::GenericArray;
+use aes_gcm::aead::rand_core::RngCore;
 use aes_gcm::aead::{Aead, OsRng};
-use aes_gcm::{AeadCore, Aes256Gcm, KeyInit};
+use aes_gcm::{Aes256Gcm, KeyInit};
 use anyhow::Context;
 use base64::{Engine as _, engine::general_purpose};
 use std::error::Error;
-use std::fs;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Cursor, Read, Write};
 
-pub fn encrypt_with_aes(
+/// Encrypts `input_file` under `key` as one AEAD message in a container whose header
+/// records `kdf`.
+fn seal_file(
     input_file: &str,
     output_file: &str,
-    key_file: &str,
-) -> Result<(), Box<dyn Error>> {
-    let aes_key = Aes256Gcm::generate_key(&mut OsRng);
-    let cipher = Aes256Gcm::new(&aes_key);
-    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    key: &[u8],
+    algorithm: AeadAlgorithm,
+    kdf: Kdf,
+    armored: bool,
+) -> anyhow::Result<()> {
+    let cipher = algorithm.new_cipher(key)?;
+    let nonce = algorithm.generate_nonce();
 
-    let plaintext = fs::read(input_file)?;
-    let ciphertext = cipher
-        .encrypt(&nonce, plaintext.as_ref())
-        .map_err(|e| anyhow::anyhow!("AES Encryption Failed {:?}", e))?;
-
-    let encrypted_data = format!(
-        "{}\n{}",
-        general_purpose::STANDARD.encode(&nonce),
-        general_purpose::STANDARD.encode(&ciphertext)
+    let header = Header::new(
+        Algorithm::new(algorithm, false),
+        kdf,
+        Recipient::KeyHolder,
+        nonce.clone(),
     );
+    let mut encrypted_data = header.to_bytes()?;
 
-    fs::write(output_file, &encrypted_data)?;
+    let plaintext = fs::read(input_file)?;
+    let ciphertext = cipher.encrypt(&nonce, &plaintext, &encrypted_data)?;
+    encrypted_data.extend_from_slice(&ciphertext);
+
+    if armored {
+        fs::write(output_file, armor(&encrypted_data))?;
+    } else {
+        fs::write(output_file, &encrypted_data)?;
+    }
+    Ok(())
+}
 
-    let encoded_key = general_purpose::STANDARD.encode(&aes_key);
+/// Streaming counterpart of [`seal_file`]; the output is always a binary container.
+fn seal_file_stream(
+    input_file: &str,
+    output_file: &str,
+    key: &[u8],
+    algorithm: AeadAlgorithm,
+    kdf: Kdf,
+) -> anyhow::Result<()> {
+    let cipher = algorithm.new_cipher(key)?;
+    let mut prefix = vec![0u8; stream_prefix_len(algorithm)];
+    OsRng.fill_bytes(&mut prefix);
+    let header = Header::new(
+        Algorithm::new(algorithm, true),
+        kdf,
+        Recipient::KeyHolder,
+        prefix.clone(),
+    )
+    .to_bytes()?;
+
+    let reader = BufReader::new(File::open(input_file)?);
+    let mut writer = BufWriter::new(File::create(output_file)?);
+    writer.write_all(&header)?;
+    encrypt_stream(&cipher, &prefix, &header, reader, writer)
+}
+
+/// A fresh random file key.
+fn generate_key() -> [u8; KEY_LEN] {
+    let mut key = [0u8; KEY_LEN];
+    OsRng.fill_bytes(&mut key);
+    key
+}
+
+/// Encrypts with a random key, written base64-encoded to `key_file`.
+pub fn encrypt_with_key_file(
+    input_file: &str,
+    output_file: &str,
+    key_file: &str,
+    algorithm: AeadAlgorithm,
+    armored: bool,
+) -> Result<(), Box<dyn Error>> {
+    let key = generate_key();
+    seal_file(input_file, output_file, &key, algorithm, Kdf::None, armored)?;
+
+    let encoded_key = general_purpose::STANDARD.encode(key);
     fs::write(key_file, encoded_key)?;
 
     println!(
-        "AES Encryption done. Encrypted file written to {} and key written to {}",
+        "Encryption done. Encrypted file written to {} and key written to {}",
         output_file, key_file
     );
     Ok(())
 }
 
-pub fn decrypt_with_aes(
+/// Encrypts with a key derived from `passphrase` instead of a random key file. The
+/// salt and costs go into the container header.
+pub fn encrypt_with_passphrase(
+    input_file: &str,
+    output_file: &str,
+    passphrase: &str,
+    kdf_algorithm: KdfAlgorithm,
+    algorithm: AeadAlgorithm,
+    armored: bool,
+    stream: bool,
+) -> Result<(), Box<dyn Error>> {
+    let kdf = kdf_algorithm.new_kdf();
+    let key = derive_key(&kdf, passphrase)?;
+    if stream {
+        seal_file_stream(input_file, output_file, &key, algorithm, kdf)?;
+    } else {
+        seal_file(input_file, output_file, &key, algorithm, kdf, armored)?;
+    }
+    Ok(())
+}
+
+/// Decrypts the payload that follows a container header into `output_file`. The
+/// header bytes are checked as associated data. Streamed payloads are decrypted in
+/// constant memory, and the output is removed again if any part fails to
+/// authenticate, so no unverified plaintext is left behind.
+pub fn decrypt_envelope_payload(
+    header: &Header,
+    header_bytes: &[u8],
+    key: &[u8],
+    mut payload: impl Read,
+    output_file: &str,
+) -> anyhow::Result<()> {
+    let cipher = header.algorithm.aead().new_cipher(key)?;
+    if header.algorithm.is_stream() {
+        if header.nonce.len() != stream_prefix_len(cipher.algorithm()) {
+            anyhow::bail!("Stream nonce prefix has the wrong length");
+        }
+        let writer = BufWriter::new(File::create(output_file)?);
+        if let Err(err) = decrypt_stream(&cipher, &header.nonce, header_bytes, payload, writer) {
+            let _ = fs::remove_file(output_file);
+            return Err(err);
+        }
+    } else {
+        let mut ciphertext = Vec::new();
+        payload.read_to_end(&mut ciphertext)?;
+        let plaintext = cipher.decrypt(&header.nonce, &ciphertext, header_bytes)?;
+        fs::write(output_file, plaintext)?;
+    }
+    Ok(())
+}
+
+/// A container file with its header read and the payload still to come.
+struct OpenedEnvelope {
+    header: Header,
+    /// Raw header, the associated data of the payload
+    header_bytes: Vec<u8>,
+    payload: Box<dyn Read>,
+}
+
+/// Opens a container file, binary or armored, and reads its header. `None` if the
+/// file is in one of the older formats.
+fn open_envelope(input_file: &str) -> anyhow::Result<Option<OpenedEnvelope>> {
+    let mut reader = BufReader::new(File::open(input_file)?);
+    let start = reader.fill_buf()?;
+    if start.starts_with(MAGIC) {
+        let (header, header_bytes) = Header::read_from(&mut reader)?;
+        return Ok(Some(OpenedEnvelope {
+            header,
+            header_bytes,
+            payload: Box::new(reader),
+        }));
+    }
+    if is_armored(start) {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let mut payload = Cursor::new(dearmor(&text)?);
+        let (header, header_bytes) = Header::read_from(&mut payload)?;
+        return Ok(Some(OpenedEnvelope {
+            header,
+            header_bytes,
+            payload: Box::new(payload),
+        }));
+    }
+    Ok(None)
+}
+
+/// Decrypts a container (binary or armored), or the legacy two-line base64 format.
+pub fn decrypt_with_key_file(
     input_file: &str,
     output_file: &str,
     key_file: &str,
 ) -> Result<(), Box<dyn Error>> {
+    let key_b64 = fs::read_to_string(key_file)?;
+    let key_bytes = general_purpose::STANDARD.decode(key_b64.trim())?;
+
+    if let Some(envelope) = open_envelope(input_file)? {
+        check_key_holder(&envelope.header)?;
+        if envelope.header.kdf != Kdf::None {
+            return Err("File is encrypted with a passphrase, not a key file".into());
+        }
+        decrypt_envelope_payload(
+            &envelope.header,
+            &envelope.header_bytes,
+            &key_bytes,
+            envelope.payload,
+            output_file,
+        )?;
+        println!("Decrypted file written to {}", output_file);
+        return Ok(());
+    }
+
     let content = fs::read_to_string(input_file)?;
     let mut lines = content.lines();
 
@@ -55,9 +222,6 @@ pub fn decrypt_with_aes(
     let nonce_bytes = general_purpose::STANDARD.decode(nonce_b64)?;
     let ciphertext = general_purpose::STANDARD.decode(ciphertext_b64)?;
 
-    let key_b64 = fs::read_to_string(key_file)?;
-    let key_bytes = general_purpose::STANDARD.decode(key_b64.trim())?;
-
     let key = GenericArray::from_slice(&key_bytes);
 
     let cipher = Aes256Gcm::new(key);
@@ -71,3 +235,437 @@ pub fn decrypt_with_aes(
     println!("Decrypted file written to {}", output_file);
     Ok(())
 }
+
+/// Decrypts a container written by [`encrypt_with_passphrase`].
+pub fn decrypt_with_passphrase(
+    input_file: &str,
+    output_file: &str,
+    passphrase: &str,
+) -> Result<(), Box<dyn Error>> {
+    let envelope = open_envelope(input_file)?.context("Only container files take a passphrase")?;
+    check_key_holder(&envelope.header)?;
+    let key = derive_key(&envelope.header.kdf, passphrase)?;
+    decrypt_envelope_payload(
+        &envelope.header,
+        &envelope.header_bytes,
+        &key,
+        envelope.payload,
+        output_file,
+    )
+    .context("Wrong passphrase, or the file was modified")?;
+    Ok(())
+}
+
+/// Symmetric decryption only handles containers without an RSA-wrapped key.
+fn check_key_holder(header: &Header) -> anyhow::Result<()> {
+    if header.recipient != Recipient::KeyHolder {
+        anyhow::bail!("File is encrypted for an RSA key; use hybrid decryption");
+    }
+    Ok(())
+}
+
+// Streaming mode: the STREAM construction (Hoang, Reyhanitabar, Rogaway, Vizár) over
+// any of the AEADs, so files of any size are encrypted and decrypted in constant memory.
+//
+// The container header (a stream algorithm, with the random nonce prefix as its nonce:
+// the AEAD's nonce length less 5 bytes) is followed by the segments. Every segment
+// but the last holds STREAM_SEGMENT_SIZE bytes of plaintext plus its 16-byte tag; the
+// last one holds less (possibly nothing). Segment i is sealed under the nonce
+// prefix || i as a big-endian u32 || 1 if it is the last segment, else 0. Dropping,
+// reordering or appending segments therefore makes some tag fail, and cutting the file
+// at a segment boundary leaves no segment sealed as the last one. Every segment also
+// takes the container header as associated data.
+
+/// Plaintext bytes per segment.
+pub const STREAM_SEGMENT_SIZE: usize = 64 * 1024;
+
+/// Random part of the segment nonces: what the counter and last-segment flag leave.
+pub fn stream_prefix_len(algorithm: AeadAlgorithm) -> usize {
+    algorithm.nonce_len() - 5
+}
+
+fn stream_nonce(prefix: &[u8], counter: u32, last: bool) -> Vec<u8> {
+    let mut nonce = prefix.to_vec();
+    nonce.extend_from_slice(&counter.to_be_bytes());
+    nonce.push(last as u8);
+    nonce
+}
+
+/// Reads until `buf` is full or the input ends, returning the number of bytes read.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Encrypts `reader` to `writer` segment by segment, binding `aad` into every segment.
+pub fn encrypt_stream(
+    cipher: &AeadCipher,
+    prefix: &[u8],
+    aad: &[u8],
+    mut reader: impl Read,
+    mut writer: impl Write,
+) -> anyhow::Result<()> {
+    let mut segment = vec![0u8; STREAM_SEGMENT_SIZE];
+    let mut counter: u32 = 0;
+    loop {
+        let len = read_full(&mut reader, &mut segment)?;
+        // A full segment is never the last one: if the input ends right after it, an
+        // empty last segment follows
+        let last = len < STREAM_SEGMENT_SIZE;
+        let nonce = stream_nonce(prefix, counter, last);
+        let sealed = cipher.encrypt(&nonce, &segment[..len], aad)?;
+        writer.write_all(&sealed)?;
+        if last {
+            break;
+        }
+        counter = counter
+            .checked_add(1)
+            .context("Input is too large for one stream")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Decrypts a stream written by [`encrypt_stream`]. Plaintext is written as each
+/// segment checks out, so on error `writer` may hold a prefix of the message that the
+/// caller should discard.
+pub fn decrypt_stream(
+    cipher: &AeadCipher,
+    prefix: &[u8],
+    aad: &[u8],
+    mut reader: impl Read,
+    mut writer: impl Write,
+) -> anyhow::Result<()> {
+    let mut segment = vec![0u8; STREAM_SEGMENT_SIZE + TAG_LEN];
+    let mut counter: u32 = 0;
+    loop {
+        let len = read_full(&mut reader, &mut segment)?;
+        let last = len < segment.len();
+        if len < TAG_LEN {
+            anyhow::bail!("Stream is truncated");
+        }
+        let nonce = stream_nonce(prefix, counter, last);
+        let plaintext = cipher.decrypt(&nonce, &segment[..len], aad).map_err(|_| {
+            anyhow::anyhow!(
+                "Segment {} failed authentication: the file was truncated, reordered or modified",
+                counter
+            )
+        })?;
+        writer.write_all(&plaintext)?;
+        if last {
+            break;
+        }
+        counter = counter
+            .checked_add(1)
+            .context("Stream has too many segments")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Streaming counterpart of [`encrypt_with_key_file`] for files larger than memory. The key
+/// file is written the same way; the output is always a binary container.
+pub fn encrypt_with_key_file_stream(
+    input_file: &str,
+    output_file: &str,
+    key_file: &str,
+    algorithm: AeadAlgorithm,
+) -> Result<(), Box<dyn Error>> {
+    let key = generate_key();
+    seal_file_stream(input_file, output_file, &key, algorithm, Kdf::None)?;
+
+    fs::write(key_file, general_purpose::STANDARD.encode(key))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sealed length of a full segment.
+    const SEALED: usize = STREAM_SEGMENT_SIZE + TAG_LEN;
+
+    /// A cipher, nonce prefix and header as [`seal_file_stream`] would make them.
+    struct Stream {
+        cipher: AeadCipher,
+        prefix: Vec<u8>,
+        header: Vec<u8>,
+    }
+
+    impl Stream {
+        fn new(algorithm: AeadAlgorithm) -> Self {
+            let cipher = algorithm.new_cipher(&generate_key()).unwrap();
+            let mut prefix = vec![0u8; stream_prefix_len(algorithm)];
+            OsRng.fill_bytes(&mut prefix);
+            let header = Header::new(
+                Algorithm::new(algorithm, true),
+                Kdf::None,
+                Recipient::KeyHolder,
+                prefix.clone(),
+            )
+            .to_bytes()
+            .unwrap();
+            Stream {
+                cipher,
+                prefix,
+                header,
+            }
+        }
+
+        fn seal(&self, plain: &[u8]) -> Vec<u8> {
+            let mut sealed = Vec::new();
+            encrypt_stream(&self.cipher, &self.prefix, &self.header, plain, &mut sealed).unwrap();
+            sealed
+        }
+
+        fn open_with_header(&self, header: &[u8], sealed: &[u8]) -> anyhow::Result<Vec<u8>> {
+            let mut plain = Vec::new();
+            decrypt_stream(&self.cipher, &self.prefix, header, sealed, &mut plain)?;
+            Ok(plain)
+        }
+
+        fn open(&self, sealed: &[u8]) -> anyhow::Result<Vec<u8>> {
+            self.open_with_header(&self.header, sealed)
+        }
+    }
+
+    fn plaintext(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn stream_round_trips() {
+        for algorithm in AeadAlgorithm::ALL {
+            let stream = Stream::new(algorithm);
+            for len in [0, 1, STREAM_SEGMENT_SIZE - 1, 2 * STREAM_SEGMENT_SIZE + 100] {
+                let plain = plaintext(len);
+                let sealed = stream.seal(&plain);
+                assert_eq!(stream.open(&sealed).unwrap(), plain);
+            }
+        }
+    }
+
+    #[test]
+    fn stream_round_trips_whole_segments() {
+        let stream = Stream::new(AeadAlgorithm::Aes256Gcm);
+        let plain = plaintext(2 * STREAM_SEGMENT_SIZE);
+        let sealed = stream.seal(&plain);
+        // Two full segments and an empty last one
+        assert_eq!(sealed.len(), 2 * SEALED + TAG_LEN);
+        assert_eq!(stream.open(&sealed).unwrap(), plain);
+    }
+
+    #[test]
+    fn stream_rejects_dropped_last_segment() {
+        let stream = Stream::new(AeadAlgorithm::Aes256Gcm);
+        let sealed = stream.seal(&plaintext(2 * STREAM_SEGMENT_SIZE + 100));
+        assert!(stream.open(&sealed[..2 * SEALED]).is_err());
+
+        // With whole segments the last one is just a tag
+        let sealed = stream.seal(&plaintext(2 * STREAM_SEGMENT_SIZE));
+        assert!(stream.open(&sealed[..2 * SEALED]).is_err());
+    }
+
+    #[test]
+    fn stream_rejects_cut_at_segment_boundary() {
+        let stream = Stream::new(AeadAlgorithm::Aes256Gcm);
+        let sealed = stream.seal(&plaintext(2 * STREAM_SEGMENT_SIZE + 100));
+        assert!(stream.open(&sealed[..SEALED]).is_err());
+        assert!(stream.open(&[]).is_err());
+    }
+
+    #[test]
+    fn stream_rejects_swapped_segments() {
+        let stream = Stream::new(AeadAlgorithm::Aes256Gcm);
+        let sealed = stream.seal(&plaintext(2 * STREAM_SEGMENT_SIZE + 100));
+        let mut swapped = sealed[SEALED..2 * SEALED].to_vec();
+        swapped.extend_from_slice(&sealed[..SEALED]);
+        swapped.extend_from_slice(&sealed[2 * SEALED..]);
+        assert!(stream.open(&swapped).is_err());
+    }
+
+    #[test]
+    fn stream_rejects_appended_segment() {
+        let stream = Stream::new(AeadAlgorithm::Aes256Gcm);
+        let sealed = stream.seal(&plaintext(STREAM_SEGMENT_SIZE + 100));
+        let mut appended = sealed.clone();
+        appended.extend_from_slice(&sealed[SEALED..]);
+        assert!(stream.open(&appended).is_err());
+
+        // A segment sealed under the next counter, after the empty last segment
+        let sealed = stream.seal(&plaintext(STREAM_SEGMENT_SIZE));
+        let mut appended = sealed.clone();
+        let extra = stream
+            .cipher
+            .encrypt(
+                &stream_nonce(&stream.prefix, 2, true),
+                b"more",
+                &stream.header,
+            )
+            .unwrap();
+        appended.extend_from_slice(&extra);
+        assert!(stream.open(&appended).is_err());
+    }
+
+    #[test]
+    fn stream_rejects_modified_header() {
+        let stream = Stream::new(AeadAlgorithm::Aes256Gcm);
+        let sealed = stream.seal(&plaintext(100));
+        for i in 0..stream.header.len() {
+            let mut tampered = stream.header.clone();
+            tampered[i] ^= 1;
+            assert!(stream.open_with_header(&tampered, &sealed).is_err());
+        }
+    }
+
+    /// A fresh directory for one test's files, removed again when dropped.
+    struct ScratchDir(std::path::PathBuf);
+
+    impl ScratchDir {
+        fn new(test: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("csk-{}-{}", std::process::id(), test));
+            fs::create_dir_all(&dir).unwrap();
+            ScratchDir(dir)
+        }
+
+        fn path(&self, name: &str) -> String {
+            self.0.join(name).to_str().unwrap().to_string()
+        }
+    }
+
+    impl Drop for ScratchDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn key_file_round_trips_armored_and_binary() {
+        let dir = ScratchDir::new("key-file-round-trip");
+        let (input, output, decrypted, key) = (
+            dir.path("in"),
+            dir.path("out"),
+            dir.path("decrypted"),
+            dir.path("key"),
+        );
+        fs::write(&input, plaintext(1000)).unwrap();
+        for algorithm in AeadAlgorithm::ALL {
+            for armored in [false, true] {
+                encrypt_with_key_file(&input, &output, &key, algorithm, armored).unwrap();
+                assert_eq!(is_armored(&fs::read(&output).unwrap()), armored);
+                decrypt_with_key_file(&output, &decrypted, &key).unwrap();
+                assert_eq!(fs::read(&decrypted).unwrap(), plaintext(1000));
+            }
+        }
+    }
+
+    #[test]
+    fn decrypts_legacy_two_line_format() {
+        let dir = ScratchDir::new("legacy");
+        let (input, decrypted, key_file) = (dir.path("in"), dir.path("decrypted"), dir.path("key"));
+        // As written before the container format: base64 nonce, newline, base64
+        // AES-256-GCM ciphertext without associated data
+        let key = generate_key();
+        let nonce = [7u8; 12];
+        let ciphertext = Aes256Gcm::new(GenericArray::from_slice(&key))
+            .encrypt(GenericArray::from_slice(&nonce), b"attack at dawn".as_ref())
+            .unwrap();
+        let legacy = format!(
+            "{}\n{}",
+            general_purpose::STANDARD.encode(nonce),
+            general_purpose::STANDARD.encode(ciphertext)
+        );
+        fs::write(&input, legacy).unwrap();
+        fs::write(&key_file, general_purpose::STANDARD.encode(key)).unwrap();
+
+        decrypt_with_key_file(&input, &decrypted, &key_file).unwrap();
+        assert_eq!(fs::read(&decrypted).unwrap(), b"attack at dawn");
+    }
+
+    #[test]
+    fn rejects_modified_container_header() {
+        let dir = ScratchDir::new("modified-header");
+        let (input, output, decrypted, key) = (
+            dir.path("in"),
+            dir.path("out"),
+            dir.path("decrypted"),
+            dir.path("key"),
+        );
+        fs::write(&input, plaintext(100)).unwrap();
+        encrypt_with_key_file(&input, &output, &key, AeadAlgorithm::Aes256Gcm, false).unwrap();
+        let sealed = fs::read(&output).unwrap();
+        let (_, header_bytes) = Header::read_from(&mut sealed.as_slice()).unwrap();
+        for i in 0..header_bytes.len() {
+            let mut tampered = sealed.clone();
+            tampered[i] ^= 1;
+            fs::write(&output, &tampered).unwrap();
+            assert!(decrypt_with_key_file(&output, &decrypted, &key).is_err());
+        }
+    }
+
+    #[test]
+    fn passphrase_round_trips_with_each_kdf() {
+        let dir = ScratchDir::new("passphrase-round-trip");
+        let (input, output, decrypted) = (dir.path("in"), dir.path("out"), dir.path("decrypted"));
+        fs::write(&input, plaintext(1000)).unwrap();
+        // Cheap costs; the defaults take seconds in a debug build
+        let salt = vec![3u8; 16];
+        let kdfs = [
+            Kdf::Argon2id {
+                salt: salt.clone(),
+                memory_kib: 64,
+                iterations: 1,
+                parallelism: 1,
+            },
+            Kdf::Scrypt {
+                salt: salt.clone(),
+                log_n: 4,
+                r: 8,
+                p: 1,
+            },
+            Kdf::Pbkdf2Sha256 {
+                salt,
+                iterations: 1000,
+            },
+        ];
+        for kdf in kdfs {
+            let key = derive_key(&kdf, "hunter2").unwrap();
+            seal_file(&input, &output, &key, AeadAlgorithm::Aes256Gcm, kdf, false).unwrap();
+            decrypt_with_passphrase(&output, &decrypted, "hunter2").unwrap();
+            assert_eq!(fs::read(&decrypted).unwrap(), plaintext(1000));
+            assert!(decrypt_with_passphrase(&output, &decrypted, "hunter3").is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_kdf_costs_over_the_cap() {
+        let dir = ScratchDir::new("kdf-cap");
+        let (input, output, decrypted) = (dir.path("in"), dir.path("out"), dir.path("decrypted"));
+        fs::write(&input, plaintext(100)).unwrap();
+        let kdf = Kdf::Argon2id {
+            salt: vec![3u8; 16],
+            memory_kib: u32::MAX,
+            iterations: u32::MAX,
+            parallelism: 1,
+        };
+        seal_file(
+            &input,
+            &output,
+            &generate_key(),
+            AeadAlgorithm::Aes256Gcm,
+            kdf,
+            false,
+        )
+        .unwrap();
+        let err = decrypt_with_passphrase(&output, &decrypted, "hunter2").unwrap_err();
+        assert!(err.to_string().contains("unreasonably high"), "{}", err);
+    }
+}
@@ -0,0 +1,487 @@
+//! Educational block-cipher modes of operation (ECB, CBC, CFB, OFB, CTR) over raw
+//! AES-128/192/256, with PKCS#7, ISO/IEC 7816-4 or zero padding.
+//!
+//! None of these modes authenticate anything; they are here to show how each one
+//! chains blocks, and why ECB leaks patterns. Real files go through `aead.rs`.
+//! Every function also returns a per-block trace for the UI.
+
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use aes::{Aes128, Aes192, Aes256};
+use anyhow::{Result, bail};
+
+pub const BLOCK_LEN: usize = 16;
+
+pub type Block = [u8; BLOCK_LEN];
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockMode {
+    #[default]
+    Ecb,
+    Cbc,
+    Cfb,
+    Ofb,
+    Ctr,
+}
+
+impl BlockMode {
+    pub const ALL: [BlockMode; 5] = [
+        BlockMode::Ecb,
+        BlockMode::Cbc,
+        BlockMode::Cfb,
+        BlockMode::Ofb,
+        BlockMode::Ctr,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BlockMode::Ecb => "ECB",
+            BlockMode::Cbc => "CBC",
+            BlockMode::Cfb => "CFB",
+            BlockMode::Ofb => "OFB",
+            BlockMode::Ctr => "CTR",
+        }
+    }
+
+    pub fn encrypt_formula(self) -> &'static str {
+        match self {
+            BlockMode::Ecb => "C_i = E_K(P_i)",
+            BlockMode::Cbc => "C_i = E_K(P_i xor C_i-1), C_0 = IV",
+            BlockMode::Cfb => "C_i = P_i xor E_K(C_i-1), C_0 = IV",
+            BlockMode::Ofb => "O_i = E_K(O_i-1), O_0 = IV, C_i = P_i xor O_i",
+            BlockMode::Ctr => "C_i = P_i xor E_K(IV + i)",
+        }
+    }
+
+    pub fn decrypt_formula(self) -> &'static str {
+        match self {
+            BlockMode::Ecb => "P_i = D_K(C_i)",
+            BlockMode::Cbc => "P_i = D_K(C_i) xor C_i-1, C_0 = IV",
+            BlockMode::Cfb => "P_i = C_i xor E_K(C_i-1), C_0 = IV",
+            BlockMode::Ofb => "O_i = E_K(O_i-1), O_0 = IV, P_i = C_i xor O_i",
+            BlockMode::Ctr => "P_i = C_i xor E_K(IV + i)",
+        }
+    }
+
+    /// ECB and CBC feed whole blocks through the cipher (and its inverse when
+    /// decrypting); the other three turn it into a keystream and take any length.
+    pub fn needs_padding(self) -> bool {
+        matches!(self, BlockMode::Ecb | BlockMode::Cbc)
+    }
+
+    pub fn needs_iv(self) -> bool {
+        self != BlockMode::Ecb
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Padding {
+    #[default]
+    Pkcs7,
+    Iso7816,
+    Zero,
+}
+
+impl Padding {
+    pub const ALL: [Padding; 3] = [Padding::Pkcs7, Padding::Iso7816, Padding::Zero];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Padding::Pkcs7 => "PKCS#7",
+            Padding::Iso7816 => "ISO/IEC 7816-4",
+            Padding::Zero => "Zero",
+        }
+    }
+
+    /// Pads to a whole number of blocks. PKCS#7 and ISO 7816-4 always add at least
+    /// one byte; zero padding adds nothing to input that is already aligned, but
+    /// turns empty input into one block of zeros so there is something to decrypt.
+    pub fn pad(self, data: &[u8]) -> Vec<u8> {
+        let mut padded = data.to_vec();
+        let fill = BLOCK_LEN - data.len() % BLOCK_LEN;
+        match self {
+            Padding::Pkcs7 => padded.resize(data.len() + fill, fill as u8),
+            Padding::Iso7816 => {
+                padded.push(0x80);
+                padded.resize(data.len() + fill, 0);
+            }
+            Padding::Zero => {
+                if fill != BLOCK_LEN || data.is_empty() {
+                    padded.resize(data.len() + fill, 0);
+                }
+            }
+        }
+        padded
+    }
+
+    /// Removes the padding. Zero padding cannot tell its own zeros from trailing
+    /// zeros in the message, so both are stripped.
+    pub fn unpad(self, data: &[u8]) -> Result<Vec<u8>> {
+        if data.is_empty() || !data.len().is_multiple_of(BLOCK_LEN) {
+            bail!(
+                "Padded data must be a non-empty multiple of {} bytes",
+                BLOCK_LEN
+            );
+        }
+        let len = match self {
+            Padding::Pkcs7 => {
+                let fill = data[data.len() - 1] as usize;
+                if fill == 0
+                    || fill > BLOCK_LEN
+                    || data[data.len() - fill..]
+                        .iter()
+                        .any(|&b| b as usize != fill)
+                {
+                    bail!("Invalid PKCS#7 padding");
+                }
+                data.len() - fill
+            }
+            Padding::Iso7816 => {
+                let marker = data
+                    .iter()
+                    .rposition(|&b| b != 0)
+                    .filter(|&i| data[i] == 0x80 && data.len() - i <= BLOCK_LEN);
+                match marker {
+                    Some(i) => i,
+                    None => bail!("Invalid ISO/IEC 7816-4 padding"),
+                }
+            }
+            Padding::Zero => data.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1),
+        };
+        Ok(data[..len].to_vec())
+    }
+}
+
+/// Raw AES with the key size picked from the key length.
+enum BlockCipher {
+    Aes128(Box<Aes128>),
+    Aes192(Box<Aes192>),
+    Aes256(Box<Aes256>),
+}
+
+impl BlockCipher {
+    fn new(key: &[u8]) -> Result<Self> {
+        Ok(match key.len() {
+            16 => BlockCipher::Aes128(Box::new(Aes128::new(GenericArray::from_slice(key)))),
+            24 => BlockCipher::Aes192(Box::new(Aes192::new(GenericArray::from_slice(key)))),
+            32 => BlockCipher::Aes256(Box::new(Aes256::new(GenericArray::from_slice(key)))),
+            n => bail!("AES key must be 16, 24 or 32 bytes, got {}", n),
+        })
+    }
+
+    fn encrypt(&self, block: &Block) -> Block {
+        let mut out = GenericArray::clone_from_slice(block);
+        match self {
+            BlockCipher::Aes128(c) => c.encrypt_block(&mut out),
+            BlockCipher::Aes192(c) => c.encrypt_block(&mut out),
+            BlockCipher::Aes256(c) => c.encrypt_block(&mut out),
+        }
+        out.into()
+    }
+
+    fn decrypt(&self, block: &Block) -> Block {
+        let mut out = GenericArray::clone_from_slice(block);
+        match self {
+            BlockCipher::Aes128(c) => c.decrypt_block(&mut out),
+            BlockCipher::Aes192(c) => c.decrypt_block(&mut out),
+            BlockCipher::Aes256(c) => c.decrypt_block(&mut out),
+        }
+        out.into()
+    }
+}
+
+/// One block of the operation, in the order the data flows.
+#[derive(Clone, Debug)]
+pub struct BlockStep {
+    /// Plaintext block when encrypting, ciphertext block when decrypting
+    pub input: Vec<u8>,
+    /// What went into AES: the block itself, the chained value, or the counter
+    pub cipher_input: Block,
+    pub cipher_output: Block,
+    pub output: Vec<u8>,
+}
+
+pub struct ModeOutput {
+    pub data: Vec<u8>,
+    pub steps: Vec<BlockStep>,
+}
+
+fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+fn to_block(bytes: &[u8]) -> Block {
+    let mut block = [0u8; BLOCK_LEN];
+    block.copy_from_slice(bytes);
+    block
+}
+
+fn check_iv(mode: BlockMode, iv: &[u8]) -> Result<Block> {
+    if !mode.needs_iv() {
+        return Ok([0u8; BLOCK_LEN]);
+    }
+    if iv.len() != BLOCK_LEN {
+        bail!(
+            "{} needs a {}-byte IV, got {}",
+            mode.name(),
+            BLOCK_LEN,
+            iv.len()
+        );
+    }
+    Ok(to_block(iv))
+}
+
+/// The `i`-th CTR counter block: the IV read as a 128-bit big-endian integer plus
+/// `i`, wrapping around.
+fn counter_block(iv: &Block, i: usize) -> Block {
+    u128::from_be_bytes(*iv)
+        .wrapping_add(i as u128)
+        .to_be_bytes()
+}
+
+/// Keystream modes are their own inverse apart from which side feeds CFB's register.
+fn keystream_mode(
+    mode: BlockMode,
+    cipher: &BlockCipher,
+    iv: Block,
+    input: &[u8],
+    decrypting: bool,
+) -> ModeOutput {
+    let mut data = Vec::with_capacity(input.len());
+    let mut steps = Vec::new();
+    let mut register = iv;
+    for (i, chunk) in input.chunks(BLOCK_LEN).enumerate() {
+        let cipher_input = match mode {
+            BlockMode::Ctr => counter_block(&iv, i),
+            _ => register,
+        };
+        let cipher_output = cipher.encrypt(&cipher_input);
+        let output = xor(chunk, &cipher_output);
+        register = match mode {
+            BlockMode::Ofb => cipher_output,
+            // Only the last chunk can be short, and nothing chains from it
+            BlockMode::Cfb if chunk.len() == BLOCK_LEN => {
+                to_block(if decrypting { chunk } else { &output })
+            }
+            _ => register,
+        };
+        data.extend_from_slice(&output);
+        steps.push(BlockStep {
+            input: chunk.to_vec(),
+            cipher_input,
+            cipher_output,
+            output,
+        });
+    }
+    ModeOutput { data, steps }
+}
+
+pub fn encrypt_blocks(
+    mode: BlockMode,
+    padding: Padding,
+    key: &[u8],
+    iv: &[u8],
+    plaintext: &[u8],
+) -> Result<ModeOutput> {
+    let cipher = BlockCipher::new(key)?;
+    let iv = check_iv(mode, iv)?;
+    if !mode.needs_padding() {
+        return Ok(keystream_mode(mode, &cipher, iv, plaintext, false));
+    }
+
+    let padded = padding.pad(plaintext);
+    let mut data = Vec::with_capacity(padded.len());
+    let mut steps = Vec::new();
+    let mut previous = iv;
+    for chunk in padded.chunks(BLOCK_LEN) {
+        let cipher_input = match mode {
+            BlockMode::Cbc => to_block(&xor(chunk, &previous)),
+            _ => to_block(chunk),
+        };
+        let cipher_output = cipher.encrypt(&cipher_input);
+        previous = cipher_output;
+        data.extend_from_slice(&cipher_output);
+        steps.push(BlockStep {
+            input: chunk.to_vec(),
+            cipher_input,
+            cipher_output,
+            output: cipher_output.to_vec(),
+        });
+    }
+    Ok(ModeOutput { data, steps })
+}
+
+pub fn decrypt_blocks(
+    mode: BlockMode,
+    padding: Padding,
+    key: &[u8],
+    iv: &[u8],
+    ciphertext: &[u8],
+) -> Result<ModeOutput> {
+    let cipher = BlockCipher::new(key)?;
+    let iv = check_iv(mode, iv)?;
+    if !mode.needs_padding() {
+        return Ok(keystream_mode(mode, &cipher, iv, ciphertext, true));
+    }
+    if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(BLOCK_LEN) {
+        bail!(
+            "{} ciphertext must be a non-empty multiple of {} bytes",
+            mode.name(),
+            BLOCK_LEN
+        );
+    }
+
+    let mut padded = Vec::with_capacity(ciphertext.len());
+    let mut steps = Vec::new();
+    let mut previous = iv;
+    for chunk in ciphertext.chunks(BLOCK_LEN) {
+        let cipher_input = to_block(chunk);
+        let cipher_output = cipher.decrypt(&cipher_input);
+        let output = match mode {
+            BlockMode::Cbc => xor(&cipher_output, &previous),
+            _ => cipher_output.to_vec(),
+        };
+        previous = cipher_input;
+        padded.extend_from_slice(&output);
+        steps.push(BlockStep {
+            input: chunk.to_vec(),
+            cipher_input,
+            cipher_output,
+            output,
+        });
+    }
+    Ok(ModeOutput {
+        data: padding.unpad(&padded)?,
+        steps,
+    })
+}
+
+/// Encrypts image pixels in place of the originals, cut back to their length so the
+/// result can be drawn with the same dimensions. Only the picture matters here, so
+/// the (zero) padding is thrown away with the rest of the last block.
+pub fn encrypt_pixels(mode: BlockMode, key: &[u8], iv: &[u8], pixels: &[u8]) -> Result<Vec<u8>> {
+    let mut data = encrypt_blocks(mode, Padding::Zero, key, iv, pixels)?.data;
+    data.truncate(pixels.len());
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NIST SP 800-38A, appendix F: AES-128 with the same key and plaintext throughout
+    const KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+    const PLAINTEXT: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+                             30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+    const IV: &str = "000102030405060708090a0b0c0d0e0f";
+    const CTR_IV: &str = "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+
+    fn check_nist_vector(mode: BlockMode, iv: &str, ciphertext: &str) {
+        let key = hex::decode(KEY).unwrap();
+        let iv = hex::decode(iv).unwrap();
+        let plaintext = hex::decode(PLAINTEXT).unwrap();
+        // The plaintext is whole blocks, so zero padding adds nothing
+        let encrypted = encrypt_blocks(mode, Padding::Zero, &key, &iv, &plaintext).unwrap();
+        assert_eq!(hex::encode(&encrypted.data), ciphertext, "{}", mode.name());
+        let decrypted = decrypt_blocks(mode, Padding::Zero, &key, &iv, &encrypted.data).unwrap();
+        assert_eq!(decrypted.data, plaintext, "{}", mode.name());
+    }
+
+    #[test]
+    fn ecb_matches_nist() {
+        check_nist_vector(
+            BlockMode::Ecb,
+            "",
+            "3ad77bb40d7a3660a89ecaf32466ef97f5d3d58503b9699de785895a96fdbaaf\
+             43b1cd7f598ece23881b00e3ed0306887b0c785e27e8ad3f8223207104725dd4",
+        );
+    }
+
+    #[test]
+    fn cbc_matches_nist() {
+        check_nist_vector(
+            BlockMode::Cbc,
+            IV,
+            "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2\
+             73bed6b8e3c1743b7116e69e222295163ff1caa1681fac09120eca307586e1a7",
+        );
+    }
+
+    #[test]
+    fn cfb_matches_nist() {
+        check_nist_vector(
+            BlockMode::Cfb,
+            IV,
+            "3b3fd92eb72dad20333449f8e83cfb4ac8a64537a0b3a93fcde3cdad9f1ce58b\
+             26751f67a3cbb140b1808cf187a4f4dfc04b05357c5d1c0eeac4c66f9ff7f2e6",
+        );
+    }
+
+    #[test]
+    fn ofb_matches_nist() {
+        check_nist_vector(
+            BlockMode::Ofb,
+            IV,
+            "3b3fd92eb72dad20333449f8e83cfb4a7789508d16918f03f53c52dac54ed825\
+             9740051e9c5fecf64344f7a82260edcc304c6528f659c77866a510d9c1d6ae5e",
+        );
+    }
+
+    #[test]
+    fn ctr_matches_nist() {
+        check_nist_vector(
+            BlockMode::Ctr,
+            CTR_IV,
+            "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff\
+             5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee",
+        );
+    }
+
+    #[test]
+    fn padding_round_trips() {
+        for padding in Padding::ALL {
+            for len in 0..=2 * BLOCK_LEN {
+                // Ends in a non-zero byte so zero padding can be told apart
+                let data: Vec<u8> = (1..=len as u8).collect();
+                let padded = padding.pad(&data);
+                assert!(!padded.is_empty(), "{} {}", padding.name(), len);
+                assert!(padded.len().is_multiple_of(BLOCK_LEN));
+                assert_eq!(
+                    padding.unpad(&padded).unwrap(),
+                    data,
+                    "{} {}",
+                    padding.name(),
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_bad_padding() {
+        let mut block = [0u8; BLOCK_LEN];
+        assert!(Padding::Pkcs7.unpad(&block).is_err());
+        assert!(Padding::Iso7816.unpad(&block).is_err());
+        block[BLOCK_LEN - 1] = 17;
+        assert!(Padding::Pkcs7.unpad(&block).is_err());
+        assert!(Padding::Zero.unpad(&block[..5]).is_err());
+    }
+
+    #[test]
+    fn empty_message_round_trips_in_every_mode() {
+        let key = hex::decode(KEY).unwrap();
+        let iv = hex::decode(IV).unwrap();
+        for mode in BlockMode::ALL {
+            for padding in Padding::ALL {
+                let encrypted = encrypt_blocks(mode, padding, &key, &iv, b"").unwrap();
+                let decrypted = decrypt_blocks(mode, padding, &key, &iv, &encrypted.data).unwrap();
+                assert!(
+                    decrypted.data.is_empty(),
+                    "{} {}",
+                    mode.name(),
+                    padding.name()
+                );
+            }
+        }
+    }
+}
@@ -1,5 +1,10 @@
+use crate::algorithms::fitness::LanguageModel;
+use crate::algorithms::math::{
+    determinant_mod, euclid_algo, extended_euclid_algo, matrix_inverse_mod, rank_mod_prime,
+};
+use crate::algorithms::transposition::{columnar_decrypt, columnar_encrypt};
+use anyhow::{Result, anyhow, bail};
 use nalgebra::{DMatrix, DVector};
-use playfair_cipher::{cryptable::Cypher, playfair};
 
 fn char_to_num(c: char) -> i32 {
     c as i32 - 'A' as i32
@@ -10,7 +15,7 @@ fn num_to_char(n: i32) -> char {
 }
 
 fn mod26(n: i32) -> i32 {
-    ((n % 26) + 26) % 26
+    n.rem_euclid(26)
 }
 
 pub fn caesar_cipher_encrypt(plaintext: String, key: i32) -> String {
@@ -93,20 +98,6 @@ pub fn vigenere_cipher_decrypt(ciphertext: String, key: String) -> String {
         .collect()
 }
 
-pub fn playfair_encrypt(plaintext: String, key: String) -> String {
-    let playfair_key = playfair::PlayFairKey::new(key.as_str());
-    playfair_key
-        .encrypt(plaintext.as_str())
-        .unwrap_or_else(|_| String::from("Something went wrong"))
-}
-
-pub fn playfair_decrypt(ciphertext: String, key: String) -> String {
-    let playfair_key = playfair::PlayFairKey::new(key.as_str());
-    playfair_key
-        .decrypt(ciphertext.as_str())
-        .unwrap_or_else(|_| String::from("Something went wrong"))
-}
-
 pub fn hill_cipher_encrypt(plaintext: String, key: DMatrix<i32>) -> Option<String> {
     let size = key.ncols();
 
@@ -146,3 +137,1777 @@ pub fn hill_cipher_encrypt(plaintext: String, key: DMatrix<i32>) -> Option<Strin
 
     Some(ciphertext)
 }
+
+pub fn hill_cipher_decrypt(ciphertext: String, key: DMatrix<i32>) -> Result<String> {
+    let size = key.ncols();
+
+    if key.nrows() != size {
+        bail!(
+            "Hill key must be a square matrix, got {}x{}",
+            key.nrows(),
+            size
+        );
+    }
+
+    let det = determinant_mod(&key, 26);
+    if euclid_algo(det, 26) != 1 {
+        bail!(
+            "Key matrix is not invertible mod 26: determinant {} shares a factor with 26",
+            det
+        );
+    }
+
+    let inverse = matrix_inverse_mod(&key, 26)
+        .ok_or_else(|| anyhow!("Key matrix is not invertible mod 26"))?;
+
+    hill_cipher_encrypt(ciphertext, inverse).ok_or_else(|| anyhow!("Hill decryption failed"))
+}
+
+/// Parses a Hill key typed either as numbers (e.g. `3 3; 2 5` or `3,3,2,5`) or as a keyword
+/// whose letters fill the matrix row by row. The number of entries must be a perfect square.
+pub fn parse_hill_key(key: &str) -> Result<DMatrix<i32>> {
+    let values: Vec<i32> = if key.chars().any(|c| c.is_ascii_digit()) {
+        key.split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse::<i32>()
+                    .map(|v| v.rem_euclid(26))
+                    .map_err(|_| anyhow!("Invalid number in key matrix: {}", s))
+            })
+            .collect::<Result<_>>()?
+    } else {
+        key.chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| char_to_num(c.to_ascii_uppercase()))
+            .collect()
+    };
+
+    if values.is_empty() {
+        bail!("Key is empty");
+    }
+
+    let size = (values.len() as f64).sqrt().round() as usize;
+    if size * size != values.len() {
+        bail!(
+            "Key has {} entries, which does not fill an n x n matrix",
+            values.len()
+        );
+    }
+
+    Ok(DMatrix::from_row_slice(size, size, &values))
+}
+
+/// Advances `chosen` to the next combination of distinct indices below `n`, in
+/// lexicographic order. Returns false after the last one.
+fn next_combination(chosen: &mut [usize], n: usize) -> bool {
+    let k = chosen.len();
+    for i in (0..k).rev() {
+        if chosen[i] < n - k + i {
+            chosen[i] += 1;
+            for j in i + 1..k {
+                chosen[j] = chosen[j - 1] + 1;
+            }
+            return true;
+        }
+    }
+    false
+}
+
+/// Block combinations tried by the Hill known-plaintext attack before giving up.
+const MAX_HILL_COMBINATIONS: usize = 20_000;
+
+/// Known-plaintext attack on the Hill cipher: recovers a `size` x `size` key from the
+/// start of a message and the matching ciphertext.
+///
+/// With plaintext blocks as the columns of P and ciphertext blocks as the columns of C,
+/// the key is C * P^-1 mod 26. Block combinations are tried until P is invertible, and
+/// the key must then explain every other block too.
+pub fn hill_known_plaintext_attack(
+    plaintext: String,
+    ciphertext: String,
+    size: usize,
+) -> Result<DMatrix<i32>> {
+    let (plain, cipher) = (key_numbers(&plaintext), key_numbers(&ciphertext));
+    if size == 0 {
+        bail!("Key size must be at least 1");
+    }
+    let blocks = plain.len().min(cipher.len()) / size;
+    if blocks < size {
+        bail!(
+            "Need at least {} letters of matching plaintext and ciphertext for a {}x{} key",
+            size * size,
+            size,
+            size
+        );
+    }
+
+    let block_matrix = |text: &[i32], chosen: &[usize]| {
+        DMatrix::from_fn(size, chosen.len(), |row, col| {
+            text[chosen[col] * size + row]
+        })
+    };
+    // P is invertible mod 26 only if it is invertible mod 2 and mod 13, which no choice
+    // of blocks can achieve when all of them together fall short of full rank
+    let all: Vec<usize> = (0..blocks).collect();
+    let all_plain = block_matrix(&plain, &all);
+    if rank_mod_prime(&all_plain, 2) < size || rank_mod_prime(&all_plain, 13) < size {
+        bail!(
+            "No {} of the {} known blocks form a matrix invertible mod 26; more known plaintext is needed",
+            size,
+            blocks
+        );
+    }
+
+    let mut chosen: Vec<usize> = (0..size).collect();
+    for _ in 0..MAX_HILL_COMBINATIONS {
+        if let Some(plain_inverse) = matrix_inverse_mod(&block_matrix(&plain, &chosen), 26) {
+            let key = (block_matrix(&cipher, &chosen) * plain_inverse).map(|v| v.rem_euclid(26));
+            let predicted = (&key * &all_plain).map(|v| v.rem_euclid(26));
+            if predicted != block_matrix(&cipher, &all) {
+                bail!(
+                    "No {}x{} key maps this plaintext to this ciphertext; check the key size and that both texts start at the same block",
+                    size,
+                    size
+                );
+            }
+            return Ok(key);
+        }
+        if !next_combination(&mut chosen, blocks) {
+            bail!(
+                "No {} of the {} known blocks form a matrix invertible mod 26; more known plaintext is needed",
+                size,
+                blocks
+            );
+        }
+    }
+    bail!(
+        "No invertible block combination among the first {} tried; different known plaintext is needed",
+        MAX_HILL_COMBINATIONS
+    )
+}
+
+fn check_affine_key(a: i32) -> Result<i32> {
+    extended_euclid_algo(a.rem_euclid(26), 26)
+        .ok_or_else(|| anyhow!("Invalid affine key: a = {} is not coprime with 26", a))
+}
+
+pub fn affine_encrypt(plaintext: String, a: i32, b: i32) -> Result<String> {
+    check_affine_key(a)?;
+    let (a, b) = (a.rem_euclid(26), b.rem_euclid(26));
+
+    Ok(plaintext
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphabetic() {
+                let new_char = num_to_char(a * char_to_num(c.to_ascii_uppercase()) + b);
+                if c.is_ascii_uppercase() {
+                    new_char
+                } else {
+                    new_char.to_ascii_lowercase()
+                }
+            } else {
+                c
+            }
+        })
+        .collect())
+}
+
+pub fn affine_decrypt(ciphertext: String, a: i32, b: i32) -> Result<String> {
+    let a_inv = check_affine_key(a)?;
+    let b = b.rem_euclid(26);
+
+    Ok(ciphertext
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphabetic() {
+                let new_char = num_to_char(a_inv * (char_to_num(c.to_ascii_uppercase()) - b));
+                if c.is_ascii_uppercase() {
+                    new_char
+                } else {
+                    new_char.to_ascii_lowercase()
+                }
+            } else {
+                c
+            }
+        })
+        .collect())
+}
+
+pub struct AffineCandidate {
+    pub a: i32,
+    pub b: i32,
+    pub plaintext: String,
+    pub score: f64,
+}
+
+/// Decrypts with all 312 valid affine keys and ranks the results by chi-squared
+/// against the letter frequencies of `model`, best first.
+pub fn affine_brute_force(ciphertext: &str, model: &LanguageModel) -> Vec<AffineCandidate> {
+    let mut candidates: Vec<AffineCandidate> = (1..26)
+        .filter(|&a| euclid_algo(a, 26) == 1)
+        .flat_map(|a| (0..26).map(move |b| (a, b)))
+        .filter_map(|(a, b)| {
+            let plaintext = affine_decrypt(ciphertext.to_string(), a, b).ok()?;
+            let score = model.chi_squared(&plaintext);
+            Some(AffineCandidate {
+                a,
+                b,
+                plaintext,
+                score,
+            })
+        })
+        .collect();
+
+    candidates.sort_by(|x, y| x.score.total_cmp(&y.score));
+    candidates
+}
+
+/// Alphabet for 5x5 squares: J is merged into I.
+pub const POLYBIUS_ALPHABET_5X5: &str = "ABCDEFGHIKLMNOPQRSTUVWXYZ";
+pub const POLYBIUS_ALPHABET_6X6: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+/// Alphabet for the Trifid 3x3x3 cube, with `.` as the 27th symbol.
+pub const TRIFID_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ.";
+
+/// A keyword-mixed square (or cube, for Trifid) of symbols. The keyword's distinct
+/// symbols come first, followed by the rest of the alphabet in order.
+#[derive(Clone, Debug)]
+pub struct PolybiusSquare {
+    pub size: usize,
+    pub cells: Vec<char>,
+}
+
+impl PolybiusSquare {
+    pub fn new(keyword: &str, alphabet: &str, size: usize) -> Self {
+        Self {
+            size,
+            cells: keyed_symbols(keyword, alphabet),
+        }
+    }
+
+    /// 5x5 square with J merged into I.
+    pub fn square_5x5(keyword: &str) -> Self {
+        Self::new(keyword, POLYBIUS_ALPHABET_5X5, 5)
+    }
+
+    /// 6x6 square of letters and digits, as used by ADFGVX.
+    pub fn square_6x6(keyword: &str) -> Self {
+        Self::new(keyword, POLYBIUS_ALPHABET_6X6, 6)
+    }
+
+    /// Index of `c` in the square, after folding it into the square's alphabet.
+    pub fn position(&self, c: char) -> Option<usize> {
+        let alphabet: String = self.cells.iter().collect();
+        let c = fold_into_alphabet(c, &alphabet)?;
+        self.cells.iter().position(|&x| x == c)
+    }
+
+    /// Zero-based (row, column) of `c`.
+    pub fn coords(&self, c: char) -> Option<(usize, usize)> {
+        self.position(c).map(|i| (i / self.size, i % self.size))
+    }
+
+    pub fn at(&self, row: usize, col: usize) -> Option<char> {
+        if row >= self.size || col >= self.size {
+            return None;
+        }
+        self.cells.get(row * self.size + col).copied()
+    }
+
+    pub fn rows(&self) -> Vec<Vec<char>> {
+        self.cells.chunks(self.size).map(|r| r.to_vec()).collect()
+    }
+}
+
+/// Maps `c` onto a symbol of `alphabet`: upper-cases letters and merges J into I
+/// (or I into J) when the alphabet only has one of the two.
+pub fn fold_into_alphabet(c: char, alphabet: &str) -> Option<char> {
+    let c = c.to_ascii_uppercase();
+    if alphabet.contains(c) {
+        Some(c)
+    } else if c == 'J' && alphabet.contains('I') {
+        Some('I')
+    } else if c == 'I' && alphabet.contains('J') {
+        Some('J')
+    } else {
+        None
+    }
+}
+
+/// The keyword's distinct symbols, then the rest of `alphabet` in order. Symbols
+/// outside the alphabet are folded into it or dropped.
+fn keyed_symbols(keyword: &str, alphabet: &str) -> Vec<char> {
+    let mut symbols: Vec<char> = Vec::with_capacity(alphabet.len());
+    let candidates = keyword.chars().chain(alphabet.chars());
+    for c in candidates.filter_map(|c| fold_into_alphabet(c, alphabet)) {
+        if !symbols.contains(&c) {
+            symbols.push(c);
+        }
+    }
+    symbols
+}
+
+fn adfgvx_labels(size: usize) -> &'static str {
+    if size == 6 { "ADFGVX" } else { "ADFGX" }
+}
+
+fn fractionate_encrypt(plaintext: &str, square: &PolybiusSquare, key: &str) -> Result<String> {
+    let labels: Vec<char> = adfgvx_labels(square.size).chars().collect();
+    let fractionated: String = plaintext
+        .chars()
+        .filter_map(|c| square.coords(c))
+        .flat_map(|(row, col)| [labels[row], labels[col]])
+        .collect();
+    columnar_encrypt(&fractionated, key)
+}
+
+fn fractionate_decrypt(ciphertext: &str, square: &PolybiusSquare, key: &str) -> Result<String> {
+    let labels = adfgvx_labels(square.size);
+    let symbols: String = ciphertext
+        .chars()
+        .map(|c| c.to_ascii_uppercase())
+        .filter(|&c| labels.contains(c))
+        .collect();
+    if !symbols.len().is_multiple_of(2) {
+        bail!("Ciphertext must have an even number of {} symbols", labels);
+    }
+
+    let fractionated: Vec<usize> = columnar_decrypt(&symbols, key)?
+        .chars()
+        .filter_map(|c| labels.find(c))
+        .collect();
+    fractionated
+        .chunks(2)
+        .map(|pair| {
+            square
+                .at(pair[0], pair[1])
+                .ok_or_else(|| anyhow!("Coordinates outside the square"))
+        })
+        .collect()
+}
+
+pub fn adfgx_encrypt(plaintext: String, square_key: String, key: String) -> Result<String> {
+    fractionate_encrypt(&plaintext, &PolybiusSquare::square_5x5(&square_key), &key)
+}
+
+pub fn adfgx_decrypt(ciphertext: String, square_key: String, key: String) -> Result<String> {
+    fractionate_decrypt(&ciphertext, &PolybiusSquare::square_5x5(&square_key), &key)
+}
+
+pub fn adfgvx_encrypt(plaintext: String, square_key: String, key: String) -> Result<String> {
+    fractionate_encrypt(&plaintext, &PolybiusSquare::square_6x6(&square_key), &key)
+}
+
+pub fn adfgvx_decrypt(ciphertext: String, square_key: String, key: String) -> Result<String> {
+    fractionate_decrypt(&ciphertext, &PolybiusSquare::square_6x6(&square_key), &key)
+}
+
+/// Splits `len` symbols into blocks of `period` (0 means one block for the whole message).
+fn period_blocks(len: usize, period: usize) -> Vec<std::ops::Range<usize>> {
+    let period = if period == 0 { len.max(1) } else { period };
+    (0..len)
+        .step_by(period)
+        .map(|start| start..(start + period).min(len))
+        .collect()
+}
+
+/// Shared Bifid/Trifid encryption: each symbol becomes `dims` digits in base `size`, the
+/// digits of a block are written out by dimension, then read back in groups of `dims`.
+fn fractionated_period_encrypt(
+    symbols: &[usize],
+    size: usize,
+    dims: u32,
+    period: usize,
+) -> Vec<usize> {
+    let digits =
+        |s: usize| -> Vec<usize> { (0..dims).rev().map(|d| (s / size.pow(d)) % size).collect() };
+
+    let mut out = Vec::with_capacity(symbols.len());
+    for block in period_blocks(symbols.len(), period) {
+        let coords: Vec<Vec<usize>> = symbols[block].iter().map(|&s| digits(s)).collect();
+        let stream: Vec<usize> = (0..dims as usize)
+            .flat_map(|d| coords.iter().map(move |c| c[d]))
+            .collect();
+        out.extend(
+            stream
+                .chunks(dims as usize)
+                .map(|group| group.iter().fold(0, |acc, &d| acc * size + d)),
+        );
+    }
+    out
+}
+
+fn fractionated_period_decrypt(
+    symbols: &[usize],
+    size: usize,
+    dims: u32,
+    period: usize,
+) -> Vec<usize> {
+    let mut out = Vec::with_capacity(symbols.len());
+    for block in period_blocks(symbols.len(), period) {
+        let n = block.len();
+        let stream: Vec<usize> = symbols[block]
+            .iter()
+            .flat_map(|&s| (0..dims).rev().map(move |d| (s / size.pow(d)) % size))
+            .collect();
+        out.extend(
+            (0..n).map(|i| (0..dims as usize).fold(0, |acc, d| acc * size + stream[d * n + i])),
+        );
+    }
+    out
+}
+
+fn bifid_process(text: &str, square_key: &str, period: usize, encrypt: bool) -> String {
+    let square = PolybiusSquare::square_5x5(square_key);
+    let symbols: Vec<usize> = text.chars().filter_map(|c| square.position(c)).collect();
+    let result = if encrypt {
+        fractionated_period_encrypt(&symbols, 5, 2, period)
+    } else {
+        fractionated_period_decrypt(&symbols, 5, 2, period)
+    };
+    result.into_iter().map(|i| square.cells[i]).collect()
+}
+
+/// Bifid with a 5x5 square. `period` 0 fractionates the whole message as one block.
+pub fn bifid_encrypt(plaintext: String, square_key: String, period: usize) -> String {
+    bifid_process(&plaintext, &square_key, period, true)
+}
+
+pub fn bifid_decrypt(ciphertext: String, square_key: String, period: usize) -> String {
+    bifid_process(&ciphertext, &square_key, period, false)
+}
+
+pub fn trifid_cube(keyword: &str) -> PolybiusSquare {
+    PolybiusSquare::new(keyword, TRIFID_ALPHABET, 3)
+}
+
+fn trifid_process(text: &str, cube_key: &str, period: usize, encrypt: bool) -> String {
+    let cube = trifid_cube(cube_key);
+    let symbols: Vec<usize> = text.chars().filter_map(|c| cube.position(c)).collect();
+    let result = if encrypt {
+        fractionated_period_encrypt(&symbols, 3, 3, period)
+    } else {
+        fractionated_period_decrypt(&symbols, 3, 3, period)
+    };
+    result.into_iter().map(|i| cube.cells[i]).collect()
+}
+
+/// Trifid with a keyword-mixed 3x3x3 cube of A-Z plus `.`. `period` 0 means one block.
+pub fn trifid_encrypt(plaintext: String, cube_key: String, period: usize) -> String {
+    trifid_process(&plaintext, &cube_key, period, true)
+}
+
+pub fn trifid_decrypt(ciphertext: String, cube_key: String, period: usize) -> String {
+    trifid_process(&ciphertext, &cube_key, period, false)
+}
+
+fn nihilist_number(square: &PolybiusSquare, c: char) -> Option<usize> {
+    square.coords(c).map(|(row, col)| (row + 1) * 10 + col + 1)
+}
+
+/// Nihilist cipher: letters of the message and of the key become two-digit Polybius
+/// coordinates (11-55), which are added together. Output is space-separated numbers.
+pub fn nihilist_encrypt(plaintext: String, square_key: String, key: String) -> Result<String> {
+    let square = PolybiusSquare::square_5x5(&square_key);
+    let key_numbers: Vec<usize> = key
+        .chars()
+        .filter_map(|c| nihilist_number(&square, c))
+        .collect();
+    if key_numbers.is_empty() {
+        bail!("Nihilist key must contain at least one letter");
+    }
+
+    Ok(plaintext
+        .chars()
+        .filter_map(|c| nihilist_number(&square, c))
+        .zip(key_numbers.iter().cycle())
+        .map(|(p, k)| (p + k).to_string())
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+pub fn nihilist_decrypt(ciphertext: String, square_key: String, key: String) -> Result<String> {
+    let square = PolybiusSquare::square_5x5(&square_key);
+    let key_numbers: Vec<usize> = key
+        .chars()
+        .filter_map(|c| nihilist_number(&square, c))
+        .collect();
+    if key_numbers.is_empty() {
+        bail!("Nihilist key must contain at least one letter");
+    }
+
+    ciphertext
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .zip(key_numbers.iter().cycle())
+        .map(|(number, k)| {
+            let value: usize = number
+                .parse()
+                .map_err(|_| anyhow!("Invalid number: {}", number))?;
+            let plain = value
+                .checked_sub(*k)
+                .ok_or_else(|| anyhow!("{} is smaller than the key value {}", value, k))?;
+            let (row, col) = (plain / 10, plain % 10);
+            if row == 0 || col == 0 {
+                bail!("{} does not decode to a square position", number);
+            }
+            square
+                .at(row - 1, col - 1)
+                .ok_or_else(|| anyhow!("{} does not decode to a square position", number))
+        })
+        .collect()
+}
+
+fn key_numbers(key: &str) -> Vec<i32> {
+    key.chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| char_to_num(c.to_ascii_uppercase()))
+        .collect()
+}
+
+fn letter_key(key: &str) -> Result<Vec<i32>> {
+    let numbers = key_numbers(key);
+    if numbers.is_empty() {
+        bail!("Key must contain at least one letter");
+    }
+    Ok(numbers)
+}
+
+/// Applies `f(index, letter)` to every letter of `text`, keeping case and passing
+/// everything else through. `index` counts letters only.
+fn map_letters(text: &str, mut f: impl FnMut(usize, i32) -> i32) -> String {
+    let mut index = 0;
+    text.chars()
+        .map(|c| {
+            if c.is_ascii_alphabetic() {
+                let new_char = num_to_char(f(index, char_to_num(c.to_ascii_uppercase())));
+                index += 1;
+                if c.is_ascii_uppercase() {
+                    new_char
+                } else {
+                    new_char.to_ascii_lowercase()
+                }
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Plaintext autokey: the key is followed by the plaintext itself.
+pub fn autokey_encrypt(plaintext: String, key: String) -> Result<String> {
+    let mut stream = letter_key(&key)?;
+    Ok(map_letters(&plaintext, |i, p| {
+        stream.push(p);
+        p + stream[i]
+    }))
+}
+
+pub fn autokey_decrypt(ciphertext: String, key: String) -> Result<String> {
+    let mut stream = letter_key(&key)?;
+    Ok(map_letters(&ciphertext, |i, c| {
+        let p = mod26(c - stream[i]);
+        stream.push(p);
+        p
+    }))
+}
+
+/// Ciphertext autokey: the key is followed by the ciphertext produced so far.
+pub fn ciphertext_autokey_encrypt(plaintext: String, key: String) -> Result<String> {
+    let mut stream = letter_key(&key)?;
+    Ok(map_letters(&plaintext, |i, p| {
+        let c = mod26(p + stream[i]);
+        stream.push(c);
+        c
+    }))
+}
+
+pub fn ciphertext_autokey_decrypt(ciphertext: String, key: String) -> Result<String> {
+    let mut stream = letter_key(&key)?;
+    Ok(map_letters(&ciphertext, |i, c| {
+        stream.push(c);
+        c - stream[i]
+    }))
+}
+
+/// Beaufort: `C = K - P`. It is its own inverse, so this also decrypts.
+pub fn beaufort_cipher(text: String, key: String) -> Result<String> {
+    let key = letter_key(&key)?;
+    Ok(map_letters(&text, |i, p| key[i % key.len()] - p))
+}
+
+/// Variant Beaufort: `C = P - K`, i.e. Vigenère decryption used for encryption.
+pub fn variant_beaufort_encrypt(plaintext: String, key: String) -> Result<String> {
+    let key = letter_key(&key)?;
+    Ok(map_letters(&plaintext, |i, p| p - key[i % key.len()]))
+}
+
+pub fn variant_beaufort_decrypt(ciphertext: String, key: String) -> Result<String> {
+    let key = letter_key(&key)?;
+    Ok(map_letters(&ciphertext, |i, c| c + key[i % key.len()]))
+}
+
+fn digit_key(key: &str) -> Result<Vec<i32>> {
+    let digits: Vec<i32> = key
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| d as i32)
+        .collect();
+    if digits.is_empty() {
+        bail!("Gronsfeld key must contain at least one digit");
+    }
+    Ok(digits)
+}
+
+/// Gronsfeld: Vigenère with a numeric key, each digit being a shift of 0-9.
+pub fn gronsfeld_encrypt(plaintext: String, key: String) -> Result<String> {
+    let key = digit_key(&key)?;
+    Ok(map_letters(&plaintext, |i, p| p + key[i % key.len()]))
+}
+
+pub fn gronsfeld_decrypt(ciphertext: String, key: String) -> Result<String> {
+    let key = digit_key(&key)?;
+    Ok(map_letters(&ciphertext, |i, c| c - key[i % key.len()]))
+}
+
+/// Porta: each key letter pair (AB, CD, ...) selects one of 13 reciprocal alphabets
+/// swapping the two halves of the alphabet. Encryption and decryption are the same.
+pub fn porta_cipher(text: String, key: String) -> Result<String> {
+    let key = letter_key(&key)?;
+    Ok(map_letters(&text, |i, p| {
+        let shift = key[i % key.len()] / 2;
+        if p < 13 {
+            13 + (p + shift).rem_euclid(13)
+        } else {
+            (p - 13 - shift).rem_euclid(13)
+        }
+    }))
+}
+
+/// Reads a book-text file to use as a running key, keeping only its letters.
+pub fn load_running_key(path: &str) -> Result<String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Could not read key text {}: {}", path, e))?;
+    Ok(text.chars().filter(|c| c.is_ascii_alphabetic()).collect())
+}
+
+fn running_key(key_text: &str, text: &str) -> Result<Vec<i32>> {
+    let key = key_numbers(key_text);
+    let needed = text.chars().filter(|c| c.is_ascii_alphabetic()).count();
+    if key.len() < needed {
+        bail!(
+            "Running key has {} letters but the message needs {}",
+            key.len(),
+            needed
+        );
+    }
+    Ok(key)
+}
+
+/// Running-key cipher: Vigenère with a key text at least as long as the message.
+pub fn running_key_encrypt(plaintext: String, key_text: String) -> Result<String> {
+    let key = running_key(&key_text, &plaintext)?;
+    Ok(map_letters(&plaintext, |i, p| p + key[i]))
+}
+
+pub fn running_key_decrypt(ciphertext: String, key_text: String) -> Result<String> {
+    let key = running_key(&key_text, &ciphertext)?;
+    Ok(map_letters(&ciphertext, |i, c| c - key[i]))
+}
+
+pub const PLAIN_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// Atbash: the alphabet reversed, A <-> Z, B <-> Y and so on.
+pub const ATBASH_ALPHABET: &str = "ZYXWVUTSRQPONMLKJIHGFEDCBA";
+
+/// Keyword-mixed alphabet: the keyword's distinct letters, then the rest of A-Z in order.
+pub fn keyword_alphabet(keyword: &str) -> String {
+    keyed_symbols(keyword, PLAIN_ALPHABET).into_iter().collect()
+}
+
+/// Checks that `alphabet` is a permutation of A-Z (whitespace is ignored) and returns
+/// the cipher letter for each plaintext letter.
+pub fn check_substitution_alphabet(alphabet: &str) -> Result<Vec<i32>> {
+    let letters: Vec<char> = alphabet
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    if let Some(c) = letters.iter().find(|c| !c.is_ascii_uppercase()) {
+        bail!("Alphabet may only contain letters, found '{}'", c);
+    }
+    for (i, c) in letters.iter().enumerate() {
+        if letters[..i].contains(c) {
+            bail!("Letter {} appears more than once in the alphabet", c);
+        }
+    }
+    if letters.len() != 26 {
+        let missing: String = PLAIN_ALPHABET
+            .chars()
+            .filter(|c| !letters.contains(c))
+            .collect();
+        bail!("Alphabet is missing {} letters: {}", missing.len(), missing);
+    }
+
+    Ok(letters.into_iter().map(char_to_num).collect())
+}
+
+/// Monoalphabetic substitution: plaintext letter `A + i` becomes `alphabet[i]`.
+pub fn substitution_encrypt(plaintext: String, alphabet: String) -> Result<String> {
+    let alphabet = check_substitution_alphabet(&alphabet)?;
+    Ok(map_letters(&plaintext, |_, p| alphabet[p as usize]))
+}
+
+pub fn substitution_decrypt(ciphertext: String, alphabet: String) -> Result<String> {
+    let alphabet = check_substitution_alphabet(&alphabet)?;
+    let mut inverse = [0; 26];
+    for (p, &c) in alphabet.iter().enumerate() {
+        inverse[c as usize] = p as i32;
+    }
+    Ok(map_letters(&ciphertext, |_, c| inverse[c as usize]))
+}
+
+/// Which letter a 25-cell digraph square leaves out.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SquareMerge {
+    /// J is written as I
+    #[default]
+    JIntoI,
+    /// Q is dropped from the square and the text
+    OmitQ,
+}
+
+impl SquareMerge {
+    pub fn alphabet(self) -> &'static str {
+        match self {
+            Self::JIntoI => POLYBIUS_ALPHABET_5X5,
+            Self::OmitQ => "ABCDEFGHIJKLMNOPRSTUVWXYZ",
+        }
+    }
+}
+
+/// Text preparation shared by Playfair, Four-square and Two-square.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DigraphOptions {
+    pub merge: SquareMerge,
+    /// Completes an odd-length message and, for Playfair, splits doubled letters
+    pub padding: char,
+}
+
+impl Default for DigraphOptions {
+    fn default() -> Self {
+        Self {
+            merge: SquareMerge::JIntoI,
+            padding: 'X',
+        }
+    }
+}
+
+impl DigraphOptions {
+    pub fn square(&self, keyword: &str) -> PolybiusSquare {
+        PolybiusSquare::new(keyword, self.merge.alphabet(), 5)
+    }
+}
+
+/// Folds `text` into the square's alphabet and cuts it into pairs. Doubled letters in a
+/// pair are split with the padding letter when `split_doubles` is set.
+fn digraphs(text: &str, options: DigraphOptions, split_doubles: bool) -> Result<Vec<(char, char)>> {
+    let alphabet = options.merge.alphabet();
+    let padding = options.padding.to_ascii_uppercase();
+    if !alphabet.contains(padding) {
+        bail!("Padding letter {} is not in the square", padding);
+    }
+
+    let letters: Vec<char> = text
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .filter_map(|c| fold_into_alphabet(c, alphabet))
+        .collect();
+
+    let mut pairs = Vec::with_capacity(letters.len() / 2 + 1);
+    let mut i = 0;
+    while i < letters.len() {
+        let a = letters[i];
+        match letters.get(i + 1) {
+            Some(&b) if !(split_doubles && a == b) => {
+                pairs.push((a, b));
+                i += 2;
+            }
+            _ => {
+                pairs.push((a, padding));
+                i += 1;
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+fn square_coords(square: &PolybiusSquare, c: char) -> Result<(usize, usize)> {
+    square
+        .coords(c)
+        .ok_or_else(|| anyhow!("Letter {} is not in the square", c))
+}
+
+fn square_at(square: &PolybiusSquare, row: usize, col: usize) -> Result<char> {
+    square
+        .at(row, col)
+        .ok_or_else(|| anyhow!("Square is incomplete"))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayfairRule {
+    /// Same row: take the letter to the right (left when decrypting)
+    Row,
+    /// Same column: take the letter below (above when decrypting)
+    Column,
+    /// Different row and column: swap columns within the rectangle
+    Rectangle,
+}
+
+/// One digraph of a Playfair operation and the rule used on it.
+#[derive(Clone, Copy, Debug)]
+pub struct PlayfairStep {
+    pub input: (char, char),
+    pub output: (char, char),
+    pub rule: PlayfairRule,
+}
+
+pub fn playfair_steps(
+    text: &str,
+    key: &str,
+    options: DigraphOptions,
+    encrypt: bool,
+) -> Result<Vec<PlayfairStep>> {
+    let square = options.square(key);
+    let shift = if encrypt { 1 } else { 4 };
+
+    digraphs(text, options, encrypt)?
+        .into_iter()
+        .map(|(a, b)| {
+            let (ra, ca) = square_coords(&square, a)?;
+            let (rb, cb) = square_coords(&square, b)?;
+            let (rule, (ra, ca), (rb, cb)) = if ra == rb {
+                (
+                    PlayfairRule::Row,
+                    (ra, (ca + shift) % 5),
+                    (rb, (cb + shift) % 5),
+                )
+            } else if ca == cb {
+                (
+                    PlayfairRule::Column,
+                    ((ra + shift) % 5, ca),
+                    ((rb + shift) % 5, cb),
+                )
+            } else {
+                (PlayfairRule::Rectangle, (ra, cb), (rb, ca))
+            };
+            Ok(PlayfairStep {
+                input: (a, b),
+                output: (square_at(&square, ra, ca)?, square_at(&square, rb, cb)?),
+                rule,
+            })
+        })
+        .collect()
+}
+
+fn join_steps(steps: Vec<PlayfairStep>) -> String {
+    steps
+        .into_iter()
+        .flat_map(|step| [step.output.0, step.output.1])
+        .collect()
+}
+
+pub fn playfair_encrypt(plaintext: String, key: String, options: DigraphOptions) -> Result<String> {
+    Ok(join_steps(playfair_steps(&plaintext, &key, options, true)?))
+}
+
+pub fn playfair_decrypt(
+    ciphertext: String,
+    key: String,
+    options: DigraphOptions,
+) -> Result<String> {
+    Ok(join_steps(playfair_steps(
+        &ciphertext,
+        &key,
+        options,
+        false,
+    )?))
+}
+
+/// Four-square: plain squares top-left and bottom-right, keyed squares top-right
+/// (`key1`) and bottom-left (`key2`).
+fn four_square(
+    text: &str,
+    key1: &str,
+    key2: &str,
+    options: DigraphOptions,
+    encrypt: bool,
+) -> Result<String> {
+    let plain = options.square("");
+    let (upper_right, lower_left) = (options.square(key1), options.square(key2));
+    let (first, second) = if encrypt {
+        (&plain, &plain)
+    } else {
+        (&upper_right, &lower_left)
+    };
+    let (first_out, second_out) = if encrypt {
+        (&upper_right, &lower_left)
+    } else {
+        (&plain, &plain)
+    };
+
+    let mut output = String::new();
+    for (a, b) in digraphs(text, options, false)? {
+        let (ra, ca) = square_coords(first, a)?;
+        let (rb, cb) = square_coords(second, b)?;
+        output.push(square_at(first_out, ra, cb)?);
+        output.push(square_at(second_out, rb, ca)?);
+    }
+    Ok(output)
+}
+
+pub fn four_square_encrypt(
+    plaintext: String,
+    key1: String,
+    key2: String,
+    options: DigraphOptions,
+) -> Result<String> {
+    four_square(&plaintext, &key1, &key2, options, true)
+}
+
+pub fn four_square_decrypt(
+    ciphertext: String,
+    key1: String,
+    key2: String,
+    options: DigraphOptions,
+) -> Result<String> {
+    four_square(&ciphertext, &key1, &key2, options, false)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TwoSquareLayout {
+    /// `key1` square above `key2`; pairs in the same column pass through unchanged
+    Vertical,
+    /// `key1` square left of `key2`; pairs in the same row come out reversed
+    Horizontal,
+}
+
+fn two_square(
+    text: &str,
+    key1: &str,
+    key2: &str,
+    layout: TwoSquareLayout,
+    options: DigraphOptions,
+    encrypt: bool,
+) -> Result<String> {
+    let (first, second) = (options.square(key1), options.square(key2));
+
+    let mut output = String::new();
+    for (a, b) in digraphs(text, options, false)? {
+        let pair = match layout {
+            // Vertical Two-square is its own inverse
+            TwoSquareLayout::Vertical => {
+                let (ra, ca) = square_coords(&first, a)?;
+                let (rb, cb) = square_coords(&second, b)?;
+                if ca == cb {
+                    (a, b)
+                } else {
+                    (square_at(&first, ra, cb)?, square_at(&second, rb, ca)?)
+                }
+            }
+            TwoSquareLayout::Horizontal if encrypt => {
+                let (ra, ca) = square_coords(&first, a)?;
+                let (rb, cb) = square_coords(&second, b)?;
+                (square_at(&second, ra, cb)?, square_at(&first, rb, ca)?)
+            }
+            TwoSquareLayout::Horizontal => {
+                let (ra, ca) = square_coords(&second, a)?;
+                let (rb, cb) = square_coords(&first, b)?;
+                (square_at(&first, ra, cb)?, square_at(&second, rb, ca)?)
+            }
+        };
+        output.push(pair.0);
+        output.push(pair.1);
+    }
+    Ok(output)
+}
+
+pub fn two_square_encrypt(
+    plaintext: String,
+    key1: String,
+    key2: String,
+    layout: TwoSquareLayout,
+    options: DigraphOptions,
+) -> Result<String> {
+    two_square(&plaintext, &key1, &key2, layout, options, true)
+}
+
+pub fn two_square_decrypt(
+    ciphertext: String,
+    key1: String,
+    key2: String,
+    layout: TwoSquareLayout,
+    options: DigraphOptions,
+) -> Result<String> {
+    two_square(&ciphertext, &key1, &key2, layout, options, false)
+}
+
+/// Baconian alphabets: the historical 24-letter one shares I/J and U/V.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BaconVariant {
+    #[default]
+    TwentyFour,
+    TwentySix,
+}
+
+impl BaconVariant {
+    fn alphabet(self) -> &'static str {
+        match self {
+            Self::TwentyFour => "ABCDEFGHIKLMNOPQRSTUWXYZ",
+            Self::TwentySix => PLAIN_ALPHABET,
+        }
+    }
+
+    fn index(self, c: char) -> Option<usize> {
+        let c = match (self, c.to_ascii_uppercase()) {
+            (Self::TwentyFour, 'J') => 'I',
+            (Self::TwentyFour, 'V') => 'U',
+            (_, c) => c,
+        };
+        self.alphabet().find(c)
+    }
+}
+
+/// Each letter becomes a group of five A/B symbols (its index in binary, A = 0).
+pub fn baconian_encrypt(plaintext: String, variant: BaconVariant) -> String {
+    plaintext
+        .chars()
+        .filter_map(|c| variant.index(c))
+        .map(|i| {
+            (0..5)
+                .rev()
+                .map(|bit| if i >> bit & 1 == 0 { 'A' } else { 'B' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn baconian_decrypt(ciphertext: String, variant: BaconVariant) -> Result<String> {
+    let bits: Vec<usize> = ciphertext
+        .chars()
+        .filter_map(|c| match c.to_ascii_uppercase() {
+            'A' => Some(0),
+            'B' => Some(1),
+            _ => None,
+        })
+        .collect();
+    if !bits.len().is_multiple_of(5) {
+        bail!("Baconian ciphertext needs groups of five A/B symbols");
+    }
+
+    let alphabet: Vec<char> = variant.alphabet().chars().collect();
+    bits.chunks(5)
+        .map(|group| {
+            let index = group.iter().fold(0, |acc, bit| acc * 2 + bit);
+            alphabet
+                .get(index)
+                .copied()
+                .ok_or_else(|| anyhow!("Group {} is not a letter", index))
+        })
+        .collect()
+}
+
+/// Hides A/B symbols in a cover text using two typefaces: lower case for A, upper case
+/// for B. Letters of the cover text beyond the message are left in lower case.
+pub fn bacon_hide(symbols: &str, cover: &str) -> Result<String> {
+    let symbols: Vec<char> = symbols
+        .chars()
+        .map(|c| c.to_ascii_uppercase())
+        .filter(|c| matches!(c, 'A' | 'B'))
+        .collect();
+    let capacity = cover.chars().filter(|c| c.is_ascii_alphabetic()).count();
+    if capacity < symbols.len() {
+        bail!(
+            "Cover text has {} letters but the message needs {}",
+            capacity,
+            symbols.len()
+        );
+    }
+
+    let mut symbols = symbols.into_iter();
+    Ok(cover
+        .chars()
+        .map(|c| {
+            if !c.is_ascii_alphabetic() {
+                return c;
+            }
+            match symbols.next() {
+                Some('B') => c.to_ascii_uppercase(),
+                _ => c.to_ascii_lowercase(),
+            }
+        })
+        .collect())
+}
+
+/// Reads the typeface of every letter of a cover text back as A (lower) or B (upper).
+pub fn bacon_reveal(cover: &str) -> String {
+    cover
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| if c.is_ascii_uppercase() { 'B' } else { 'A' })
+        .collect()
+}
+
+const MORSE: [(char, &str); 36] = [
+    ('A', ".-"),
+    ('B', "-..."),
+    ('C', "-.-."),
+    ('D', "-.."),
+    ('E', "."),
+    ('F', "..-."),
+    ('G', "--."),
+    ('H', "...."),
+    ('I', ".."),
+    ('J', ".---"),
+    ('K', "-.-"),
+    ('L', ".-.."),
+    ('M', "--"),
+    ('N', "-."),
+    ('O', "---"),
+    ('P', ".--."),
+    ('Q', "--.-"),
+    ('R', ".-."),
+    ('S', "..."),
+    ('T', "-"),
+    ('U', "..-"),
+    ('V', "...-"),
+    ('W', ".--"),
+    ('X', "-..-"),
+    ('Y', "-.--"),
+    ('Z', "--.."),
+    ('0', "-----"),
+    ('1', ".----"),
+    ('2', "..---"),
+    ('3', "...--"),
+    ('4', "....-"),
+    ('5', "....."),
+    ('6', "-...."),
+    ('7', "--..."),
+    ('8', "---.."),
+    ('9', "----."),
+];
+
+/// Morse code using `x` between letters and `xx` between words, as the fractionating
+/// ciphers expect. Characters without a Morse code are dropped.
+pub fn morse_encode(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter_map(|c| {
+                    let c = c.to_ascii_uppercase();
+                    MORSE.iter().find(|(m, _)| *m == c).map(|(_, code)| *code)
+                })
+                .collect::<Vec<_>>()
+                .join("x")
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("xx")
+}
+
+pub fn morse_decode(morse: &str) -> Result<String> {
+    morse
+        .trim_matches('x')
+        .split("xx")
+        .map(|word| {
+            word.split('x')
+                .filter(|code| !code.is_empty())
+                .map(|code| {
+                    MORSE
+                        .iter()
+                        .find(|(_, m)| *m == code)
+                        .map(|(c, _)| *c)
+                        .ok_or_else(|| anyhow!("{} is not a Morse code", code))
+                })
+                .collect::<Result<String>>()
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(|words| words.join(" "))
+}
+
+/// The 26 Morse trigrams in table order (`...`, `..-`, `..x`, ...), `xxx` excluded.
+fn morse_trigrams() -> Vec<String> {
+    let symbols = ['.', '-', 'x'];
+    let mut trigrams = Vec::with_capacity(26);
+    for a in symbols {
+        for b in symbols {
+            for c in symbols {
+                trigrams.push(format!("{}{}{}", a, b, c));
+            }
+        }
+    }
+    trigrams.pop();
+    trigrams
+}
+
+/// Fractionated Morse: the Morse string is cut into trigrams and each trigram is
+/// replaced by a letter of the keyword-mixed alphabet.
+pub fn fractionated_morse_encrypt(plaintext: String, key: String) -> Result<String> {
+    let mut morse = morse_encode(&plaintext);
+    if morse.is_empty() {
+        bail!("Nothing to encrypt: no characters with a Morse code");
+    }
+    while !morse.len().is_multiple_of(3) {
+        morse.push('x');
+    }
+
+    let alphabet: Vec<char> = keyword_alphabet(&key).chars().collect();
+    let trigrams = morse_trigrams();
+    Ok(morse
+        .as_bytes()
+        .chunks(3)
+        .map(|chunk| {
+            let trigram = std::str::from_utf8(chunk).unwrap_or_default();
+            let index = trigrams.iter().position(|t| t == trigram).unwrap_or(0);
+            alphabet[index]
+        })
+        .collect())
+}
+
+pub fn fractionated_morse_decrypt(ciphertext: String, key: String) -> Result<String> {
+    let alphabet = keyword_alphabet(&key);
+    let trigrams = morse_trigrams();
+    let morse: String = ciphertext
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| {
+            let index = alphabet.find(c.to_ascii_uppercase()).unwrap_or(0);
+            trigrams[index].as_str()
+        })
+        .collect();
+    morse_decode(&morse)
+}
+
+/// Parses a Pollux key: one of `.`, `-` or `x` for each digit 0-9.
+fn pollux_key(key: &str) -> Result<Vec<char>> {
+    let symbols: Vec<char> = key
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if symbols.len() != 10 || symbols.iter().any(|c| !matches!(c, '.' | '-' | 'x')) {
+        bail!("Pollux key needs exactly 10 symbols from '.', '-' and 'x', one per digit 0-9");
+    }
+    for needed in ['.', '-', 'x'] {
+        if !symbols.contains(&needed) {
+            bail!("Pollux key must assign at least one digit to '{}'", needed);
+        }
+    }
+    Ok(symbols)
+}
+
+/// Pollux: every Morse symbol is replaced by a random digit assigned to it, so the same
+/// plaintext encrypts differently each time.
+pub fn pollux_encrypt(plaintext: String, key: String) -> Result<String> {
+    use aes_gcm::aead::{OsRng, rand_core::RngCore};
+
+    let key = pollux_key(&key)?;
+    let morse = morse_encode(&plaintext);
+    Ok(morse
+        .chars()
+        .map(|symbol| {
+            let digits: Vec<usize> = (0..10).filter(|&d| key[d] == symbol).collect();
+            let choice = digits[OsRng.next_u32() as usize % digits.len()];
+            char::from(b'0' + choice as u8)
+        })
+        .collect())
+}
+
+pub fn pollux_decrypt(ciphertext: String, key: String) -> Result<String> {
+    let key = pollux_key(&key)?;
+    let morse: String = ciphertext
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| key[d as usize])
+        .collect();
+    morse_decode(&morse)
+}
+
+/// Chaocipher's two alphabets, which are permuted after every letter.
+#[derive(Clone, Debug)]
+pub struct Chaocipher {
+    /// Ciphertext alphabet
+    pub left: Vec<char>,
+    /// Plaintext alphabet
+    pub right: Vec<char>,
+}
+
+impl Chaocipher {
+    pub fn new(left: &str, right: &str) -> Result<Self> {
+        let to_chars = |alphabet: &str| -> Result<Vec<char>> {
+            Ok(check_substitution_alphabet(alphabet)?
+                .into_iter()
+                .map(num_to_char)
+                .collect())
+        };
+        Ok(Self {
+            left: to_chars(left)?,
+            right: to_chars(right)?,
+        })
+    }
+
+    fn permute(&mut self, index: usize) {
+        // Left: bring the cipher letter to the zenith, then move the letter at
+        // zenith+1 to the nadir
+        self.left.rotate_left(index);
+        let c = self.left.remove(1);
+        self.left.insert(13, c);
+
+        // Right: bring the plain letter to the zenith, shift once more, then move the
+        // letter at zenith+2 to the nadir
+        self.right.rotate_left((index + 1) % 26);
+        let c = self.right.remove(2);
+        self.right.insert(13, c);
+    }
+
+    pub fn encrypt_letter(&mut self, p: char) -> char {
+        let index = self.right.iter().position(|&c| c == p).unwrap_or(0);
+        let c = self.left[index];
+        self.permute(index);
+        c
+    }
+
+    pub fn decrypt_letter(&mut self, c: char) -> char {
+        let index = self.left.iter().position(|&x| x == c).unwrap_or(0);
+        let p = self.right[index];
+        self.permute(index);
+        p
+    }
+}
+
+fn chaocipher(text: &str, left: &str, right: &str, encrypt: bool) -> Result<String> {
+    let mut machine = Chaocipher::new(left, right)?;
+    Ok(text
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| {
+            let c = c.to_ascii_uppercase();
+            if encrypt {
+                machine.encrypt_letter(c)
+            } else {
+                machine.decrypt_letter(c)
+            }
+        })
+        .collect())
+}
+
+pub fn chaocipher_encrypt(plaintext: String, left: String, right: String) -> Result<String> {
+    chaocipher(&plaintext, &left, &right, true)
+}
+
+pub fn chaocipher_decrypt(ciphertext: String, left: String, right: String) -> Result<String> {
+    chaocipher(&ciphertext, &left, &right, false)
+}
+
+/// The VIC cipher's straddling checkerboard: eight symbols get a single digit, the
+/// other twenty get two digits, the first being one of the two blank columns of the
+/// top row. Besides A-Z the board holds `.` (full stop) and `/` (figure shift).
+#[derive(Clone, Debug)]
+pub struct StraddlingCheckerboard {
+    pub blanks: (u8, u8),
+    /// Three rows of ten cells; the blank cells of the top row are `None`
+    pub rows: [[Option<char>; 10]; 3],
+}
+
+impl StraddlingCheckerboard {
+    pub fn new(keyword: &str, blanks: (u8, u8)) -> Result<Self> {
+        if blanks.0 > 9 || blanks.1 > 9 || blanks.0 == blanks.1 {
+            bail!("Checkerboard needs two different blank columns from 0 to 9");
+        }
+        let mut symbols = keyword_alphabet(keyword)
+            .chars()
+            .chain(['.', '/'])
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        let mut rows = [[None; 10]; 3];
+        for (col, cell) in rows[0].iter_mut().enumerate() {
+            if col as u8 != blanks.0 && col as u8 != blanks.1 {
+                *cell = symbols.next();
+            }
+        }
+        for row in rows[1..].iter_mut() {
+            for cell in row.iter_mut() {
+                *cell = symbols.next();
+            }
+        }
+        Ok(Self { blanks, rows })
+    }
+
+    fn code(&self, symbol: char) -> Option<String> {
+        let prefixes = [None, Some(self.blanks.0), Some(self.blanks.1)];
+        self.rows.iter().zip(prefixes).find_map(|(row, prefix)| {
+            let col = row.iter().position(|&c| c == Some(symbol))?;
+            Some(match prefix {
+                Some(p) => format!("{}{}", p, col),
+                None => col.to_string(),
+            })
+        })
+    }
+
+    /// Letters and full stops are looked up on the board; each digit is sent as the
+    /// figure-shift code followed by the digit itself.
+    pub fn encode(&self, plaintext: &str) -> String {
+        let figure_shift = self.code('/').unwrap_or_default();
+        plaintext
+            .chars()
+            .filter_map(|c| {
+                if c.is_ascii_digit() {
+                    Some(format!("{}{}", figure_shift, c))
+                } else {
+                    self.code(c.to_ascii_uppercase())
+                }
+            })
+            .collect()
+    }
+
+    pub fn decode(&self, ciphertext: &str) -> Result<String> {
+        let mut digits = ciphertext
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .map(|d| d as u8);
+        let mut plaintext = String::new();
+        while let Some(d) = digits.next() {
+            let row = if d == self.blanks.0 {
+                1
+            } else if d == self.blanks.1 {
+                2
+            } else {
+                plaintext.extend(self.rows[0][d as usize]);
+                continue;
+            };
+            let col = digits
+                .next()
+                .ok_or_else(|| anyhow!("Ciphertext ends in the middle of a two-digit code"))?;
+            match self.rows[row][col as usize] {
+                Some('/') => {
+                    let digit = digits
+                        .next()
+                        .ok_or_else(|| anyhow!("Figure shift without a digit"))?;
+                    plaintext.push(char::from(b'0' + digit));
+                }
+                Some(c) => plaintext.push(c),
+                None => bail!("Empty checkerboard cell"),
+            }
+        }
+        Ok(plaintext)
+    }
+}
+
+/// Parses the two blank columns of a checkerboard, e.g. `2 6` or `26`.
+pub fn parse_checkerboard_blanks(blanks: &str) -> Result<(u8, u8)> {
+    let digits: Vec<u8> = blanks
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| d as u8)
+        .collect();
+    match digits[..] {
+        [a, b] => Ok((a, b)),
+        _ => bail!("Give exactly two blank columns, e.g. 2 6"),
+    }
+}
+
+pub fn checkerboard_encrypt(plaintext: String, key: String, blanks: String) -> Result<String> {
+    let board = StraddlingCheckerboard::new(&key, parse_checkerboard_blanks(&blanks)?)?;
+    Ok(board.encode(&plaintext))
+}
+
+pub fn checkerboard_decrypt(ciphertext: String, key: String, blanks: String) -> Result<String> {
+    let board = StraddlingCheckerboard::new(&key, parse_checkerboard_blanks(&blanks)?)?;
+    board.decode(&ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hill_key_entries_are_reduced_mod_26() {
+        let key = parse_hill_key("2147483647 -1; 5 88").unwrap();
+        assert_eq!(key, DMatrix::from_row_slice(2, 2, &[23, 25, 5, 10]));
+        let ciphertext = hill_cipher_encrypt("HELP".to_string(), key.clone()).unwrap();
+        assert_eq!(
+            hill_cipher_decrypt(ciphertext, key).unwrap(),
+            "HELP".to_string()
+        );
+    }
+
+    #[test]
+    fn affine_reduces_large_keys() {
+        let ciphertext = affine_encrypt("Affine".to_string(), i32::MAX, i32::MIN).unwrap();
+        // i32::MAX = 23 and i32::MIN = 2 (mod 26)
+        assert_eq!(
+            ciphertext,
+            affine_encrypt("Affine".to_string(), 23, 2).unwrap()
+        );
+        assert_eq!(
+            affine_decrypt(ciphertext, i32::MAX, i32::MIN).unwrap(),
+            "Affine"
+        );
+    }
+
+    // Beaufort and Porta vectors from Practical Cryptography, autokey from Wikipedia.
+    // Variant Beaufort and the running key are checked against Wikipedia's Vigenère
+    // example (ATTACKATDAWN / LEMON), which they reduce to.
+
+    #[test]
+    fn beaufort_vector() {
+        let ciphertext = beaufort_cipher(
+            "DEFENDTHEEASTWALLOFTHECASTLE".to_string(),
+            "FORTIFICATION".to_string(),
+        )
+        .unwrap();
+        assert_eq!(ciphertext, "CKMPVCPVWPIWUJOGIUAPVWRIWUUK");
+        assert_eq!(
+            beaufort_cipher(ciphertext, "FORTIFICATION".to_string()).unwrap(),
+            "DEFENDTHEEASTWALLOFTHECASTLE"
+        );
+    }
+
+    #[test]
+    fn variant_beaufort_is_vigenere_decryption() {
+        assert_eq!(
+            variant_beaufort_encrypt("LXFOPVEFRNHR".to_string(), "LEMON".to_string()).unwrap(),
+            "ATTACKATDAWN"
+        );
+        assert_eq!(
+            variant_beaufort_decrypt("ATTACKATDAWN".to_string(), "LEMON".to_string()).unwrap(),
+            "LXFOPVEFRNHR"
+        );
+    }
+
+    #[test]
+    fn porta_vector() {
+        let ciphertext = porta_cipher(
+            "DEFENDTHEEASTWALLOFTHECASTLE".to_string(),
+            "FORTIFICATION".to_string(),
+        )
+        .unwrap();
+        assert_eq!(ciphertext, "SYNNJSCVRNRLAHUTUKUCVRYRLANY");
+        assert_eq!(
+            porta_cipher(ciphertext, "FORTIFICATION".to_string()).unwrap(),
+            "DEFENDTHEEASTWALLOFTHECASTLE"
+        );
+    }
+
+    #[test]
+    fn autokey_vector() {
+        let ciphertext =
+            autokey_encrypt("ATTACKATDAWN".to_string(), "QUEENLY".to_string()).unwrap();
+        assert_eq!(ciphertext, "QNXEPVYTWTWP");
+        assert_eq!(
+            autokey_decrypt(ciphertext, "QUEENLY".to_string()).unwrap(),
+            "ATTACKATDAWN"
+        );
+    }
+
+    #[test]
+    fn ciphertext_autokey_round_trip() {
+        let ciphertext =
+            ciphertext_autokey_encrypt("ATTACKATDAWN".to_string(), "QUEENLY".to_string()).unwrap();
+        // The first key-length letters match plaintext autokey; then the key is the
+        // ciphertext QNXEP rather than the plaintext ATTAC
+        assert_eq!(ciphertext, "QNXEPVYJQXAC");
+        assert_eq!(
+            ciphertext_autokey_decrypt(ciphertext, "QUEENLY".to_string()).unwrap(),
+            "ATTACKATDAWN"
+        );
+    }
+
+    #[test]
+    fn running_key_vector() {
+        let ciphertext =
+            running_key_encrypt("ATTACKATDAWN".to_string(), "LEMONLEMONLE".to_string()).unwrap();
+        assert_eq!(ciphertext, "LXFOPVEFRNHR");
+        assert_eq!(
+            running_key_decrypt(ciphertext, "LEMONLEMONLE".to_string()).unwrap(),
+            "ATTACKATDAWN"
+        );
+        assert!(running_key_encrypt("ATTACKATDAWN".to_string(), "LEMON".to_string()).is_err());
+    }
+
+    // Gronsfeld is Vigenère with the key letters limited to A-J written as digits, so
+    // key 31415 must agree with the Vigenère key DBEBF
+    #[test]
+    fn gronsfeld_vector() {
+        let plaintext = "DEFENDTHEEASTWALLOFTHECASTLE";
+        let ciphertext = gronsfeld_encrypt(plaintext.to_string(), "31415".to_string()).unwrap();
+        assert_eq!(ciphertext, "GFJFSGULFJDTXXFOMSGYKFGBXWMI");
+        assert_eq!(
+            ciphertext,
+            vigenere_cipher_encrypt(plaintext.to_string(), "DBEBF".to_string())
+        );
+        assert_eq!(
+            gronsfeld_decrypt(ciphertext, "31415".to_string()).unwrap(),
+            plaintext
+        );
+    }
+
+    #[test]
+    fn gronsfeld_round_trip() {
+        let plaintext = "Gronsfeld, 1734: only ten alphabets!";
+        let ciphertext = gronsfeld_encrypt(plaintext.to_string(), "90210".to_string()).unwrap();
+        assert_eq!(
+            gronsfeld_decrypt(ciphertext, "90210".to_string()).unwrap(),
+            plaintext
+        );
+        assert!(gronsfeld_encrypt(plaintext.to_string(), "KEY".to_string()).is_err());
+    }
+
+    /// Vectors from the playfair_cipher crate this implementation replaced.
+    #[test]
+    fn playfair_matches_the_old_crate() {
+        let options = DigraphOptions::default();
+        let cases = [
+            (
+                "playfair example",
+                "hide the gold in the tree stump",
+                "BMODZBXDNABEKUDMUIXMMOUVIF",
+            ),
+            ("rust rules", "cratesio", "ETCUBRHP"),
+            ("secret", "a", "DV"),
+        ];
+        for (key, plaintext, ciphertext) in cases {
+            assert_eq!(
+                playfair_encrypt(plaintext.to_string(), key.to_string(), options).unwrap(),
+                ciphertext
+            );
+        }
+        assert_eq!(
+            playfair_decrypt(
+                "BMODZBXDNABEKUDMUIXMMOUVIF".to_string(),
+                "playfair example".to_string(),
+                options
+            )
+            .unwrap(),
+            "HIDETHEGOLDINTHETREXESTUMP"
+        );
+        assert_eq!(
+            playfair_decrypt("ETCUBRHP".to_string(), "rustrules".to_string(), options).unwrap(),
+            "CRATESIO"
+        );
+    }
+
+    // Four-square and vertical Two-square examples from Wikipedia, keys EXAMPLE and
+    // KEYWORD, with Q left out of the squares.
+
+    #[test]
+    fn four_square_vector() {
+        let options = DigraphOptions {
+            merge: SquareMerge::OmitQ,
+            ..DigraphOptions::default()
+        };
+        let ciphertext = four_square_encrypt(
+            "HELP ME OBIWAN KENOBI".to_string(),
+            "EXAMPLE".to_string(),
+            "KEYWORD".to_string(),
+            options,
+        )
+        .unwrap();
+        assert_eq!(ciphertext, "FYGMKYHOBXMFKKKIMD");
+        assert_eq!(
+            four_square_decrypt(
+                ciphertext,
+                "EXAMPLE".to_string(),
+                "KEYWORD".to_string(),
+                options
+            )
+            .unwrap(),
+            "HELPMEOBIWANKENOBI"
+        );
+    }
+
+    #[test]
+    fn two_square_vectors() {
+        let options = DigraphOptions {
+            merge: SquareMerge::OmitQ,
+            ..DigraphOptions::default()
+        };
+        for (layout, expected) in [
+            (TwoSquareLayout::Vertical, "HEDLXWSDJYANHOTKDG"),
+            // Worked out by hand from the same squares: the right square's corner
+            // comes first, so the same-row pair ME comes out reversed
+            (TwoSquareLayout::Horizontal, "GXBNEMPBIAYRGPSEBH"),
+        ] {
+            let ciphertext = two_square_encrypt(
+                "HELP ME OBIWAN KENOBI".to_string(),
+                "EXAMPLE".to_string(),
+                "KEYWORD".to_string(),
+                layout,
+                options,
+            )
+            .unwrap();
+            assert_eq!(ciphertext, expected, "{:?}", layout);
+            assert_eq!(
+                two_square_decrypt(
+                    ciphertext,
+                    "EXAMPLE".to_string(),
+                    "KEYWORD".to_string(),
+                    layout,
+                    options
+                )
+                .unwrap(),
+                "HELPMEOBIWANKENOBI",
+                "{:?}",
+                layout
+            );
+        }
+    }
+
+    /// Deterministic xorshift letters, so the Hill round trips are reproducible.
+    fn pseudo_random_letters(state: &mut u64, len: usize) -> Vec<i32> {
+        (0..len)
+            .map(|_| {
+                *state ^= *state << 13;
+                *state ^= *state >> 7;
+                *state ^= *state << 17;
+                (*state % 26) as i32
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hill_known_plaintext_recovers_random_keys() {
+        let mut state = 0x2545_f491_4f6c_dd1d;
+        for round in 0..200 {
+            let size = 2 + round % 4;
+            let key = loop {
+                let entries = pseudo_random_letters(&mut state, size * size);
+                let key = DMatrix::from_row_slice(size, size, &entries);
+                if matrix_inverse_mod(&key, 26).is_some() {
+                    break key;
+                }
+            };
+            // 20 blocks, so the text itself is all but certain to span the key space
+            let plaintext: String = pseudo_random_letters(&mut state, size * 20)
+                .into_iter()
+                .map(num_to_char)
+                .collect();
+            let ciphertext = hill_cipher_encrypt(plaintext.clone(), key.clone()).unwrap();
+            assert_eq!(
+                hill_known_plaintext_attack(plaintext, ciphertext, size).unwrap(),
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn hill_known_plaintext_rejects_singular_text_quickly() {
+        // Only even letters: every block matrix is singular mod 2, and trying all
+        // C(300, 6) combinations would never finish
+        let plaintext = "ACEGIKMOQSUWY".repeat(150);
+        let key = DMatrix::<i32>::identity(6, 6);
+        let ciphertext = hill_cipher_encrypt(plaintext.clone(), key).unwrap();
+        assert!(hill_known_plaintext_attack(plaintext, ciphertext, 6).is_err());
+    }
+}
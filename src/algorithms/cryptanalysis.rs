@@ -0,0 +1,925 @@
+//! Attacks on classical ciphers: given only ciphertext, find the most likely keys.
+//!
+//! Candidates are scored against a [`LanguageModel`] from `fitness.rs`, so every attack
+//! works for any language with a model, and are returned best first.
+
+use crate::algorithms::classical_ciphers::{
+    DigraphOptions, caesar_cipher_encrypt, check_substitution_alphabet, fold_into_alphabet,
+    playfair_decrypt, substitution_decrypt, vigenere_cipher_decrypt,
+};
+use crate::algorithms::fitness::{LanguageModel, index_of_coincidence, text_letters};
+use crate::algorithms::transposition::{columnar_decrypt_symbols, rail_fence_decrypt};
+use aes_gcm::aead::OsRng;
+use aes_gcm::aead::rand_core::RngCore;
+use anyhow::{Result, anyhow, bail};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Longest Vigenère key considered when estimating the key length.
+pub const MAX_VIGENERE_KEY_LENGTH: usize = 20;
+
+#[derive(Clone, Debug)]
+pub struct CaesarCandidate {
+    /// The shift the ciphertext was encrypted with
+    pub shift: i32,
+    pub plaintext: String,
+    /// Chi-squared against the model's letter frequencies, lower is better
+    pub chi_squared: f64,
+    /// Quadgram log-probability per quadgram, higher is better
+    pub quadgram: f64,
+}
+
+/// Sum of each candidate's position when sorted by chi-squared (ascending) and by
+/// quadgram score (descending), so neither score's scale dominates the ranking.
+fn combined_ranks(chi_squared: &[f64], quadgram: &[f64]) -> Vec<usize> {
+    let n = chi_squared.len();
+    let mut by_chi: Vec<usize> = (0..n).collect();
+    by_chi.sort_by(|&a, &b| chi_squared[a].total_cmp(&chi_squared[b]));
+    let mut by_quad: Vec<usize> = (0..n).collect();
+    by_quad.sort_by(|&a, &b| quadgram[b].total_cmp(&quadgram[a]));
+
+    let mut rank = vec![0; n];
+    for order in [by_chi, by_quad] {
+        for (position, i) in order.into_iter().enumerate() {
+            rank[i] += position;
+        }
+    }
+    rank
+}
+
+/// Tries all 26 shifts and ranks the results, most like `model`'s language first.
+pub fn crack_caesar(ciphertext: &str, model: &LanguageModel) -> Vec<CaesarCandidate> {
+    let candidates: Vec<CaesarCandidate> = (0..26)
+        .map(|shift| {
+            let plaintext = caesar_cipher_encrypt(ciphertext.to_string(), (26 - shift) % 26);
+            CaesarCandidate {
+                shift,
+                chi_squared: model.chi_squared(&plaintext),
+                quadgram: model.score_per_quadgram(&plaintext),
+                plaintext,
+            }
+        })
+        .collect();
+
+    let chi: Vec<f64> = candidates.iter().map(|c| c.chi_squared).collect();
+    let quad: Vec<f64> = candidates.iter().map(|c| c.quadgram).collect();
+    let rank = combined_ranks(&chi, &quad);
+
+    // Ties go to the better quadgram score
+    let mut ranked: Vec<(usize, CaesarCandidate)> = rank.into_iter().zip(candidates).collect();
+    ranked.sort_by(|(ra, a), (rb, b)| ra.cmp(rb).then(b.quadgram.total_cmp(&a.quadgram)));
+    ranked.into_iter().map(|(_, c)| c).collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct KeyLengthCandidate {
+    pub length: usize,
+    /// Average index of coincidence of the columns; close to the model's expected IoC
+    /// (about 0.067 for English) at the right length
+    pub ioc: f64,
+    /// How many repeated-trigram spacings are a multiple of this length
+    pub kasiski: usize,
+}
+
+/// Kasiski examination: distances between repeats of every trigram in the text.
+pub fn kasiski_spacings(letters: &[u8]) -> Vec<usize> {
+    let mut last_seen: HashMap<&[u8], usize> = HashMap::new();
+    let mut spacings = Vec::new();
+    for (i, trigram) in letters.windows(3).enumerate() {
+        if let Some(previous) = last_seen.insert(trigram, i) {
+            spacings.push(i - previous);
+        }
+    }
+    spacings
+}
+
+/// Splits letters into `length` columns, column `i` holding every letter enciphered
+/// with key letter `i`.
+fn columns(letters: &[u8], length: usize) -> Vec<Vec<u8>> {
+    (0..length)
+        .map(|i| letters.iter().skip(i).step_by(length).copied().collect())
+        .collect()
+}
+
+/// Scores every key length from 1 to `max_length` with the average column IoC and the
+/// Kasiski spacing count. The result is ordered by length.
+pub fn vigenere_key_lengths(ciphertext: &str, max_length: usize) -> Vec<KeyLengthCandidate> {
+    let letters = text_letters(ciphertext);
+    let spacings = kasiski_spacings(&letters);
+    let max_length = max_length.min(letters.len() / 2);
+
+    (1..=max_length)
+        .map(|length| {
+            let iocs: Vec<f64> = columns(&letters, length)
+                .iter()
+                .filter(|column| column.len() > 1)
+                .map(|column| index_of_coincidence(column))
+                .collect();
+            KeyLengthCandidate {
+                length,
+                ioc: iocs.iter().sum::<f64>() / iocs.len().max(1) as f64,
+                kasiski: spacings.iter().filter(|&&s| s % length == 0).count(),
+            }
+        })
+        .collect()
+}
+
+/// Multiples of the true key length score as well as the length itself, and their
+/// shorter columns often score higher still, so take the shortest length that gets at
+/// least halfway from random text's IoC (1/26) to the best one.
+pub fn best_key_length(candidates: &[KeyLengthCandidate]) -> Option<usize> {
+    const RANDOM_IOC: f64 = 1.0 / 26.0;
+    let best = candidates.iter().map(|c| c.ioc).fold(0.0, f64::max);
+    let threshold = (best + RANDOM_IOC) / 2.0;
+    candidates
+        .iter()
+        .find(|c| c.ioc >= threshold)
+        .map(|c| c.length)
+}
+
+fn shift_letters(letters: &[u8], shift: u8) -> String {
+    letters
+        .iter()
+        .map(|&l| (b'A' + (l + 26 - shift) % 26) as char)
+        .collect()
+}
+
+/// Recovers each key letter independently: the Caesar shift of its column with the
+/// lowest chi-squared against the language.
+pub fn vigenere_key_by_chi_squared(
+    ciphertext: &str,
+    length: usize,
+    model: &LanguageModel,
+) -> String {
+    let letters = text_letters(ciphertext);
+    columns(&letters, length.max(1))
+        .iter()
+        .map(|column| {
+            let shift = (0..26u8)
+                .min_by(|&a, &b| {
+                    model
+                        .chi_squared(&shift_letters(column, a))
+                        .total_cmp(&model.chi_squared(&shift_letters(column, b)))
+                })
+                .unwrap_or(0);
+            (b'A' + shift) as char
+        })
+        .collect()
+}
+
+/// Hill-climbs the key one letter at a time on the quadgram score of the whole
+/// decryption, which fixes letters that short columns got wrong.
+pub fn refine_vigenere_key(ciphertext: &str, key: &str, model: &LanguageModel) -> String {
+    let letters = text_letters(ciphertext);
+    let mut key = text_letters(key);
+    if key.is_empty() {
+        return String::new();
+    }
+
+    let decrypt = |key: &[u8]| -> Vec<u8> {
+        letters
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (c + 26 - key[i % key.len()]) % 26)
+            .collect()
+    };
+
+    let mut best = model.score_quadgrams(&decrypt(&key));
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..key.len() {
+            for letter in 0..26 {
+                let previous = key[i];
+                key[i] = letter;
+                let score = model.score_quadgrams(&decrypt(&key));
+                if score > best {
+                    best = score;
+                    improved = true;
+                } else {
+                    key[i] = previous;
+                }
+            }
+        }
+    }
+
+    key.iter().map(|&k| (b'A' + k) as char).collect()
+}
+
+/// Every stage of a Vigenère attack, for showing the work.
+#[derive(Clone, Debug)]
+pub struct VigenereAnalysis {
+    pub key_lengths: Vec<KeyLengthCandidate>,
+    pub length: usize,
+    pub chi_squared_key: String,
+    pub refined_key: String,
+    pub plaintext: String,
+}
+
+/// Breaks a Vigenère ciphertext. `length` forces a key length instead of the best
+/// IoC estimate.
+pub fn break_vigenere(
+    ciphertext: &str,
+    length: Option<usize>,
+    model: &LanguageModel,
+) -> Result<VigenereAnalysis> {
+    let key_lengths = vigenere_key_lengths(ciphertext, MAX_VIGENERE_KEY_LENGTH);
+    let length = match length {
+        Some(0) => bail!("Key length must be at least 1"),
+        Some(length) => length,
+        None => best_key_length(&key_lengths)
+            .ok_or_else(|| anyhow!("Need at least a few letters of ciphertext"))?,
+    };
+
+    let chi_squared_key = vigenere_key_by_chi_squared(ciphertext, length, model);
+    let refined_key = refine_vigenere_key(ciphertext, &chi_squared_key, model);
+    let plaintext = vigenere_cipher_decrypt(ciphertext.to_string(), refined_key.clone());
+
+    Ok(VigenereAnalysis {
+        key_lengths,
+        length,
+        chi_squared_key,
+        refined_key,
+        plaintext,
+    })
+}
+
+/// Reads locked substitution mappings written as cipher/plain letter pairs, e.g.
+/// `QE XT` for "Q decrypts to E, X decrypts to T". Returns `(cipher, plain)` pairs as
+/// numbers 0-25.
+pub fn parse_locked_mappings(text: &str) -> Result<Vec<(u8, u8)>> {
+    let mut locks: Vec<(u8, u8)> = Vec::new();
+    for pair in text.split_whitespace() {
+        let letters = text_letters(pair);
+        if letters.len() != 2 || pair.chars().count() != 2 {
+            bail!("'{}' is not a cipher/plain letter pair like QE", pair);
+        }
+        let (cipher, plain) = (letters[0], letters[1]);
+        for &(c, p) in &locks {
+            if (c == cipher) != (p == plain) {
+                bail!(
+                    "'{}' conflicts with {}{}",
+                    pair,
+                    (b'A' + c) as char,
+                    (b'A' + p) as char
+                );
+            }
+        }
+        if !locks.contains(&(cipher, plain)) {
+            locks.push((cipher, plain));
+        }
+    }
+    Ok(locks)
+}
+
+/// Best substitution key found so far by [`solve_substitution`].
+#[derive(Clone, Debug)]
+pub struct SubstitutionSolution {
+    /// Cipher alphabet in the form `substitution_encrypt` takes: plain A encrypts to
+    /// the first letter, and so on
+    pub alphabet: String,
+    /// Quadgram log10-probability per quadgram of the decryption
+    pub fitness: f64,
+    pub plaintext: String,
+    /// Restarts completed when this solution was reported
+    pub restarts: usize,
+}
+
+/// Moves every locked plain letter into place by swapping, keeping `decrypt` a permutation.
+fn apply_locks(decrypt: &mut [u8; 26], locks: &[(u8, u8)]) {
+    for &(cipher, plain) in locks {
+        if let Some(j) = decrypt.iter().position(|&p| p == plain) {
+            decrypt.swap(cipher as usize, j);
+        }
+    }
+}
+
+fn shuffled_key(locks: &[(u8, u8)]) -> [u8; 26] {
+    let mut decrypt: [u8; 26] = std::array::from_fn(|i| i as u8);
+    for i in (1..26).rev() {
+        let j = OsRng.next_u32() as usize % (i + 1);
+        decrypt.swap(i, j);
+    }
+    apply_locks(&mut decrypt, locks);
+    decrypt
+}
+
+fn solution(
+    ciphertext: &str,
+    decrypt: &[u8; 26],
+    fitness: f64,
+    restarts: usize,
+) -> Result<SubstitutionSolution> {
+    let mut alphabet = [b'A'; 26];
+    for (cipher, &plain) in decrypt.iter().enumerate() {
+        alphabet[plain as usize] = b'A' + cipher as u8;
+    }
+    let alphabet = String::from_utf8_lossy(&alphabet).into_owned();
+    Ok(SubstitutionSolution {
+        plaintext: substitution_decrypt(ciphertext.to_string(), alphabet.clone())?,
+        alphabet,
+        fitness,
+        restarts,
+    })
+}
+
+/// Hill-climbs monoalphabetic substitution keys on quadgram fitness, swapping two
+/// unlocked letters at a time, with a random restart whenever no swap helps.
+///
+/// The first climb starts from `start` (a cipher alphabet) when given. `report` gets
+/// the best key so far after every climb. Runs until `stop` is set or `max_restarts`
+/// climbs are done.
+pub fn solve_substitution(
+    ciphertext: &str,
+    locks: &[(u8, u8)],
+    start: Option<&str>,
+    max_restarts: usize,
+    model: &LanguageModel,
+    stop: &AtomicBool,
+    mut report: impl FnMut(&SubstitutionSolution),
+) -> Result<()> {
+    let letters = text_letters(ciphertext);
+    if letters.len() < 4 {
+        bail!("Need at least four letters of ciphertext");
+    }
+    let quadgrams = (letters.len() - 3) as f64;
+    let unlocked: Vec<usize> = (0..26)
+        .filter(|&c| !locks.iter().any(|&(locked, _)| locked as usize == c))
+        .collect();
+
+    let mut start = match start {
+        Some(alphabet) => {
+            let encrypt = check_substitution_alphabet(alphabet)?;
+            let mut decrypt = [0u8; 26];
+            for (plain, &cipher) in encrypt.iter().enumerate() {
+                decrypt[cipher as usize] = plain as u8;
+            }
+            apply_locks(&mut decrypt, locks);
+            Some(decrypt)
+        }
+        None => None,
+    };
+
+    let mut plaintext = vec![0u8; letters.len()];
+    let mut score = |decrypt: &[u8; 26]| {
+        for (p, &c) in plaintext.iter_mut().zip(&letters) {
+            *p = decrypt[c as usize];
+        }
+        model.score_quadgrams(&plaintext) / quadgrams
+    };
+
+    let mut best = f64::NEG_INFINITY;
+    let mut best_key = [0u8; 26];
+    for restart in 1..=max_restarts {
+        let mut decrypt = start.take().unwrap_or_else(|| shuffled_key(locks));
+        let mut current = score(&decrypt);
+        let mut improved = true;
+        while improved && !stop.load(Ordering::Relaxed) {
+            improved = false;
+            for (n, &a) in unlocked.iter().enumerate() {
+                for &b in &unlocked[n + 1..] {
+                    decrypt.swap(a, b);
+                    let candidate = score(&decrypt);
+                    if candidate > current {
+                        current = candidate;
+                        improved = true;
+                    } else {
+                        decrypt.swap(a, b);
+                    }
+                }
+            }
+        }
+
+        if current > best {
+            best = current;
+            best_key = decrypt;
+        }
+        report(&solution(ciphertext, &best_key, best, restart)?);
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Small xorshift generator for search loops that need millions of random numbers;
+/// seeded from the OS.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new() -> Self {
+        Self(OsRng.next_u64() | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    /// Uniform in [0, 1).
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Simulated annealing schedule: the temperature falls linearly from `temperature` to
+/// zero over `iterations` key changes, and the whole run is repeated `rounds` times.
+#[derive(Clone, Copy, Debug)]
+pub struct AnnealingSettings {
+    pub temperature: f64,
+    pub iterations: usize,
+    pub rounds: usize,
+}
+
+impl AnnealingSettings {
+    /// Starting temperature that suits a ciphertext of `letters` letters; longer texts
+    /// have larger score differences between neighbouring keys.
+    pub fn for_text(letters: usize) -> Self {
+        Self {
+            temperature: (10.0 + 0.087 * (letters as f64 - 84.0)).max(5.0),
+            iterations: 500_000,
+            rounds: 5,
+        }
+    }
+}
+
+/// Best Playfair square found so far by [`solve_playfair`].
+#[derive(Clone, Debug)]
+pub struct PlayfairSolution {
+    /// The 25 letters of the square, row by row; usable as the key of `playfair_decrypt`
+    pub square: String,
+    /// Quadgram log10-probability per quadgram of the decryption
+    pub fitness: f64,
+    pub plaintext: String,
+    pub round: usize,
+    /// Iterations done in the current round
+    pub iteration: usize,
+}
+
+/// Random change to a Playfair square: mostly swapping two letters, sometimes moving
+/// whole rows or columns so the search can escape keys that are right up to a shift.
+fn mutate_square(square: &mut [u8; 25], rng: &mut XorShift) {
+    let (a, b) = (rng.below(5), rng.below(5));
+    match rng.below(50) {
+        0 => {
+            for col in 0..5 {
+                square.swap(a * 5 + col, b * 5 + col);
+            }
+        }
+        1 => {
+            for row in 0..5 {
+                square.swap(row * 5 + a, row * 5 + b);
+            }
+        }
+        2 => square.reverse(),
+        3 => {
+            for row in 0..5 {
+                square[row * 5..row * 5 + 5].reverse();
+            }
+        }
+        4 => {
+            for row in 0..5 {
+                for col in row + 1..5 {
+                    square.swap(row * 5 + col, col * 5 + row);
+                }
+            }
+        }
+        _ => square.swap(rng.below(25), rng.below(25)),
+    }
+}
+
+fn playfair_decrypt_letters(square: &[u8; 25], ciphertext: &[u8], plaintext: &mut [u8]) {
+    let mut position = [0usize; 26];
+    for (i, &letter) in square.iter().enumerate() {
+        position[letter as usize] = i;
+    }
+    for (pair, out) in ciphertext
+        .chunks_exact(2)
+        .zip(plaintext.chunks_exact_mut(2))
+    {
+        let (a, b) = (position[pair[0] as usize], position[pair[1] as usize]);
+        let (ra, ca, rb, cb) = (a / 5, a % 5, b / 5, b % 5);
+        let (a, b) = if ra == rb {
+            (ra * 5 + (ca + 4) % 5, rb * 5 + (cb + 4) % 5)
+        } else if ca == cb {
+            ((ra + 4) % 5 * 5 + ca, (rb + 4) % 5 * 5 + cb)
+        } else {
+            (ra * 5 + cb, rb * 5 + ca)
+        };
+        out[0] = square[a];
+        out[1] = square[b];
+    }
+}
+
+/// Ciphertext-only attack on Playfair by simulated annealing over key squares, scored
+/// with the model's quadgrams. `report` gets the best square so far every few thousand
+/// iterations; the search ends after `settings.rounds` rounds or when `stop` is set.
+pub fn solve_playfair(
+    ciphertext: &str,
+    options: DigraphOptions,
+    settings: AnnealingSettings,
+    model: &LanguageModel,
+    stop: &AtomicBool,
+    mut report: impl FnMut(&PlayfairSolution),
+) -> Result<()> {
+    let alphabet = options.merge.alphabet();
+    let letters: Vec<u8> = ciphertext
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .filter_map(|c| fold_into_alphabet(c, alphabet))
+        .map(|c| c as u8 - b'A')
+        .collect();
+    if letters.len() < 4 || letters.len() % 2 == 1 {
+        bail!("Playfair ciphertext needs an even number of letters, at least four");
+    }
+    if let Some(pair) = letters.chunks_exact(2).find(|pair| pair[0] == pair[1]) {
+        bail!(
+            "Ciphertext pair {0}{0} has a doubled letter, which Playfair never produces",
+            (b'A' + pair[0]) as char
+        );
+    }
+
+    let mut rng = XorShift::new();
+    let mut plaintext = vec![0u8; letters.len()];
+    let mut score = |square: &[u8; 25]| {
+        playfair_decrypt_letters(square, &letters, &mut plaintext);
+        model.score_quadgrams(&plaintext)
+    };
+    let quadgrams = (letters.len() - 3) as f64;
+    let to_solution = |square: &[u8; 25], score: f64, round, iteration| -> Result<_> {
+        let square: String = square.iter().map(|&l| (b'A' + l) as char).collect();
+        Ok(PlayfairSolution {
+            plaintext: playfair_decrypt(ciphertext.to_string(), square.clone(), options)?,
+            square,
+            fitness: score / quadgrams,
+            round,
+            iteration,
+        })
+    };
+
+    let mut best_square = [0u8; 25];
+    for (cell, c) in best_square.iter_mut().zip(alphabet.bytes()) {
+        *cell = c - b'A';
+    }
+    let mut best = f64::NEG_INFINITY;
+
+    for round in 1..=settings.rounds {
+        let mut square = best_square;
+        for i in (1..25).rev() {
+            square.swap(i, rng.below(i + 1));
+        }
+        let mut current = score(&square);
+
+        for iteration in 0..settings.iterations {
+            let temperature =
+                settings.temperature * (1.0 - iteration as f64 / settings.iterations as f64);
+            let mut candidate = square;
+            mutate_square(&mut candidate, &mut rng);
+            let candidate_score = score(&candidate);
+            let change = candidate_score - current;
+            if change >= 0.0 || (temperature > 0.0 && rng.unit() < (change / temperature).exp()) {
+                square = candidate;
+                current = candidate_score;
+                if current > best {
+                    best = current;
+                    best_square = square;
+                }
+            }
+
+            if iteration % 10_000 == 0 {
+                if stop.load(Ordering::Relaxed) {
+                    return Ok(());
+                }
+                report(&to_solution(&best_square, best, round, iteration)?);
+            }
+        }
+        report(&to_solution(
+            &best_square,
+            best,
+            round,
+            settings.iterations,
+        )?);
+    }
+    Ok(())
+}
+
+/// Language score used to rank transposition candidates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NgramFitness {
+    /// Letter pairs: coarser, but steadier on short ciphertexts
+    Bigram,
+    #[default]
+    Quadgram,
+}
+
+impl NgramFitness {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bigram => "Bigram",
+            Self::Quadgram => "Quadgram",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Bigram => Self::Quadgram,
+            Self::Quadgram => Self::Bigram,
+        }
+    }
+
+    /// Log10-probability per n-gram of `letters`, so texts of any length compare.
+    fn score(self, letters: &[u8], model: &LanguageModel) -> f64 {
+        let (total, n) = match self {
+            Self::Bigram => (model.score_bigrams(letters), 2),
+            Self::Quadgram => (model.score_quadgrams(letters), 4),
+        };
+        total / (letters.len() + 1).saturating_sub(n).max(1) as f64
+    }
+}
+
+/// Most rails tried by [`crack_rail_fence`] in the UI.
+pub const MAX_RAIL_FENCE_RAILS: usize = 20;
+
+#[derive(Clone, Debug)]
+pub struct RailFenceCandidate {
+    pub rails: usize,
+    pub offset: usize,
+    /// Log10-probability per n-gram of the decryption
+    pub fitness: f64,
+    pub plaintext: String,
+}
+
+/// Tries every rail count from 2 to `max_rails` with every starting offset and ranks
+/// the decryptions, most like the model's language first.
+pub fn crack_rail_fence(
+    ciphertext: &str,
+    max_rails: usize,
+    fitness: NgramFitness,
+    model: &LanguageModel,
+) -> Result<Vec<RailFenceCandidate>> {
+    if text_letters(ciphertext).len() < 4 {
+        bail!("Rail fence attack needs at least four letters");
+    }
+
+    let mut candidates = Vec::new();
+    for rails in 2..=max_rails.min(ciphertext.chars().count()) {
+        for offset in 0..2 * (rails - 1) {
+            let plaintext = rail_fence_decrypt(ciphertext, rails, offset)?;
+            candidates.push(RailFenceCandidate {
+                rails,
+                offset,
+                fitness: fitness.score(&text_letters(&plaintext), model),
+                plaintext,
+            });
+        }
+    }
+    candidates.sort_by(|a, b| b.fitness.total_cmp(&a.fitness));
+    Ok(candidates)
+}
+
+/// Widest columnar key tried by [`solve_columnar`] in the UI.
+pub const MAX_COLUMNAR_WIDTH: usize = 12;
+
+/// Widths up to this are searched exhaustively (7! = 5040 orders); wider keys are
+/// hill-climbed.
+const EXHAUSTIVE_COLUMNAR_WIDTH: usize = 7;
+
+/// Random restarts of the column order climb for each wider key.
+const COLUMNAR_RESTARTS: usize = 20;
+
+/// Best column order found for one key width by [`solve_columnar`].
+#[derive(Clone, Debug)]
+pub struct ColumnarCandidate {
+    pub width: usize,
+    /// A keyword with that reading order, usable with `columnar_decrypt`
+    pub key: String,
+    /// Log10-probability per n-gram of the decryption
+    pub fitness: f64,
+    pub plaintext: String,
+}
+
+/// Steps `order` to the next permutation in lexicographic order; false after the last.
+fn next_permutation(order: &mut [usize]) -> bool {
+    let Some(i) = order.windows(2).rposition(|w| w[0] < w[1]) else {
+        return false;
+    };
+    let j = order.iter().rposition(|&v| v > order[i]).unwrap_or(i + 1);
+    order.swap(i, j);
+    order[i + 1..].reverse();
+    true
+}
+
+/// Keyword whose letters rank the columns in `order`: A over the column read first.
+fn order_keyword(order: &[usize]) -> String {
+    let mut key = vec!['A'; order.len()];
+    for (position, &col) in order.iter().enumerate() {
+        key[col] = (b'A' + position as u8) as char;
+    }
+    key.into_iter().collect()
+}
+
+/// Ciphertext-only attack on single columnar transposition. Each key width from 2 to
+/// `max_width` gets the column order with the best n-gram fitness: every order for
+/// narrow keys, hill-climbing with swaps and moves of single columns for wider ones.
+///
+/// `report` gets the best candidate of every width searched so far, best first. Runs
+/// until all widths are done or `stop` is set.
+pub fn solve_columnar(
+    ciphertext: &str,
+    max_width: usize,
+    fitness: NgramFitness,
+    model: &LanguageModel,
+    stop: &AtomicBool,
+    mut report: impl FnMut(&Vec<ColumnarCandidate>),
+) -> Result<()> {
+    let symbols: Vec<char> = ciphertext.chars().collect();
+    if text_letters(ciphertext).len() < 4 {
+        bail!("Columnar attack needs at least four letters");
+    }
+
+    // Non-letters travel with the columns but are left out of the score
+    let codes: Vec<Option<u8>> = symbols
+        .iter()
+        .map(|c| {
+            c.is_ascii_alphabetic()
+                .then(|| c.to_ascii_uppercase() as u8 - b'A')
+        })
+        .collect();
+    let mut letters = Vec::with_capacity(codes.len());
+    let mut score = |order: &[usize]| {
+        letters.clear();
+        letters.extend(
+            columnar_decrypt_symbols(&codes, order)
+                .into_iter()
+                .flatten(),
+        );
+        fitness.score(&letters, model)
+    };
+    let to_candidate = |order: &[usize], fitness: f64| ColumnarCandidate {
+        width: order.len(),
+        key: order_keyword(order),
+        fitness,
+        plaintext: columnar_decrypt_symbols(&symbols, order)
+            .into_iter()
+            .collect(),
+    };
+
+    let mut rng = XorShift::new();
+    let mut candidates: Vec<ColumnarCandidate> = Vec::new();
+    let mut publish = |candidates: &mut Vec<ColumnarCandidate>, best: ColumnarCandidate| {
+        candidates.retain(|c| c.width != best.width);
+        candidates.push(best);
+        candidates.sort_by(|a, b| b.fitness.total_cmp(&a.fitness));
+        report(candidates);
+    };
+
+    for width in 2..=max_width.min(symbols.len() / 2) {
+        let mut best_order: Vec<usize> = (0..width).collect();
+        let mut best = score(&best_order);
+
+        if width <= EXHAUSTIVE_COLUMNAR_WIDTH {
+            let mut order = best_order.clone();
+            let mut tried = 0usize;
+            while next_permutation(&mut order) {
+                let candidate = score(&order);
+                if candidate > best {
+                    best = candidate;
+                    best_order.clone_from(&order);
+                }
+                tried += 1;
+                if tried.is_multiple_of(1000) && stop.load(Ordering::Relaxed) {
+                    return Ok(());
+                }
+            }
+            publish(&mut candidates, to_candidate(&best_order, best));
+            continue;
+        }
+
+        for _ in 0..COLUMNAR_RESTARTS {
+            let mut order: Vec<usize> = (0..width).collect();
+            for i in (1..width).rev() {
+                order.swap(i, rng.below(i + 1));
+            }
+            let mut current = score(&order);
+
+            let mut improved = true;
+            while improved {
+                improved = false;
+                for i in 0..width {
+                    for j in 0..width {
+                        if i == j {
+                            continue;
+                        }
+                        for swap in [true, false] {
+                            let mut candidate = order.clone();
+                            if swap {
+                                candidate.swap(i, j);
+                            } else {
+                                let col = candidate.remove(i);
+                                candidate.insert(j, col);
+                            }
+                            let candidate_score = score(&candidate);
+                            if candidate_score > current {
+                                order = candidate;
+                                current = candidate_score;
+                                improved = true;
+                            }
+                        }
+                    }
+                }
+                if stop.load(Ordering::Relaxed) {
+                    return Ok(());
+                }
+            }
+
+            if current > best {
+                best = current;
+                best_order = order;
+                publish(&mut candidates, to_candidate(&best_order, best));
+            }
+        }
+        publish(&mut candidates, to_candidate(&best_order, best));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::classical_ciphers::vigenere_cipher_encrypt;
+    use crate::algorithms::fitness::Language;
+    use crate::algorithms::transposition::{columnar_encrypt, rail_fence_encrypt};
+
+    // The opening of A Tale of Two Cities, about 300 letters.
+    const PLAINTEXT: &str = "IT WAS THE BEST OF TIMES IT WAS THE WORST OF TIMES IT WAS THE AGE OF \
+        WISDOM IT WAS THE AGE OF FOOLISHNESS IT WAS THE EPOCH OF BELIEF IT WAS THE EPOCH OF \
+        INCREDULITY IT WAS THE SEASON OF LIGHT IT WAS THE SEASON OF DARKNESS IT WAS THE SPRING \
+        OF HOPE IT WAS THE WINTER OF DESPAIR WE HAD EVERYTHING BEFORE US WE HAD NOTHING BEFORE \
+        US WE WERE ALL GOING DIRECT TO HEAVEN WE WERE ALL GOING DIRECT THE OTHER WAY";
+
+    fn letters() -> String {
+        PLAINTEXT
+            .chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .collect()
+    }
+
+    #[test]
+    fn caesar_ranks_the_right_shift_first() {
+        let model = LanguageModel::built_in(Language::English);
+        let ciphertext = caesar_cipher_encrypt(PLAINTEXT.to_string(), 7);
+        let candidates = crack_caesar(&ciphertext, &model);
+        assert_eq!(candidates[0].shift, 7);
+        assert_eq!(candidates[0].plaintext, PLAINTEXT);
+    }
+
+    #[test]
+    fn vigenere_recovers_the_key() {
+        let model = LanguageModel::built_in(Language::English);
+        let ciphertext = vigenere_cipher_encrypt(PLAINTEXT.to_string(), "LEMON".to_string());
+        let analysis = break_vigenere(&ciphertext, None, &model).unwrap();
+        assert_eq!(analysis.length, 5);
+        assert_eq!(analysis.refined_key, "LEMON");
+        assert_eq!(analysis.plaintext, PLAINTEXT);
+    }
+
+    #[test]
+    fn rail_fence_recovers_rails_and_offset() {
+        let model = LanguageModel::built_in(Language::English);
+        let plaintext = letters();
+        let ciphertext = rail_fence_encrypt(&plaintext, 4, 2).unwrap();
+        let candidates = crack_rail_fence(&ciphertext, 10, NgramFitness::Quadgram, &model).unwrap();
+        assert_eq!((candidates[0].rails, candidates[0].offset), (4, 2));
+        assert_eq!(candidates[0].plaintext, plaintext);
+    }
+
+    #[test]
+    fn columnar_recovers_the_key_order() {
+        let model = LanguageModel::built_in(Language::English);
+        let plaintext = letters();
+        let ciphertext = columnar_encrypt(&plaintext, "ZEBRAS").unwrap();
+        let stop = AtomicBool::new(false);
+        let mut best = None;
+        solve_columnar(
+            &ciphertext,
+            7,
+            NgramFitness::Quadgram,
+            &model,
+            &stop,
+            |candidates| {
+                best = candidates.first().cloned();
+            },
+        )
+        .unwrap();
+        let best = best.unwrap();
+        assert_eq!(best.width, 6);
+        assert_eq!(best.plaintext, plaintext);
+        assert_eq!(columnar_encrypt(&plaintext, &best.key).unwrap(), ciphertext);
+    }
+}
@@ -0,0 +1,359 @@
+//! Enigma M3/M4 simulator.
+//!
+//! Rotors I-VIII, the M4 "Greek" rotors Beta and Gamma, reflectors B and C and their thin
+//! M4 variants, ring settings, start positions and a plugboard. Stepping follows the real
+//! machine including the middle rotor's double step.
+
+use anyhow::{Result, anyhow, bail};
+
+const ROTORS: [(&str, &str, &str); 10] = [
+    ("I", "EKMFLGDQVZNTOWYHXUSPAIBRCJ", "Q"),
+    ("II", "AJDKSIRUXBLHWTMCQGZNPYFVOE", "E"),
+    ("III", "BDFHJLCPRTXVZNYEIWGAKMUSQO", "V"),
+    ("IV", "ESOVPZJAYQUIRHXLNFTGKDCMWB", "J"),
+    ("V", "VZBRGITYUPSDNHLXAWMJQOFECK", "Z"),
+    ("VI", "JPGVOUMFYQBENHZRDKASXLICTW", "ZM"),
+    ("VII", "NZJHGRCXMYSWBOUFAIVLPEKQDT", "ZM"),
+    ("VIII", "FKQHTLXOCBJSPDZRAMEWNIUYGV", "ZM"),
+    ("BETA", "LEYJVCNIXWPBQMDRTAKZGFUHOS", ""),
+    ("GAMMA", "FSOKANUERHMBTIYCWLQPZXVGJD", ""),
+];
+
+const REFLECTORS: [(&str, &str, bool); 4] = [
+    ("B", "YRUHQSLDPXNGOKMIEBFZCWVJAT", false),
+    ("C", "FVPJIAOYEDRZXWGCTKUQSBNMHL", false),
+    ("B-THIN", "ENKQAUYWJICOPBLMDXZVFTHRGS", true),
+    ("C-THIN", "RDOBJNTKVEHMLFCWZAXGYIPSUQ", true),
+];
+
+fn letter_index(c: char) -> Option<u8> {
+    c.is_ascii_alphabetic()
+        .then(|| c.to_ascii_uppercase() as u8 - b'A')
+}
+
+fn index_letter(i: u8) -> char {
+    (b'A' + i % 26) as char
+}
+
+fn parse_wiring(wiring: &str) -> [u8; 26] {
+    let mut out = [0; 26];
+    for (i, c) in wiring.bytes().enumerate() {
+        out[i] = c - b'A';
+    }
+    out
+}
+
+#[derive(Clone, Debug)]
+pub struct Rotor {
+    pub name: String,
+    forward: [u8; 26],
+    backward: [u8; 26],
+    notches: Vec<u8>,
+    pub ring: u8,
+    pub position: u8,
+}
+
+impl Rotor {
+    pub fn new(name: &str, ring: u8, position: u8) -> Result<Self> {
+        let upper = name.trim().to_ascii_uppercase();
+        let (name, wiring, notches) = ROTORS
+            .iter()
+            .find(|(n, _, _)| *n == upper)
+            .ok_or_else(|| anyhow!("Unknown rotor: {}", name))?;
+
+        let forward = parse_wiring(wiring);
+        let mut backward = [0; 26];
+        for (i, &o) in forward.iter().enumerate() {
+            backward[o as usize] = i as u8;
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            forward,
+            backward,
+            notches: notches.bytes().map(|b| b - b'A').collect(),
+            ring: ring % 26,
+            position: position % 26,
+        })
+    }
+
+    pub fn is_greek(&self) -> bool {
+        self.notches.is_empty()
+    }
+
+    fn at_notch(&self) -> bool {
+        self.notches.contains(&self.position)
+    }
+
+    fn step(&mut self) {
+        self.position = (self.position + 1) % 26;
+    }
+
+    fn encode(&self, c: u8, wiring: &[u8; 26]) -> u8 {
+        let shift = (26 + self.position - self.ring) % 26;
+        (wiring[((c + shift) % 26) as usize] + 26 - shift) % 26
+    }
+
+    pub fn window(&self) -> char {
+        index_letter(self.position)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Reflector {
+    pub name: String,
+    wiring: [u8; 26],
+    pub thin: bool,
+}
+
+impl Reflector {
+    pub fn new(name: &str) -> Result<Self> {
+        let upper = name.trim().to_ascii_uppercase().replace(' ', "-");
+        let (name, wiring, thin) = REFLECTORS
+            .iter()
+            .find(|(n, _, _)| *n == upper)
+            .ok_or_else(|| anyhow!("Unknown reflector: {}", name))?;
+
+        Ok(Self {
+            name: name.to_string(),
+            wiring: parse_wiring(wiring),
+            thin: *thin,
+        })
+    }
+}
+
+/// Parses plugboard pairs such as `AV BS CG`. Each letter may only be used once.
+pub fn parse_plugboard(pairs: &str) -> Result<[u8; 26]> {
+    let mut board: [u8; 26] = std::array::from_fn(|i| i as u8);
+
+    for pair in pairs.split_whitespace() {
+        let letters: Vec<u8> = pair.chars().filter_map(letter_index).collect();
+        if letters.len() != 2 || letters[0] == letters[1] {
+            bail!("Invalid plugboard pair: {}", pair);
+        }
+        let (a, b) = (letters[0] as usize, letters[1] as usize);
+        if board[a] != a as u8 || board[b] != b as u8 {
+            bail!("Letter used twice on the plugboard: {}", pair);
+        }
+        board[a] = b as u8;
+        board[b] = a as u8;
+    }
+
+    Ok(board)
+}
+
+/// Parses ring settings or start positions given either as letters (`BUL`) or as
+/// 1-based numbers (`02 21 12`).
+pub fn parse_settings(settings: &str, count: usize) -> Result<Vec<u8>> {
+    let values: Vec<u8> = if settings.chars().any(|c| c.is_ascii_digit()) {
+        settings
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|s| !s.is_empty())
+            .map(|s| match s.parse::<u8>() {
+                Ok(n @ 1..=26) => Ok(n - 1),
+                _ => Err(anyhow!("Setting must be between 01 and 26: {}", s)),
+            })
+            .collect::<Result<_>>()?
+    } else {
+        settings.chars().filter_map(letter_index).collect()
+    };
+
+    if values.len() != count {
+        bail!("Expected {} settings, got {}", count, values.len());
+    }
+    Ok(values)
+}
+
+#[derive(Clone, Debug)]
+pub struct EnigmaMachine {
+    /// Rotors from left to right as seen by the operator. On an M4 the leftmost is Greek.
+    pub rotors: Vec<Rotor>,
+    pub reflector: Reflector,
+    plugboard: [u8; 26],
+}
+
+impl EnigmaMachine {
+    /// Builds a machine from operator-style settings, e.g. reflector `B`, rotors `II IV V`,
+    /// rings `BUL`, positions `BLA`, plugboard `AV BS CG`. Three rotors make an M3, four
+    /// (the first being Beta or Gamma, with a thin reflector) make an M4.
+    pub fn from_settings(
+        reflector: &str,
+        rotors: &str,
+        rings: &str,
+        positions: &str,
+        plugboard: &str,
+    ) -> Result<Self> {
+        let names: Vec<&str> = rotors.split_whitespace().collect();
+        let rings = parse_settings(rings, names.len())?;
+        let positions = parse_settings(positions, names.len())?;
+
+        let rotors = names
+            .iter()
+            .zip(rings.iter().zip(positions.iter()))
+            .map(|(name, (&ring, &pos))| Rotor::new(name, ring, pos))
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::new(
+            rotors,
+            Reflector::new(reflector)?,
+            parse_plugboard(plugboard)?,
+        )
+    }
+
+    pub fn new(rotors: Vec<Rotor>, reflector: Reflector, plugboard: [u8; 26]) -> Result<Self> {
+        match rotors.len() {
+            3 => {
+                if reflector.thin {
+                    bail!("Thin reflectors only fit the four-rotor M4");
+                }
+                if rotors.iter().any(Rotor::is_greek) {
+                    bail!("Beta and Gamma only fit the leftmost slot of an M4");
+                }
+            }
+            4 => {
+                if !reflector.thin {
+                    bail!("The M4 needs a thin reflector (B-thin or C-thin)");
+                }
+                if !rotors[0].is_greek() || rotors[1..].iter().any(Rotor::is_greek) {
+                    bail!("The M4's leftmost rotor must be Beta or Gamma, the others I-VIII");
+                }
+            }
+            n => bail!("Enigma takes 3 (M3) or 4 (M4) rotors, got {}", n),
+        }
+
+        let mut seen = Vec::new();
+        for rotor in &rotors {
+            if seen.contains(&rotor.name) {
+                bail!("Rotor {} is used twice", rotor.name);
+            }
+            seen.push(rotor.name.clone());
+        }
+
+        Ok(Self {
+            rotors,
+            reflector,
+            plugboard,
+        })
+    }
+
+    fn step(&mut self) {
+        let n = self.rotors.len();
+        let (left, middle, right) = (n - 3, n - 2, n - 1);
+
+        // The middle rotor's pawl engages its own notch too, which gives the double step
+        if self.rotors[middle].at_notch() {
+            self.rotors[middle].step();
+            self.rotors[left].step();
+        } else if self.rotors[right].at_notch() {
+            self.rotors[middle].step();
+        }
+        self.rotors[right].step();
+    }
+
+    /// Presses a key: steps the rotors, then returns the lamp that lights up.
+    /// Non-letters are ignored and do not move the rotors.
+    pub fn press(&mut self, c: char) -> Option<char> {
+        let mut signal = letter_index(c)?;
+        self.step();
+
+        signal = self.plugboard[signal as usize];
+        for rotor in self.rotors.iter().rev() {
+            signal = rotor.encode(signal, &rotor.forward);
+        }
+        signal = self.reflector.wiring[signal as usize];
+        for rotor in self.rotors.iter() {
+            signal = rotor.encode(signal, &rotor.backward);
+        }
+        signal = self.plugboard[signal as usize];
+
+        Some(index_letter(signal))
+    }
+
+    /// Runs text through the machine. Letters come out upper case; anything else is
+    /// passed through unchanged so word breaks and group spacing survive.
+    pub fn process(&mut self, text: &str) -> String {
+        text.chars().map(|c| self.press(c).unwrap_or(c)).collect()
+    }
+
+    /// Letters currently showing in the rotor windows, left to right.
+    pub fn windows(&self) -> String {
+        self.rotors.iter().map(Rotor::window).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn m3_rotors_i_ii_iii_at_aaa() {
+        let mut machine = EnigmaMachine::from_settings("B", "I II III", "AAA", "AAA", "").unwrap();
+        assert_eq!(machine.process("AAAAA"), "BDZGO");
+    }
+
+    #[test]
+    fn m3_middle_rotor_double_steps() {
+        // Rotor II's notch is at E: the middle rotor steps onto it, then steps again
+        // on the next key press and carries the left rotor with it.
+        let mut machine = EnigmaMachine::from_settings("B", "I II III", "AAA", "ADU", "").unwrap();
+        let windows: Vec<String> = (0..3)
+            .map(|_| {
+                machine.press('A');
+                machine.windows()
+            })
+            .collect();
+        assert_eq!(windows, ["ADV", "AEW", "BFX"]);
+    }
+
+    /// Operation Barbarossa, 7 July 1941, first part.
+    #[test]
+    fn m3_barbarossa_message() {
+        let mut machine = EnigmaMachine::from_settings(
+            "B",
+            "II IV V",
+            "02 21 12",
+            "BLA",
+            "AV BS CG DL FU HZ IN KM OW RX",
+        )
+        .unwrap();
+        let ciphertext = "EDPUD NRGYS ZRCXN UYTPO MRMBO FKTBZ REZKM LXLVE FGUEY SIOZV \
+                          EQMIK UBPMM YLKLT TDEIS MDICA GYKUA CTCDO MOHWX MUUIA UBSTS \
+                          LRNBZ SZWNR FXWFY SSXJZ VIJHI DISHP RKLKA YUPAD TXQSP INQMA \
+                          TLPIF SVKDA SCTAC DPBOP VHJK";
+        let plaintext: String = machine
+            .process(ciphertext)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        assert_eq!(
+            plaintext,
+            "AUFKLXABTEILUNGXVONXKURTINOWAXKURTINOWAXNORDWESTLXSEBEZXSEBEZXUAFFLIEGERSTRASZER\
+             IQTUNGXDUBROWKIXDUBROWKIXOPOTSCHKAXOPOTSCHKAXUMXEINSAQTDREINULLXUHRANGETRETENX\
+             ANGRIFFXINFXRGTX"
+        );
+    }
+
+    /// Signal from U-264 (Kapitänleutnant Looks), the first message broken by the M4
+    /// Project in 2006.
+    #[test]
+    fn m4_u264_message() {
+        let mut machine = EnigmaMachine::from_settings(
+            "B-thin",
+            "Beta II IV I",
+            "AAAV",
+            "VJNA",
+            "AT BL DF GJ HM NW OP QY RZ VX",
+        )
+        .unwrap();
+        let ciphertext = "NCZWVUSXPNYMINHZXMQXSFWXWLKJAHSHNMCOCCAKUQPMKCSMHKSEINJUSBLKIOSXCKUBHMLL\
+                          XCSJUSRRDVKOHULXWCCBGVLIYXEOAHXRHKKFVDREWEZLXOBAFGYUJQUKGRTVUKAMEURBVEKS\
+                          UHHVOYHABCJWMAKLFKLMYFVNRIZRVVRTKOFDANJMOLBGFFLEOPRGTFLVRHOWOPBEKVWMUQFM\
+                          PWPARMFHAGKXIIBG";
+        assert_eq!(
+            machine.process(ciphertext),
+            "VONVONJLOOKSJHFFTTTEINSEINSDREIZWOYYQNNSNEUNINHALTXXBEIANGRIFFUNTERWASSERGEDRUE\
+             CKTYWABOSXLETZTERGEGNERSTANDNULACHTDREINULUHRMARQUANTONJOTANEUNACHTSEYHSDREIYZ\
+             WOZWONULGRADYACHTSMYSTOSSENACHXEKNSVIERMBFAELLTYNNNNNNOOOVIERYSICHTEINSNULL"
+        );
+    }
+}
@@ -0,0 +1,381 @@
+//! Versioned binary container for encrypted files, with an optional ASCII armor.
+//!
+//! Layout (all lengths big-endian):
+//!
+//! | field     | size                    |
+//! |-----------|-------------------------|
+//! | magic     | 4 bytes, `CSKE`         |
+//! | version   | u8                      |
+//! | algorithm | u8, see [`Algorithm`]   |
+//! | kdf       | u8 id, u16 length, data |
+//! | recipient | u8 id, u16 length, data |
+//! | nonce     | u8 length, data         |
+//! | payload   | rest of the file        |
+//!
+//! Everything before the payload is the header, and the header bytes are passed to the
+//! AEAD as associated data, so changing any field makes decryption fail.
+
+use crate::algorithms::aead::AeadAlgorithm;
+use anyhow::{Context, Result, anyhow, bail};
+use base64::{Engine as _, engine::general_purpose};
+use std::io::Read;
+
+pub const MAGIC: &[u8; 4] = b"CSKE";
+
+/// Version written by this build; older versions stay readable.
+pub const FORMAT_VERSION: u8 = 1;
+
+const ARMOR_BEGIN: &str = "-----BEGIN CSK ENCRYPTED FILE-----";
+const ARMOR_END: &str = "-----END CSK ENCRYPTED FILE-----";
+const ARMOR_LINE_WIDTH: usize = 64;
+
+/// The AEAD that seals the payload, and whether it does so as one message or in
+/// STREAM segments. For streams the header nonce is the segment nonce prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Aes256Gcm,
+    Aes256GcmStream,
+    ChaCha20Poly1305,
+    ChaCha20Poly1305Stream,
+    XChaCha20Poly1305,
+    XChaCha20Poly1305Stream,
+}
+
+impl Algorithm {
+    pub fn new(aead: AeadAlgorithm, stream: bool) -> Self {
+        match (aead, stream) {
+            (AeadAlgorithm::Aes256Gcm, false) => Algorithm::Aes256Gcm,
+            (AeadAlgorithm::Aes256Gcm, true) => Algorithm::Aes256GcmStream,
+            (AeadAlgorithm::ChaCha20Poly1305, false) => Algorithm::ChaCha20Poly1305,
+            (AeadAlgorithm::ChaCha20Poly1305, true) => Algorithm::ChaCha20Poly1305Stream,
+            (AeadAlgorithm::XChaCha20Poly1305, false) => Algorithm::XChaCha20Poly1305,
+            (AeadAlgorithm::XChaCha20Poly1305, true) => Algorithm::XChaCha20Poly1305Stream,
+        }
+    }
+
+    pub fn aead(self) -> AeadAlgorithm {
+        match self {
+            Algorithm::Aes256Gcm | Algorithm::Aes256GcmStream => AeadAlgorithm::Aes256Gcm,
+            Algorithm::ChaCha20Poly1305 | Algorithm::ChaCha20Poly1305Stream => {
+                AeadAlgorithm::ChaCha20Poly1305
+            }
+            Algorithm::XChaCha20Poly1305 | Algorithm::XChaCha20Poly1305Stream => {
+                AeadAlgorithm::XChaCha20Poly1305
+            }
+        }
+    }
+
+    pub fn is_stream(self) -> bool {
+        matches!(
+            self,
+            Algorithm::Aes256GcmStream
+                | Algorithm::ChaCha20Poly1305Stream
+                | Algorithm::XChaCha20Poly1305Stream
+        )
+    }
+
+    fn id(self) -> u8 {
+        match self {
+            Algorithm::Aes256Gcm => 1,
+            Algorithm::Aes256GcmStream => 2,
+            Algorithm::ChaCha20Poly1305 => 3,
+            Algorithm::ChaCha20Poly1305Stream => 4,
+            Algorithm::XChaCha20Poly1305 => 5,
+            Algorithm::XChaCha20Poly1305Stream => 6,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self> {
+        match id {
+            1 => Ok(Algorithm::Aes256Gcm),
+            2 => Ok(Algorithm::Aes256GcmStream),
+            3 => Ok(Algorithm::ChaCha20Poly1305),
+            4 => Ok(Algorithm::ChaCha20Poly1305Stream),
+            5 => Ok(Algorithm::XChaCha20Poly1305),
+            6 => Ok(Algorithm::XChaCha20Poly1305Stream),
+            _ => bail!("Unknown algorithm ID {}", id),
+        }
+    }
+}
+
+/// How the file key is derived.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Kdf {
+    /// The key is stored as is, e.g. in a key file
+    None,
+    /// Argon2id (version 0x13) over the passphrase
+    Argon2id {
+        salt: Vec<u8>,
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+    },
+    Scrypt {
+        salt: Vec<u8>,
+        log_n: u8,
+        r: u32,
+        p: u32,
+    },
+    Pbkdf2Sha256 {
+        salt: Vec<u8>,
+        iterations: u32,
+    },
+}
+
+impl Kdf {
+    fn id(&self) -> u8 {
+        match self {
+            Kdf::None => 0,
+            Kdf::Argon2id { .. } => 1,
+            Kdf::Scrypt { .. } => 2,
+            Kdf::Pbkdf2Sha256 { .. } => 3,
+        }
+    }
+
+    /// Salt (length-prefixed) followed by the cost parameters.
+    fn params(&self) -> Vec<u8> {
+        let mut params = Vec::new();
+        let mut put_salt = |salt: &[u8]| {
+            params.push(salt.len() as u8);
+            params.extend_from_slice(salt);
+        };
+        match self {
+            Kdf::None => {}
+            Kdf::Argon2id {
+                salt,
+                memory_kib,
+                iterations,
+                parallelism,
+            } => {
+                put_salt(salt);
+                params.extend_from_slice(&memory_kib.to_be_bytes());
+                params.extend_from_slice(&iterations.to_be_bytes());
+                params.extend_from_slice(&parallelism.to_be_bytes());
+            }
+            Kdf::Scrypt { salt, log_n, r, p } => {
+                put_salt(salt);
+                params.push(*log_n);
+                params.extend_from_slice(&r.to_be_bytes());
+                params.extend_from_slice(&p.to_be_bytes());
+            }
+            Kdf::Pbkdf2Sha256 { salt, iterations } => {
+                put_salt(salt);
+                params.extend_from_slice(&iterations.to_be_bytes());
+            }
+        }
+        params
+    }
+
+    fn parse(id: u8, params: &[u8]) -> Result<Self> {
+        let mut rest = params;
+        let kdf = match id {
+            0 => Kdf::None,
+            1 => Kdf::Argon2id {
+                salt: read_salt(&mut rest)?,
+                memory_kib: read_u32(&mut rest)?,
+                iterations: read_u32(&mut rest)?,
+                parallelism: read_u32(&mut rest)?,
+            },
+            2 => Kdf::Scrypt {
+                salt: read_salt(&mut rest)?,
+                log_n: read_u8(&mut rest)?,
+                r: read_u32(&mut rest)?,
+                p: read_u32(&mut rest)?,
+            },
+            3 => Kdf::Pbkdf2Sha256 {
+                salt: read_salt(&mut rest)?,
+                iterations: read_u32(&mut rest)?,
+            },
+            _ => bail!("Unknown KDF ID {}", id),
+        };
+        if !rest.is_empty() {
+            bail!("Unexpected KDF parameters");
+        }
+        Ok(kdf)
+    }
+}
+
+fn read_u8(params: &mut &[u8]) -> Result<u8> {
+    let mut byte = [0u8; 1];
+    params
+        .read_exact(&mut byte)
+        .map_err(|_| anyhow!("KDF parameters are truncated"))?;
+    Ok(byte[0])
+}
+
+fn read_u32(params: &mut &[u8]) -> Result<u32> {
+    let mut bytes = [0u8; 4];
+    params
+        .read_exact(&mut bytes)
+        .map_err(|_| anyhow!("KDF parameters are truncated"))?;
+    Ok(u32::from_be_bytes(bytes))
+}
+
+fn read_salt(params: &mut &[u8]) -> Result<Vec<u8>> {
+    let mut salt = vec![0u8; read_u8(params)? as usize];
+    params
+        .read_exact(&mut salt)
+        .map_err(|_| anyhow!("KDF parameters are truncated"))?;
+    Ok(salt)
+}
+
+/// Who can recover the file key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Recipient {
+    /// Whoever holds the key file, or the passphrase the KDF starts from
+    KeyHolder,
+    /// The file key wrapped with RSA-OAEP (SHA-256) for one public key
+    RsaOaep(Vec<u8>),
+}
+
+impl Recipient {
+    fn id(&self) -> u8 {
+        match self {
+            Recipient::KeyHolder => 0,
+            Recipient::RsaOaep(_) => 1,
+        }
+    }
+
+    fn data(&self) -> &[u8] {
+        match self {
+            Recipient::KeyHolder => &[],
+            Recipient::RsaOaep(wrapped_key) => wrapped_key,
+        }
+    }
+
+    fn parse(id: u8, data: &[u8]) -> Result<Self> {
+        match id {
+            0 => Ok(Recipient::KeyHolder),
+            1 if !data.is_empty() => Ok(Recipient::RsaOaep(data.to_vec())),
+            1 => bail!("Recipient block is missing the wrapped key"),
+            _ => bail!("Unknown recipient ID {}", id),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub version: u8,
+    pub algorithm: Algorithm,
+    pub kdf: Kdf,
+    pub recipient: Recipient,
+    pub nonce: Vec<u8>,
+}
+
+impl Header {
+    /// Header for the current format version.
+    pub fn new(algorithm: Algorithm, kdf: Kdf, recipient: Recipient, nonce: Vec<u8>) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            algorithm,
+            kdf,
+            recipient,
+            nonce,
+        }
+    }
+
+    /// The encoded header, which is also the associated data for the payload.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(self.version);
+        bytes.push(self.algorithm.id());
+        for (id, data) in [
+            (self.kdf.id(), self.kdf.params()),
+            (self.recipient.id(), self.recipient.data().to_vec()),
+        ] {
+            let len = u16::try_from(data.len()).context("Header field is too long")?;
+            bytes.push(id);
+            bytes.extend_from_slice(&len.to_be_bytes());
+            bytes.extend_from_slice(&data);
+        }
+        let nonce_len = u8::try_from(self.nonce.len()).context("Nonce is too long")?;
+        bytes.push(nonce_len);
+        bytes.extend_from_slice(&self.nonce);
+        Ok(bytes)
+    }
+
+    /// Reads a header, leaving `reader` at the start of the payload. Returns the raw
+    /// header bytes too, for use as associated data.
+    pub fn read_from(reader: &mut impl Read) -> Result<(Self, Vec<u8>)> {
+        let mut raw = Vec::new();
+        let mut take = |len: usize| -> Result<Vec<u8>> {
+            let mut buf = vec![0u8; len];
+            reader
+                .read_exact(&mut buf)
+                .map_err(|_| anyhow!("Encrypted file header is truncated"))?;
+            raw.extend_from_slice(&buf);
+            Ok(buf)
+        };
+
+        if take(MAGIC.len())? != MAGIC {
+            bail!("Not an encrypted container file");
+        }
+        let version = take(1)?[0];
+        if version == 0 || version > FORMAT_VERSION {
+            bail!(
+                "Container format version {} is not supported (newest known is {})",
+                version,
+                FORMAT_VERSION
+            );
+        }
+        let algorithm = Algorithm::from_id(take(1)?[0])?;
+        let mut block = || -> Result<(u8, Vec<u8>)> {
+            let id = take(1)?[0];
+            let len = take(2)?;
+            let data = take(u16::from_be_bytes([len[0], len[1]]) as usize)?;
+            Ok((id, data))
+        };
+        let (kdf_id, kdf_params) = block()?;
+        let (recipient_id, recipient_data) = block()?;
+        let nonce_len = take(1)?[0] as usize;
+        let nonce = take(nonce_len)?;
+
+        let header = Header {
+            version,
+            algorithm,
+            kdf: Kdf::parse(kdf_id, &kdf_params)?,
+            recipient: Recipient::parse(recipient_id, &recipient_data)?,
+            nonce,
+        };
+        Ok((header, raw))
+    }
+}
+
+/// Whether `bytes` start like a container, binary or armored.
+pub fn is_envelope(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC) || is_armored(bytes)
+}
+
+pub fn is_armored(bytes: &[u8]) -> bool {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    bytes[start..].starts_with(ARMOR_BEGIN.as_bytes())
+}
+
+/// Wraps a binary container in base64 lines between BEGIN and END markers.
+pub fn armor(bytes: &[u8]) -> String {
+    let encoded = general_purpose::STANDARD.encode(bytes);
+    let mut text = format!("{}\n", ARMOR_BEGIN);
+    for line in encoded.as_bytes().chunks(ARMOR_LINE_WIDTH) {
+        text.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        text.push('\n');
+    }
+    text.push_str(ARMOR_END);
+    text.push('\n');
+    text
+}
+
+/// Recovers the binary container from its armored form.
+pub fn dearmor(text: &str) -> Result<Vec<u8>> {
+    let body = text
+        .trim()
+        .strip_prefix(ARMOR_BEGIN)
+        .and_then(|rest| rest.strip_suffix(ARMOR_END))
+        .context("Armor BEGIN/END lines are missing")?;
+    let compact: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+    general_purpose::STANDARD
+        .decode(compact)
+        .map_err(|e| anyhow!("Armored file is not valid base64: {}", e))
+}
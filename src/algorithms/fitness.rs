@@ -0,0 +1,395 @@
+//! Scoring helpers for deciding how much a candidate plaintext looks like a natural
+//! language: English built in, or a model built from any text.
+
+use anyhow::{Result, anyhow, bail};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+/// Relative frequencies of A..Z in typical English text.
+pub const ENGLISH_FREQUENCIES: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153,
+    0.00772, 0.04025, 0.02406, 0.06749, 0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056,
+    0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+
+pub fn letter_counts(text: &str) -> [usize; 26] {
+    let mut counts = [0; 26];
+    for c in text.chars().filter(|c| c.is_ascii_alphabetic()) {
+        counts[(c.to_ascii_uppercase() as u8 - b'A') as usize] += 1;
+    }
+    counts
+}
+
+/// Letter frequencies of a reference language, A..Z. Accented letters are left out, so
+/// the values need not sum to one.
+pub struct LetterFrequencies {
+    pub language: &'static str,
+    pub frequencies: [f64; 26],
+}
+
+pub const REFERENCE_FREQUENCIES: [LetterFrequencies; 4] = [
+    LetterFrequencies {
+        language: "English",
+        frequencies: ENGLISH_FREQUENCIES,
+    },
+    LetterFrequencies {
+        language: "German",
+        frequencies: [
+            0.06516, 0.01886, 0.02732, 0.05076, 0.16396, 0.01656, 0.03009, 0.04577, 0.06550,
+            0.00268, 0.01417, 0.03437, 0.02534, 0.09776, 0.02594, 0.00670, 0.00018, 0.07003,
+            0.07270, 0.06154, 0.04166, 0.00846, 0.01921, 0.00034, 0.00039, 0.01134,
+        ],
+    },
+    LetterFrequencies {
+        language: "French",
+        frequencies: [
+            0.07636, 0.00901, 0.03260, 0.03669, 0.14715, 0.01066, 0.00866, 0.00737, 0.07529,
+            0.00613, 0.00074, 0.05456, 0.02968, 0.07095, 0.05796, 0.02521, 0.01362, 0.06693,
+            0.07948, 0.07244, 0.06311, 0.01838, 0.00049, 0.00427, 0.00128, 0.00326,
+        ],
+    },
+    LetterFrequencies {
+        language: "Spanish",
+        frequencies: [
+            0.11525, 0.02215, 0.04019, 0.05010, 0.12181, 0.00692, 0.01768, 0.00703, 0.06247,
+            0.00493, 0.00011, 0.04967, 0.03157, 0.06712, 0.08683, 0.02510, 0.00877, 0.06871,
+            0.07977, 0.04632, 0.02927, 0.01138, 0.00017, 0.00215, 0.01008, 0.00467,
+        ],
+    },
+];
+
+/// Chi-squared statistic of letter counts against a frequency table. Lower is closer.
+pub fn chi_squared(counts: &[usize; 26], frequencies: &[f64; 26]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return f64::INFINITY;
+    }
+    let scale = total as f64 / frequencies.iter().sum::<f64>();
+
+    counts
+        .iter()
+        .zip(frequencies.iter())
+        .map(|(&observed, &freq)| {
+            let expected = freq * scale;
+            (observed as f64 - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+/// Probability that two letters drawn from `letters` (numbers 0-25) are the same.
+pub fn index_of_coincidence(letters: &[u8]) -> f64 {
+    let n = letters.len();
+    if n < 2 {
+        return 0.0;
+    }
+    let mut counts = [0usize; 26];
+    for &l in letters {
+        counts[l as usize] += 1;
+    }
+    let pairs: usize = counts.iter().map(|&c| c * c.saturating_sub(1)).sum();
+    pairs as f64 / (n * (n - 1)) as f64
+}
+
+/// Quadgram counts from English prose (Alice's Adventures in Wonderland and As You
+/// Like It, from the Canterbury corpus), one `QUAD count` pair per line.
+const ENGLISH_QUADGRAMS: &str = include_str!("data/english_quadgrams.txt");
+
+/// Fewest letters a corpus needs before its n-gram counts mean anything.
+const MIN_CORPUS_LETTERS: usize = 1000;
+
+fn ngram_index(q: &[u8]) -> usize {
+    q.iter().fold(0, |acc, &l| acc * 26 + l as usize)
+}
+
+/// The `NGRAM count` lines of a table, with the n-gram as numbers 0-25. Malformed
+/// lines are skipped.
+fn table_counts(table: &str) -> impl Iterator<Item = (Vec<u8>, f64)> + '_ {
+    table.lines().filter_map(|line| {
+        let (ngram, count) = line.trim().split_once(' ')?;
+        if !ngram.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        Some((text_letters(ngram), count.trim().parse().ok()?))
+    })
+}
+
+/// Log10-probabilities from counts, with unseen entries at 1% of a single occurrence.
+fn log_probabilities(counts: &[f64]) -> (Vec<f32>, f32) {
+    let total: f64 = counts.iter().sum::<f64>().max(1.0);
+    let floor = (0.01 / total).log10() as f32;
+    let log_probs = counts
+        .iter()
+        .map(|&count| {
+            if count > 0.0 {
+                (count / total).log10() as f32
+            } else {
+                floor
+            }
+        })
+        .collect();
+    (log_probs, floor)
+}
+
+/// Languages with a built-in model.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+}
+
+impl Language {
+    pub const ALL: [Self; 1] = [Self::English];
+
+    pub fn name(self) -> &'static str {
+        REFERENCE_FREQUENCIES[self as usize].language
+    }
+
+    fn quadgram_table(self) -> &'static str {
+        match self {
+            Self::English => ENGLISH_QUADGRAMS,
+        }
+    }
+}
+
+/// What plaintext in one language looks like: letter frequencies plus pair and
+/// quadgram log-probabilities. Solvers score candidate decryptions against it, so
+/// the same attack works on German or French once a model built from their text is
+/// chosen.
+pub struct LanguageModel {
+    name: String,
+    frequencies: [f64; 26],
+    bigrams: Vec<f32>,
+    quadgrams: Vec<f32>,
+    quadgram_floor: f32,
+}
+
+impl LanguageModel {
+    /// Builds a model from `NGRAM count` lines: single letters give the letter
+    /// frequencies and four letters the quadgrams; other lengths are ignored. Pairs
+    /// are estimated from the quadgrams (each adds its count to its three pairs), and
+    /// so are the letter frequencies when the table has none.
+    pub fn from_table(name: &str, table: &str) -> Result<Self> {
+        let mut monograms = [0.0; 26];
+        let mut bigrams = vec![0.0; 26 * 26];
+        let mut quadgrams = vec![0.0; 26usize.pow(4)];
+        let mut letters_from_quadgrams = [0.0; 26];
+        for (ngram, count) in table_counts(table) {
+            match ngram.len() {
+                1 => monograms[ngram[0] as usize] += count,
+                4 => {
+                    quadgrams[ngram_index(&ngram)] += count;
+                    for pair in ngram.windows(2) {
+                        bigrams[ngram_index(pair)] += count;
+                    }
+                    for &letter in &ngram {
+                        letters_from_quadgrams[letter as usize] += count;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if quadgrams.iter().all(|&count| count == 0.0) {
+            bail!("No `QUAD count` lines found in the table");
+        }
+        if monograms.iter().all(|&count| count == 0.0) {
+            monograms = letters_from_quadgrams;
+        }
+
+        let (quadgrams, quadgram_floor) = log_probabilities(&quadgrams);
+        Ok(Self {
+            name: name.to_string(),
+            frequencies: normalized(&monograms),
+            bigrams: log_probabilities(&bigrams).0,
+            quadgrams,
+            quadgram_floor,
+        })
+    }
+
+    /// The built-in model of `language`, parsed on first use. Letter frequencies come
+    /// from the reference tables rather than the quadgram corpus.
+    pub fn built_in(language: Language) -> Arc<Self> {
+        static MODELS: [OnceLock<Arc<LanguageModel>>; Language::ALL.len()] =
+            [const { OnceLock::new() }; Language::ALL.len()];
+        MODELS[language as usize]
+            .get_or_init(|| {
+                let mut model = Self::from_table(language.name(), language.quadgram_table())
+                    .expect("built-in quadgram tables are well formed");
+                model.frequencies =
+                    normalized(&REFERENCE_FREQUENCIES[language as usize].frequencies);
+                Arc::new(model)
+            })
+            .clone()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Share of each letter A..Z, summing to one.
+    pub fn frequencies(&self) -> &[f64; 26] {
+        &self.frequencies
+    }
+
+    /// Index of coincidence expected of plaintext in this language.
+    pub fn expected_ioc(&self) -> f64 {
+        self.frequencies.iter().map(|p| p * p).sum()
+    }
+
+    /// Chi-squared statistic of the letters in `text` against this language. Lower is
+    /// closer.
+    pub fn chi_squared(&self, text: &str) -> f64 {
+        chi_squared(&letter_counts(text), &self.frequencies)
+    }
+
+    /// Quadgram score of letters given as 0-25. Higher (closer to zero) is more like
+    /// the language.
+    pub fn score_quadgrams(&self, letters: &[u8]) -> f64 {
+        letters
+            .windows(4)
+            .map(|q| self.quadgrams[ngram_index(q)] as f64)
+            .sum()
+    }
+
+    /// Pair score of letters given as 0-25. Coarser than quadgrams, but steadier on
+    /// short texts where few quadgrams are seen.
+    pub fn score_bigrams(&self, letters: &[u8]) -> f64 {
+        letters
+            .windows(2)
+            .map(|pair| self.bigrams[ngram_index(pair)] as f64)
+            .sum()
+    }
+
+    /// Average quadgram score per quadgram, comparable across texts of different length.
+    pub fn score_per_quadgram(&self, text: &str) -> f64 {
+        let letters = text_letters(text);
+        if letters.len() < 4 {
+            return self.quadgram_floor as f64;
+        }
+        self.score_quadgrams(&letters) / (letters.len() - 3) as f64
+    }
+}
+
+/// Scales counts so they sum to one; letters never seen get a small share so chi-squared
+/// stays finite.
+fn normalized(counts: &[f64; 26]) -> [f64; 26] {
+    let total = counts.iter().sum::<f64>().max(f64::MIN_POSITIVE);
+    counts.map(|count| (count / total).max(1e-5))
+}
+
+/// The letters of `text` as numbers 0-25, everything else dropped.
+pub fn text_letters(text: &str) -> Vec<u8> {
+    text.chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase() as u8 - b'A')
+        .collect()
+}
+
+/// Spells a letter with A-Z only, the way classical ciphers write it: accents are
+/// dropped and German umlauts and ß written out (Ä -> AE, ß -> SS).
+fn fold_letter(c: char) -> &'static str {
+    const LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    // ß upper-cases to "SS", so taking the first upper-case char would keep one S
+    if c == 'ß' || c == 'ẞ' {
+        return "SS";
+    }
+    match c.to_uppercase().next().unwrap_or(c) {
+        c @ 'A'..='Z' => &LETTERS[(c as u8 - b'A') as usize..][..1],
+        'Ä' => "AE",
+        'Ö' => "OE",
+        'Ü' => "UE",
+        'Æ' => "AE",
+        'Œ' => "OE",
+        'À' | 'Á' | 'Â' | 'Ã' | 'Å' => "A",
+        'Ç' => "C",
+        'È' | 'É' | 'Ê' | 'Ë' => "E",
+        'Ì' | 'Í' | 'Î' | 'Ï' => "I",
+        'Ñ' => "N",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' => "O",
+        'Ù' | 'Ú' | 'Û' => "U",
+        'Ý' | 'Ÿ' => "Y",
+        _ => "",
+    }
+}
+
+/// Turns any text into an `NGRAM count` table for [`LanguageModel::from_table`]:
+/// letter counts first, then quadgram counts, most common first. Quadgrams run across
+/// spaces and punctuation, as they do in ciphertext with the word breaks removed.
+pub fn build_ngram_table(corpus: &str) -> Result<String> {
+    let letters: Vec<u8> = corpus
+        .chars()
+        .flat_map(|c| fold_letter(c).bytes())
+        .map(|b| b - b'A')
+        .collect();
+    if letters.len() < MIN_CORPUS_LETTERS {
+        bail!(
+            "The corpus has {} letters; at least {} are needed",
+            letters.len(),
+            MIN_CORPUS_LETTERS
+        );
+    }
+
+    let mut monograms = [0usize; 26];
+    for &letter in &letters {
+        monograms[letter as usize] += 1;
+    }
+    let mut quadgrams: HashMap<&[u8], usize> = HashMap::new();
+    for quad in letters.windows(4) {
+        *quadgrams.entry(quad).or_default() += 1;
+    }
+
+    let as_text = |ngram: &[u8]| {
+        ngram
+            .iter()
+            .map(|&l| (b'A' + l) as char)
+            .collect::<String>()
+    };
+    let mut lines: Vec<String> = monograms
+        .iter()
+        .enumerate()
+        .filter(|&(_, &count)| count > 0)
+        .map(|(letter, count)| format!("{} {}", as_text(&[letter as u8]), count))
+        .collect();
+    let mut quadgrams: Vec<(&[u8], usize)> = quadgrams.into_iter().collect();
+    quadgrams.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    lines.extend(
+        quadgrams
+            .into_iter()
+            .map(|(quad, count)| format!("{} {}", as_text(quad), count)),
+    );
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Whether every non-empty line of `text` is an `NGRAM count` pair, i.e. the file is a
+/// table rather than prose.
+fn is_ngram_table(text: &str) -> bool {
+    let mut lines = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+    lines.peek().is_some() && lines.count() == table_counts(text).count()
+}
+
+/// Reads an n-gram table from `path`, counting it first when the file is plain text.
+pub fn read_ngram_table(path: &str) -> Result<String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| anyhow!("Could not read {}: {}", path, e))?;
+    if is_ngram_table(&text) {
+        Ok(text)
+    } else {
+        build_ngram_table(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fold_letter_writes_out_umlauts_and_sharp_s() {
+        let folded: String = "Straße GROẞ Bär Über café"
+            .chars()
+            .map(fold_letter)
+            .collect();
+        assert_eq!(folded, "STRASSEGROSSBAERUEBERCAFE");
+    }
+}
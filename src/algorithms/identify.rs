@@ -0,0 +1,345 @@
+//! Guesses which cipher family produced an unknown ciphertext.
+//!
+//! Every check is a cheap statistic (character set, index of coincidence, digraph
+//! properties, length divisibility, periodicity), so the ranking is a hint for which
+//! attack to try first rather than a verdict.
+
+use crate::algorithms::cryptanalysis::{
+    MAX_VIGENERE_KEY_LENGTH, best_key_length, crack_caesar, vigenere_key_lengths,
+};
+use crate::algorithms::fitness::{LanguageModel, index_of_coincidence, text_letters};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CipherFamily {
+    Transposition,
+    Caesar,
+    Monoalphabetic,
+    Polyalphabetic,
+    Playfair,
+    Hill,
+    Bifid,
+    Adfgvx,
+    PolybiusDigits,
+    StraddlingCheckerboard,
+    Baconian,
+    Morse,
+    Hex,
+    Base64,
+}
+
+impl CipherFamily {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Transposition => "Transposition",
+            Self::Caesar => "Caesar / shifted alphabet",
+            Self::Monoalphabetic => "Monoalphabetic substitution",
+            Self::Polyalphabetic => "Polyalphabetic (Vigenere family)",
+            Self::Playfair => "Playfair",
+            Self::Hill => "Hill",
+            Self::Bifid => "Bifid",
+            Self::Adfgvx => "ADFGX / ADFGVX",
+            Self::PolybiusDigits => "Polybius square / Nihilist (digit pairs)",
+            Self::StraddlingCheckerboard => "Straddling checkerboard",
+            Self::Baconian => "Baconian",
+            Self::Morse => "Morse code",
+            Self::Hex => "Hex encoding",
+            Self::Base64 => "Base64 encoding",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CipherGuess {
+    pub family: CipherFamily,
+    /// Rough confidence from 0 to 1
+    pub score: f64,
+    pub reasons: Vec<String>,
+}
+
+fn guess(family: CipherFamily, score: f64, reasons: Vec<String>) -> CipherGuess {
+    CipherGuess {
+        family,
+        score,
+        reasons,
+    }
+}
+
+/// Fewer letters than this make letter statistics meaningless.
+const MIN_LETTERS: usize = 20;
+
+/// Ranks the cipher families that could have produced `ciphertext`, most likely first,
+/// assuming a plaintext in `model`'s language. Families with no supporting evidence
+/// are left out.
+pub fn identify_cipher(ciphertext: &str, model: &LanguageModel) -> Vec<CipherGuess> {
+    let symbols: Vec<char> = ciphertext.chars().filter(|c| !c.is_whitespace()).collect();
+    if symbols.is_empty() {
+        return Vec::new();
+    }
+
+    let mut guesses = encoding_guesses(&symbols);
+    guesses.extend(symbol_set_guesses(&symbols));
+
+    let letters = text_letters(ciphertext);
+    if letters.len() * 10 >= symbols.len() * 9 && letters.len() >= MIN_LETTERS {
+        guesses.extend(letter_guesses(ciphertext, &letters, model));
+    }
+
+    guesses.sort_by(|a, b| b.score.total_cmp(&a.score));
+    guesses
+}
+
+fn encoding_guesses(symbols: &[char]) -> Vec<CipherGuess> {
+    let mut guesses = Vec::new();
+    let has_digit = symbols.iter().any(|c| c.is_ascii_digit());
+
+    if symbols.iter().all(|c| c.is_ascii_hexdigit()) && symbols.len().is_multiple_of(2) {
+        let has_hex_letter = symbols.iter().any(|c| c.is_ascii_alphabetic());
+        let score = if has_digit && has_hex_letter {
+            0.95
+        } else {
+            0.5
+        };
+        guesses.push(guess(
+            CipherFamily::Hex,
+            score,
+            vec![format!(
+                "Only 0-9 and a-f, even length ({} digits)",
+                symbols.len()
+            )],
+        ));
+    }
+
+    let body: Vec<&char> = symbols.iter().filter(|&&c| c != '=').collect();
+    let padding = symbols.len() - body.len();
+    let padded_at_end = symbols[symbols.len() - padding..].iter().all(|&c| c == '=');
+    if body
+        .iter()
+        .all(|c| c.is_ascii_alphanumeric() || **c == '+' || **c == '/')
+        && padded_at_end
+        && padding <= 2
+        && symbols.len().is_multiple_of(4)
+    {
+        let mixed_case = symbols.iter().any(|c| c.is_ascii_lowercase())
+            && symbols.iter().any(|c| c.is_ascii_uppercase());
+        let extras = has_digit || padding > 0 || body.iter().any(|c| **c == '+' || **c == '/');
+        let score = match (mixed_case, extras) {
+            (true, true) => 0.9,
+            (true, false) => 0.2,
+            _ => 0.15,
+        };
+        guesses.push(guess(
+            CipherFamily::Base64,
+            score,
+            vec![format!(
+                "Base64 alphabet, length {} is a multiple of 4",
+                symbols.len()
+            )],
+        ));
+    }
+    guesses
+}
+
+fn symbol_set_guesses(symbols: &[char]) -> Vec<CipherGuess> {
+    let mut guesses = Vec::new();
+    let upper: Vec<char> = symbols.iter().map(|c| c.to_ascii_uppercase()).collect();
+    let only = |set: &str| upper.iter().all(|&c| set.contains(c));
+
+    if only(".-/|X") && upper.iter().any(|&c| c == '.' || c == '-') {
+        guesses.push(guess(
+            CipherFamily::Morse,
+            0.95,
+            vec![String::from("Only dots, dashes and separators")],
+        ));
+    }
+
+    let mut distinct = upper.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    if distinct.len() == 2 && upper.iter().all(|c| c.is_ascii_alphabetic()) {
+        let mut reasons = vec![format!(
+            "Only two symbols: {} and {}",
+            distinct[0], distinct[1]
+        )];
+        let mut score = 0.7;
+        if upper.len().is_multiple_of(5) {
+            reasons.push(format!(
+                "Length {} splits into 5-symbol groups",
+                upper.len()
+            ));
+            score = 0.95;
+        }
+        guesses.push(guess(CipherFamily::Baconian, score, reasons));
+    }
+
+    if only("ADFGVX") && upper.len().is_multiple_of(2) && distinct.len() > 2 {
+        let letters = if only("ADFGX") { "ADFGX" } else { "ADFGVX" };
+        guesses.push(guess(
+            CipherFamily::Adfgvx,
+            0.95,
+            vec![
+                format!("Only the letters {}", letters),
+                String::from("Even length: every plaintext letter became a pair"),
+            ],
+        ));
+    }
+
+    if upper.iter().all(|c| c.is_ascii_digit()) {
+        if only("12345") && upper.len().is_multiple_of(2) {
+            guesses.push(guess(
+                CipherFamily::PolybiusDigits,
+                0.85,
+                vec![String::from(
+                    "Only digits 1-5, in an even count: square coordinates",
+                )],
+            ));
+        } else {
+            guesses.push(guess(
+                CipherFamily::StraddlingCheckerboard,
+                0.6,
+                vec![String::from("Digits 0-9 of mixed group sizes")],
+            ));
+            guesses.push(guess(
+                CipherFamily::PolybiusDigits,
+                0.3,
+                vec![String::from(
+                    "Digits only; Nihilist numbers go beyond 1-5 once the key is added",
+                )],
+            ));
+        }
+    }
+    guesses
+}
+
+fn letter_guesses(text: &str, letters: &[u8], model: &LanguageModel) -> Vec<CipherGuess> {
+    let mut guesses = Vec::new();
+    let n = letters.len();
+    let ioc = index_of_coincidence(letters);
+    // Chi-squared grows with the text, so compare it per letter
+    let chi = model.chi_squared(text) / n as f64;
+    let ioc_note = format!(
+        "IoC {:.4} ({} {:.4}, random 0.0385)",
+        ioc,
+        model.name(),
+        model.expected_ioc()
+    );
+    let mut present = [false; 26];
+    for &l in letters {
+        present[l as usize] = true;
+    }
+    let distinct = present.iter().filter(|&&p| p).count();
+    if distinct < 10 {
+        // Two-symbol or ADFGVX-style alphabets, handled by the symbol set checks
+        return guesses;
+    }
+
+    if ioc >= 0.058 {
+        if chi < 0.4 {
+            guesses.push(guess(
+                CipherFamily::Transposition,
+                0.9,
+                vec![
+                    ioc_note.clone(),
+                    format!(
+                        "Letter frequencies match {}: letters moved, not changed",
+                        model.name()
+                    ),
+                ],
+            ));
+        } else {
+            let shifted = crack_caesar(text, model)
+                .first()
+                .map(|best| best.chi_squared / n as f64)
+                .unwrap_or(f64::INFINITY);
+            if shifted < 0.4 {
+                guesses.push(guess(
+                    CipherFamily::Caesar,
+                    0.9,
+                    vec![
+                        ioc_note.clone(),
+                        format!("{} frequencies after shifting the alphabet", model.name()),
+                    ],
+                ));
+            }
+            guesses.push(guess(
+                CipherFamily::Monoalphabetic,
+                if shifted < 0.4 { 0.6 } else { 0.85 },
+                vec![
+                    ioc_note.clone(),
+                    format!("{}-like IoC but the letters are substituted", model.name()),
+                ],
+            ));
+        }
+    }
+
+    if ioc < 0.058 {
+        let lengths = vigenere_key_lengths(text, MAX_VIGENERE_KEY_LENGTH);
+        let period = best_key_length(&lengths).filter(|&length| length > 1);
+        match period.and_then(|p| lengths.get(p - 1)) {
+            Some(candidate) if candidate.ioc >= 0.056 => guesses.push(guess(
+                CipherFamily::Polyalphabetic,
+                0.85,
+                vec![
+                    ioc_note.clone(),
+                    format!(
+                        "Period {} brings the column IoC up to {:.4}",
+                        candidate.length, candidate.ioc
+                    ),
+                ],
+            )),
+            _ => guesses.push(guess(
+                CipherFamily::Polyalphabetic,
+                0.35,
+                vec![
+                    ioc_note.clone(),
+                    String::from("Flat frequencies but no clear period (autokey, running key?)"),
+                ],
+            )),
+        }
+
+        let even = n.is_multiple_of(2);
+        let mut reasons = vec![ioc_note.clone()];
+        if even {
+            reasons.push(String::from("Even length"));
+        }
+        if n.is_multiple_of(3) {
+            reasons.push(String::from("Length divisible by 3"));
+        }
+        if even || n.is_multiple_of(3) {
+            let score = if distinct == 26 { 0.5 } else { 0.4 };
+            guesses.push(guess(CipherFamily::Hill, score, reasons));
+        }
+    }
+
+    if n.is_multiple_of(2) && !present[(b'J' - b'A') as usize] {
+        let pairs = letters.chunks_exact(2);
+        let doubled = pairs.clone().any(|pair| pair[0] == pair[1]);
+        if !doubled {
+            // Chance that random pairs with this IoC would avoid doubles by luck
+            let luck = (1.0 - ioc).powi(pairs.len() as i32);
+            guesses.push(guess(
+                CipherFamily::Playfair,
+                (0.95 * (1.0 - luck)).max(0.3),
+                vec![
+                    String::from("Even length, no J"),
+                    format!(
+                        "None of the {} pairs is a doubled letter (chance {:.0}%)",
+                        pairs.len(),
+                        luck * 100.0
+                    ),
+                ],
+            ));
+        }
+    }
+
+    if distinct <= 25 && (0.045..0.062).contains(&ioc) {
+        guesses.push(guess(
+            CipherFamily::Bifid,
+            0.35,
+            vec![
+                format!("Only {} distinct letters, fits a 5x5 square", distinct),
+                ioc_note,
+            ],
+        ));
+    }
+    guesses
+}
@@ -0,0 +1,275 @@
+//! Passphrase-based key derivation for the encrypted container: Argon2id by default,
+//! scrypt and PBKDF2-HMAC-SHA256 as alternatives. Salt and costs travel in the
+//! container header so decryption needs only the passphrase.
+
+use crate::algorithms::aead::KEY_LEN;
+use crate::algorithms::envelope::Kdf;
+use aes_gcm::aead::OsRng;
+use aes_gcm::aead::rand_core::RngCore;
+use anyhow::{Result, anyhow, bail};
+use sha2::Sha256;
+
+const SALT_LEN: usize = 16;
+
+/// Argon2id costs: 64 MiB, 3 passes, one lane (RFC 9106's second recommendation,
+/// with a single lane since the derivation runs on one thread).
+const ARGON2_MEMORY_KIB: u32 = 64 * 1024;
+const ARGON2_ITERATIONS: u32 = 3;
+const ARGON2_PARALLELISM: u32 = 1;
+
+/// scrypt costs: N = 2^17, r = 8, p = 1 (128 MiB).
+const SCRYPT_LOG_N: u8 = 17;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// OWASP's current recommendation for PBKDF2-HMAC-SHA256.
+const PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// Headers asking for more than this many times the default memory or work are
+/// refused when decrypting, so a crafted file cannot make the derivation take all
+/// memory or run for hours.
+const MAX_COST_FACTOR: u64 = 4;
+
+/// More Argon2id lanes than this are refused. Lanes split the memory rather than add
+/// to it, but no tool uses anywhere near this many.
+const MAX_ARGON2_PARALLELISM: u32 = 16;
+
+/// Argon2id memory in KiB and work in KiB times passes.
+const MAX_ARGON2_MEMORY_KIB: u64 = MAX_COST_FACTOR * ARGON2_MEMORY_KIB as u64;
+const MAX_ARGON2_WORK: u64 = MAX_ARGON2_MEMORY_KIB * ARGON2_ITERATIONS as u64;
+
+/// scrypt memory in bytes (128 r N) and work in the same unit (128 r N p).
+const MAX_SCRYPT_MEMORY: u64 = MAX_COST_FACTOR * 128 * SCRYPT_R as u64 * (1 << SCRYPT_LOG_N);
+const MAX_SCRYPT_WORK: u64 = MAX_SCRYPT_MEMORY * SCRYPT_P as u64;
+
+const MAX_PBKDF2_ITERATIONS: u32 = MAX_COST_FACTOR as u32 * PBKDF2_ITERATIONS;
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KdfAlgorithm {
+    #[default]
+    Argon2id,
+    Scrypt,
+    Pbkdf2Sha256,
+}
+
+impl KdfAlgorithm {
+    pub const ALL: [KdfAlgorithm; 3] = [
+        KdfAlgorithm::Argon2id,
+        KdfAlgorithm::Scrypt,
+        KdfAlgorithm::Pbkdf2Sha256,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KdfAlgorithm::Argon2id => "Argon2id",
+            KdfAlgorithm::Scrypt => "scrypt",
+            KdfAlgorithm::Pbkdf2Sha256 => "PBKDF2-HMAC-SHA256",
+        }
+    }
+
+    /// Parameters for a new file: a fresh random salt and the default costs.
+    pub fn new_kdf(self) -> Kdf {
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        match self {
+            KdfAlgorithm::Argon2id => Kdf::Argon2id {
+                salt,
+                memory_kib: ARGON2_MEMORY_KIB,
+                iterations: ARGON2_ITERATIONS,
+                parallelism: ARGON2_PARALLELISM,
+            },
+            KdfAlgorithm::Scrypt => Kdf::Scrypt {
+                salt,
+                log_n: SCRYPT_LOG_N,
+                r: SCRYPT_R,
+                p: SCRYPT_P,
+            },
+            KdfAlgorithm::Pbkdf2Sha256 => Kdf::Pbkdf2Sha256 {
+                salt,
+                iterations: PBKDF2_ITERATIONS,
+            },
+        }
+    }
+}
+
+/// Refuses parameters over the caps, before any memory is allocated for them.
+fn check_costs(kdf: &Kdf) -> Result<()> {
+    match kdf {
+        Kdf::None => {}
+        Kdf::Argon2id {
+            memory_kib,
+            iterations,
+            parallelism,
+            ..
+        } => {
+            let memory = *memory_kib as u64;
+            if memory > MAX_ARGON2_MEMORY_KIB
+                || memory * *iterations as u64 > MAX_ARGON2_WORK
+                || *parallelism > MAX_ARGON2_PARALLELISM
+            {
+                bail!("Argon2id costs in the header are unreasonably high");
+            }
+        }
+        Kdf::Scrypt { log_n, r, p, .. } => {
+            let too_costly = *log_n >= 64 || {
+                let memory = 128 * *r as u128 * (1u128 << *log_n);
+                memory > MAX_SCRYPT_MEMORY as u128 || memory * *p as u128 > MAX_SCRYPT_WORK as u128
+            };
+            if too_costly {
+                bail!("scrypt costs in the header are unreasonably high");
+            }
+        }
+        Kdf::Pbkdf2Sha256 { iterations, .. } => {
+            if *iterations == 0 || *iterations > MAX_PBKDF2_ITERATIONS {
+                bail!("PBKDF2 iteration count in the header is out of range");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Derives the 256-bit file key from `passphrase` with the header's KDF parameters.
+pub fn derive_key(kdf: &Kdf, passphrase: &str) -> Result<[u8; KEY_LEN]> {
+    if passphrase.is_empty() {
+        bail!("Passphrase is empty");
+    }
+    check_costs(kdf)?;
+    let mut key = [0u8; KEY_LEN];
+    match kdf {
+        Kdf::None => bail!("File was not encrypted with a passphrase"),
+        Kdf::Argon2id {
+            salt,
+            memory_kib,
+            iterations,
+            parallelism,
+        } => {
+            let params = argon2::Params::new(*memory_kib, *iterations, *parallelism, Some(KEY_LEN))
+                .map_err(|e| anyhow!("Invalid Argon2id parameters: {}", e))?;
+            argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+                .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                .map_err(|e| anyhow!("Argon2id failed: {}", e))?;
+        }
+        Kdf::Scrypt { salt, log_n, r, p } => {
+            let params = scrypt::Params::new(*log_n, *r, *p, KEY_LEN)
+                .map_err(|e| anyhow!("Invalid scrypt parameters: {}", e))?;
+            scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+                .map_err(|e| anyhow!("scrypt failed: {}", e))?;
+        }
+        Kdf::Pbkdf2Sha256 { salt, iterations } => {
+            pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, *iterations, &mut key);
+        }
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Parameters cheap enough to run in a test.
+    fn cheap_kdfs() -> [Kdf; 3] {
+        let salt = b"NaCl salt bytes!".to_vec();
+        [
+            Kdf::Argon2id {
+                salt: salt.clone(),
+                memory_kib: 64,
+                iterations: 1,
+                parallelism: 1,
+            },
+            Kdf::Scrypt {
+                salt: salt.clone(),
+                log_n: 4,
+                r: 8,
+                p: 1,
+            },
+            Kdf::Pbkdf2Sha256 {
+                salt,
+                iterations: 1000,
+            },
+        ]
+    }
+
+    #[test]
+    fn derivation_depends_only_on_passphrase_and_parameters() {
+        for kdf in cheap_kdfs() {
+            let key = derive_key(&kdf, "correct horse").unwrap();
+            assert_eq!(derive_key(&kdf, "correct horse").unwrap(), key);
+            assert_ne!(derive_key(&kdf, "correct horse!").unwrap(), key);
+        }
+    }
+
+    #[test]
+    fn scrypt_matches_rfc_7914() {
+        let kdf = Kdf::Scrypt {
+            salt: b"NaCl".to_vec(),
+            log_n: 10,
+            r: 8,
+            p: 16,
+        };
+        // First half of the 64-byte test vector
+        assert_eq!(
+            hex(&derive_key(&kdf, "password").unwrap()),
+            "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162"
+        );
+    }
+
+    #[test]
+    fn pbkdf2_matches_rfc_7914() {
+        let kdf = Kdf::Pbkdf2Sha256 {
+            salt: b"salt".to_vec(),
+            iterations: 1,
+        };
+        // First half of the 64-byte test vector
+        assert_eq!(
+            hex(&derive_key(&kdf, "passwd").unwrap()),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+        );
+    }
+
+    #[test]
+    fn default_costs_are_accepted() {
+        for algorithm in KdfAlgorithm::ALL {
+            check_costs(&algorithm.new_kdf()).unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_costs_over_the_caps() {
+        let salt = vec![0u8; SALT_LEN];
+        let argon2 = |memory_kib, iterations, parallelism| Kdf::Argon2id {
+            salt: salt.clone(),
+            memory_kib,
+            iterations,
+            parallelism,
+        };
+        let scrypt = |log_n, r, p| Kdf::Scrypt {
+            salt: salt.clone(),
+            log_n,
+            r,
+            p,
+        };
+        let too_costly = [
+            argon2(2 * 1024 * 1024, 1, 1),
+            argon2(ARGON2_MEMORY_KIB, 100, 1),
+            argon2(64 * 1024, u32::MAX, 1),
+            argon2(ARGON2_MEMORY_KIB, 1, 1000),
+            scrypt(20, 8, 1),
+            scrypt(4, 1 << 20, 1),
+            scrypt(17, 8, 100),
+            scrypt(1, 8, u32::MAX),
+            scrypt(255, 8, 1),
+            Kdf::Pbkdf2Sha256 {
+                salt: salt.clone(),
+                iterations: u32::MAX,
+            },
+        ];
+        for kdf in &too_costly {
+            assert!(check_costs(kdf).is_err(), "{:?}", kdf);
+            assert!(derive_key(kdf, "passphrase").is_err(), "{:?}", kdf);
+        }
+    }
+}
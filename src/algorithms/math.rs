@@ -1,8 +1,10 @@
+use nalgebra::DMatrix;
+
 pub fn euclid_algo(a: i32, b: i32) -> i32 {
     if a == 0 {
         return b;
     }
-    euclid_algo(b % a, b)
+    euclid_algo(b % a, a)
 }
 
 pub fn extended_euclid_algo(a: i32, b: i32) -> Option<i32> {
@@ -26,3 +28,125 @@ pub fn extended_euclid_algo(a: i32, b: i32) -> Option<i32> {
 
     Some((t1 % b + b) % b)
 }
+
+/// Reduces `matrix` to upper triangular form modulo `m` using only row swaps and
+/// "subtract a multiple of another row" steps, so it works for composite moduli like 26.
+/// The same operations are applied to `companion` (pass the identity to get an inverse).
+/// Returns the number of row swaps performed.
+fn triangulate_mod(matrix: &mut DMatrix<i32>, companion: &mut DMatrix<i32>, m: i32) -> usize {
+    let n = matrix.nrows();
+    let mut swaps = 0;
+
+    for col in 0..n {
+        loop {
+            // Pick the row with the smallest non-zero entry in this column as the pivot
+            let pivot = (col..n)
+                .filter(|&r| matrix[(r, col)] != 0)
+                .min_by_key(|&r| matrix[(r, col)]);
+            let Some(pivot) = pivot else { break };
+
+            if pivot != col {
+                matrix.swap_rows(pivot, col);
+                companion.swap_rows(pivot, col);
+                swaps += 1;
+            }
+
+            let mut done = true;
+            for row in col + 1..n {
+                let q = matrix[(row, col)] / matrix[(col, col)];
+                if q == 0 {
+                    if matrix[(row, col)] != 0 {
+                        done = false;
+                    }
+                    continue;
+                }
+                for k in 0..n {
+                    matrix[(row, k)] = (matrix[(row, k)] - q * matrix[(col, k)]).rem_euclid(m);
+                    companion[(row, k)] =
+                        (companion[(row, k)] - q * companion[(col, k)]).rem_euclid(m);
+                }
+                if matrix[(row, col)] != 0 {
+                    done = false;
+                }
+            }
+
+            if done {
+                break;
+            }
+        }
+    }
+
+    swaps
+}
+
+pub fn determinant_mod(matrix: &DMatrix<i32>, m: i32) -> i32 {
+    let n = matrix.nrows();
+    let mut work = matrix.map(|v| v.rem_euclid(m));
+    let mut companion = DMatrix::<i32>::zeros(n, n);
+    let swaps = triangulate_mod(&mut work, &mut companion, m);
+
+    let det = (0..n).fold(1, |acc, i| (acc * work[(i, i)]) % m);
+    if swaps % 2 == 1 { (m - det) % m } else { det }
+}
+
+/// Inverse of a square matrix modulo `m`, or `None` if its determinant is not a unit mod `m`.
+pub fn matrix_inverse_mod(matrix: &DMatrix<i32>, m: i32) -> Option<DMatrix<i32>> {
+    let n = matrix.nrows();
+    if n != matrix.ncols() || n == 0 {
+        return None;
+    }
+
+    let mut work = matrix.map(|v| v.rem_euclid(m));
+    let mut inverse = DMatrix::<i32>::identity(n, n);
+    triangulate_mod(&mut work, &mut inverse, m);
+
+    // Work backwards from the last row, scaling each pivot to 1 and clearing the column above it
+    for col in (0..n).rev() {
+        let pivot_inv = extended_euclid_algo(work[(col, col)], m)?;
+        for k in 0..n {
+            work[(col, k)] = (work[(col, k)] * pivot_inv).rem_euclid(m);
+            inverse[(col, k)] = (inverse[(col, k)] * pivot_inv).rem_euclid(m);
+        }
+        for row in 0..col {
+            let factor = work[(row, col)];
+            if factor == 0 {
+                continue;
+            }
+            for k in 0..n {
+                work[(row, k)] = (work[(row, k)] - factor * work[(col, k)]).rem_euclid(m);
+                inverse[(row, k)] = (inverse[(row, k)] - factor * inverse[(col, k)]).rem_euclid(m);
+            }
+        }
+    }
+
+    Some(inverse)
+}
+
+/// Rank of a (possibly rectangular) matrix over the field of integers mod the prime `p`.
+pub fn rank_mod_prime(matrix: &DMatrix<i32>, p: i32) -> usize {
+    let mut work = matrix.map(|v| v.rem_euclid(p));
+    let (rows, cols) = work.shape();
+    let mut rank = 0;
+
+    for col in 0..cols {
+        // Every non-zero entry is a unit mod a prime
+        let pivot =
+            (rank..rows).find_map(|r| extended_euclid_algo(work[(r, col)], p).map(|inv| (r, inv)));
+        let Some((pivot, pivot_inv)) = pivot else {
+            continue;
+        };
+        work.swap_rows(pivot, rank);
+        for row in rank + 1..rows {
+            let factor = (work[(row, col)] * pivot_inv).rem_euclid(p);
+            for k in col..cols {
+                work[(row, k)] = (work[(row, k)] - factor * work[(rank, k)]).rem_euclid(p);
+            }
+        }
+        rank += 1;
+        if rank == rows {
+            break;
+        }
+    }
+
+    rank
+}
@@ -0,0 +1,114 @@
+//! One-time pad, on letters (mod 26) and on bytes (Vernam XOR).
+//!
+//! Pads come from the operating system's RNG. A pad must be at least as long as the
+//! message; reusing one is what the crib-dragging helpers at the bottom exploit.
+
+use aes_gcm::aead::OsRng;
+use aes_gcm::aead::rand_core::RngCore;
+use anyhow::{Result, bail};
+
+/// A pad of `len` uniformly random letters A-Z.
+pub fn generate_letter_pad(len: usize) -> String {
+    let mut pad = String::with_capacity(len);
+    let mut buf = [0u8; 64];
+    while pad.len() < len {
+        OsRng.fill_bytes(&mut buf);
+        // 234 = 9 * 26: rejecting larger bytes keeps every letter equally likely
+        for &b in buf.iter().filter(|&&b| b < 234) {
+            if pad.len() == len {
+                break;
+            }
+            pad.push((b'A' + b % 26) as char);
+        }
+    }
+    pad
+}
+
+/// A pad of `len` random bytes.
+pub fn generate_byte_pad(len: usize) -> Vec<u8> {
+    let mut pad = vec![0u8; len];
+    OsRng.fill_bytes(&mut pad);
+    pad
+}
+
+fn letters(text: &str) -> Vec<u8> {
+    text.chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase() as u8 - b'A')
+        .collect()
+}
+
+fn check_pad_length(message: usize, pad: usize) -> Result<()> {
+    if pad < message {
+        bail!(
+            "Pad is shorter than the message ({} vs {}); a one-time pad must cover every symbol",
+            pad,
+            message
+        );
+    }
+    Ok(())
+}
+
+fn letter_pad(text: &str, pad: &str, f: impl Fn(u8, u8) -> u8) -> Result<String> {
+    let (text, pad) = (letters(text), letters(pad));
+    check_pad_length(text.len(), pad.len())?;
+    Ok(text
+        .iter()
+        .zip(pad.iter())
+        .map(|(&t, &k)| (b'A' + f(t, k) % 26) as char)
+        .collect())
+}
+
+/// Adds the pad to the message letter by letter. Anything but letters is dropped, so
+/// word lengths do not leak into the ciphertext.
+pub fn otp_letters_encrypt(plaintext: &str, pad: &str) -> Result<String> {
+    letter_pad(plaintext, pad, |p, k| p + k)
+}
+
+pub fn otp_letters_decrypt(ciphertext: &str, pad: &str) -> Result<String> {
+    letter_pad(ciphertext, pad, |c, k| c + 26 - k)
+}
+
+/// Vernam cipher: XOR with the pad. Encryption and decryption are the same operation.
+pub fn otp_xor(data: &[u8], pad: &[u8]) -> Result<Vec<u8>> {
+    check_pad_length(data.len(), pad.len())?;
+    Ok(data.iter().zip(pad.iter()).map(|(d, k)| d ^ k).collect())
+}
+
+/// Result of trying a crib at one offset of two ciphertexts that share a pad.
+#[derive(Clone, Debug)]
+pub struct CribMatch {
+    pub offset: usize,
+    /// What the other message must contain at `offset` if the crib is right
+    pub fragment: Vec<u8>,
+    /// Every byte of the fragment is printable text
+    pub plausible: bool,
+}
+
+/// XOR of two ciphertexts made with the same pad, which equals the XOR of the plaintexts.
+pub fn xor_ciphertexts(c1: &[u8], c2: &[u8]) -> Vec<u8> {
+    c1.iter().zip(c2.iter()).map(|(a, b)| a ^ b).collect()
+}
+
+/// Slides `crib` along the XOR of the two ciphertexts and returns what the other
+/// plaintext would read at every position.
+pub fn crib_drag(c1: &[u8], c2: &[u8], crib: &[u8]) -> Vec<CribMatch> {
+    let combined = xor_ciphertexts(c1, c2);
+    if crib.is_empty() || crib.len() > combined.len() {
+        return Vec::new();
+    }
+
+    combined
+        .windows(crib.len())
+        .enumerate()
+        .map(|(offset, window)| {
+            let fragment: Vec<u8> = window.iter().zip(crib).map(|(x, c)| x ^ c).collect();
+            let plausible = fragment.iter().all(|&b| b.is_ascii_graphic() || b == b' ');
+            CribMatch {
+                offset,
+                fragment,
+                plausible,
+            }
+        })
+        .collect()
+}
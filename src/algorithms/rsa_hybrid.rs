@@ -1,5 +1,9 @@
+use crate::algorithms::aes_only::decrypt_envelope_payload;
+use crate::algorithms::envelope::{
+    Algorithm, Header, Kdf, MAGIC, Recipient, armor, dearmor, is_envelope,
+};
 use aes::cipher::generic_array::GenericArray;
-use aes_gcm::aead::{Aead, OsRng};
+use aes_gcm::aead::{Aead, OsRng, Payload};
 use aes_gcm::{AeadCore, Aes256Gcm, KeyInit};
 use anyhow::Context;
 use base64::{Engine as _, engine::general_purpose};
@@ -13,6 +17,7 @@ pub fn encrypt_file(
     input_file: &str,
     output_file: &str,
     public_key_file: &str,
+    armored: bool,
 ) -> Result<(), Box<dyn Error>> {
     // First let's load the RSA Public Key
     let public_key_pem = fs::read_to_string(public_key_file)?;
@@ -23,24 +28,37 @@ pub fn encrypt_file(
     let cipher = Aes256Gcm::new(&aes_key);
     let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
 
-    // Encrypt File Content
-    let plaintext = fs::read(input_file)?;
-    let ciphertext = cipher
-        .encrypt(&nonce, plaintext.as_ref())
-        .map_err(|e| anyhow::anyhow!("AES Encryption Failed {:?}", e))?;
-
     // Encrypt the keyyyyy
     let padding = Oaep::new::<Sha256>();
     let encrypted_key = public_key.encrypt(&mut OsRng, padding, aes_key.as_slice())?;
 
-    let result = format!(
-        "{}\n{}\n{}",
-        general_purpose::STANDARD.encode(&encrypted_key),
-        general_purpose::STANDARD.encode(&nonce),
-        general_purpose::STANDARD.encode(&ciphertext)
+    // The header, wrapped key included, is authenticated along with the content
+    let header = Header::new(
+        Algorithm::Aes256Gcm,
+        Kdf::None,
+        Recipient::RsaOaep(encrypted_key),
+        nonce.to_vec(),
     );
+    let mut result = header.to_bytes()?;
 
-    fs::write(output_file, result)?;
+    // Encrypt File Content
+    let plaintext = fs::read(input_file)?;
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &plaintext,
+                aad: &result,
+            },
+        )
+        .map_err(|e| anyhow::anyhow!("AES Encryption Failed {:?}", e))?;
+    result.extend_from_slice(&ciphertext);
+
+    if armored {
+        fs::write(output_file, armor(&result))?;
+    } else {
+        fs::write(output_file, result)?;
+    }
     println!("Encrypted file written to {}", output_file);
     Ok(())
 }
@@ -53,8 +71,27 @@ pub fn decrypt_file(
     let private_key_pem = fs::read_to_string(private_key_file)?;
     let private_key = RsaPrivateKey::from_pkcs8_pem(&private_key_pem)?;
 
-    // Read and split the file
-    let content = fs::read_to_string(input_file)?;
+    let bytes = fs::read(input_file)?;
+    if is_envelope(&bytes) {
+        let envelope = if bytes.starts_with(MAGIC) {
+            bytes
+        } else {
+            dearmor(&String::from_utf8(bytes)?)?
+        };
+        let mut payload = envelope.as_slice();
+        let (header, header_bytes) = Header::read_from(&mut payload)?;
+        let Recipient::RsaOaep(encrypted_key) = &header.recipient else {
+            return Err("File is not encrypted for an RSA key".into());
+        };
+        let padding = Oaep::new::<Sha256>();
+        let aes_key_bytes = private_key.decrypt(padding, encrypted_key)?;
+        decrypt_envelope_payload(&header, &header_bytes, &aes_key_bytes, payload, output_file)?;
+        println!("Decrypted file written to {}", output_file);
+        return Ok(());
+    }
+
+    // Legacy format: three base64 lines
+    let content = String::from_utf8(bytes)?;
     let mut lines = content.lines();
 
     let encrypt_key_b64 = lines.next().context("Missing encrypted AES Key")?;
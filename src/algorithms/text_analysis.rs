@@ -0,0 +1,102 @@
+//! Letter statistics of a text: the first thing to look at before choosing an attack.
+//!
+//! A flat monogram distribution points at a polyalphabetic cipher, an English-shaped
+//! but shifted one at a monoalphabetic cipher, and an English one at a transposition.
+
+use crate::algorithms::fitness::{
+    REFERENCE_FREQUENCIES, chi_squared, index_of_coincidence, text_letters,
+};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NgramCount {
+    pub ngram: String,
+    pub count: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TextAnalysis {
+    /// Number of letters A-Z; everything else is ignored
+    pub letters: usize,
+    pub monograms: [usize; 26],
+    /// Most frequent first
+    pub bigrams: Vec<NgramCount>,
+    /// Most frequent first
+    pub trigrams: Vec<NgramCount>,
+    pub ioc: f64,
+    /// Shannon entropy of the monograms, in bits per letter
+    pub entropy: f64,
+    /// Chi-squared against each reference language, closest first
+    pub chi_squared: Vec<(&'static str, f64)>,
+}
+
+/// Counts every run of `n` consecutive letters (numbers 0-25), most frequent first and
+/// alphabetical among equal counts.
+pub fn ngram_counts(letters: &[u8], n: usize) -> Vec<NgramCount> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut counts = HashMap::new();
+    for window in letters.windows(n) {
+        *counts.entry(window).or_insert(0) += 1;
+    }
+
+    let mut ngrams: Vec<NgramCount> = counts
+        .into_iter()
+        .map(|(window, count)| NgramCount {
+            ngram: window.iter().map(|&l| (b'A' + l) as char).collect(),
+            count,
+        })
+        .collect();
+    ngrams.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.ngram.cmp(&b.ngram)));
+    ngrams
+}
+
+/// Shannon entropy of a distribution given as counts, in bits. English letters come to
+/// about 4.18, uniformly random letters to log2(26) = 4.70.
+pub fn shannon_entropy(counts: &[usize]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+pub fn analyze_text(text: &str) -> TextAnalysis {
+    let letters = text_letters(text);
+    let mut monograms = [0; 26];
+    for &l in &letters {
+        monograms[l as usize] += 1;
+    }
+
+    let mut chi = Vec::new();
+    if !letters.is_empty() {
+        chi = REFERENCE_FREQUENCIES
+            .iter()
+            .map(|reference| {
+                (
+                    reference.language,
+                    chi_squared(&monograms, &reference.frequencies),
+                )
+            })
+            .collect();
+        chi.sort_by(|a, b| a.1.total_cmp(&b.1));
+    }
+
+    TextAnalysis {
+        letters: letters.len(),
+        monograms,
+        bigrams: ngram_counts(&letters, 2),
+        trigrams: ngram_counts(&letters, 3),
+        ioc: index_of_coincidence(&letters),
+        entropy: shannon_entropy(&monograms),
+        chi_squared: chi,
+    }
+}
@@ -0,0 +1,277 @@
+//! Transposition ciphers: the letters stay the same, only their positions change.
+//!
+//! All functions work on the text exactly as given (spaces and punctuation included),
+//! so decrypting always returns the original string. Route cipher is the exception: it
+//! needs a full rectangle and pads the plaintext with `X`.
+
+use anyhow::{Result, bail};
+
+const ROUTE_PADDING: char = 'X';
+
+/// A grid of characters as laid out by a cipher, with `None` for empty cells.
+pub type Grid = Vec<Vec<Option<char>>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoutePath {
+    /// Clockwise spiral starting at the top-left corner
+    Spiral,
+    /// Down the first column, up the second, and so on
+    Snake,
+}
+
+fn rail_pattern(len: usize, rails: usize, offset: usize) -> Vec<usize> {
+    if rails == 1 {
+        return vec![0; len];
+    }
+
+    let cycle = 2 * (rails - 1);
+    (0..len)
+        .map(|i| {
+            let pos = (i + offset) % cycle;
+            if pos < rails { pos } else { cycle - pos }
+        })
+        .collect()
+}
+
+fn check_rails(rails: usize) -> Result<()> {
+    if rails == 0 {
+        bail!("Number of rails must be at least 1");
+    }
+    Ok(())
+}
+
+pub fn rail_fence_encrypt(plaintext: &str, rails: usize, offset: usize) -> Result<String> {
+    check_rails(rails)?;
+    let chars: Vec<char> = plaintext.chars().collect();
+    let pattern = rail_pattern(chars.len(), rails, offset);
+
+    Ok((0..rails)
+        .flat_map(|rail| {
+            chars
+                .iter()
+                .zip(pattern.iter())
+                .filter(move |(_, r)| **r == rail)
+                .map(|(c, _)| *c)
+        })
+        .collect())
+}
+
+pub fn rail_fence_decrypt(ciphertext: &str, rails: usize, offset: usize) -> Result<String> {
+    check_rails(rails)?;
+    let chars: Vec<char> = ciphertext.chars().collect();
+    let pattern = rail_pattern(chars.len(), rails, offset);
+
+    // Positions in reading order are exactly the order the ciphertext was written in
+    let mut positions: Vec<usize> = (0..chars.len()).collect();
+    positions.sort_by_key(|&i| pattern[i]);
+
+    let mut plaintext = vec![' '; chars.len()];
+    for (c, &pos) in chars.iter().zip(positions.iter()) {
+        plaintext[pos] = *c;
+    }
+    Ok(plaintext.into_iter().collect())
+}
+
+/// The zigzag as it would be drawn on paper: one row per rail, one column per character.
+pub fn rail_fence_grid(plaintext: &str, rails: usize, offset: usize) -> Grid {
+    let chars: Vec<char> = plaintext.chars().collect();
+    let pattern = rail_pattern(chars.len(), rails.max(1), offset);
+    let mut grid = vec![vec![None; chars.len()]; rails.max(1)];
+    for (i, (c, rail)) in chars.iter().zip(pattern).enumerate() {
+        grid[rail][i] = Some(*c);
+    }
+    grid
+}
+
+/// Reading order of the columns for a columnar key: the column under the alphabetically
+/// smallest key letter is read first, ties are broken left to right.
+pub fn column_order(key: &str) -> Vec<usize> {
+    let key: Vec<char> = key
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let mut order: Vec<usize> = (0..key.len()).collect();
+    order.sort_by_key(|&i| (key[i], i));
+    order
+}
+
+fn columnar_by_order(plaintext: &str, order: &[usize]) -> String {
+    let chars: Vec<char> = plaintext.chars().collect();
+    let width = order.len();
+
+    order
+        .iter()
+        .flat_map(|&col| chars.iter().skip(col).step_by(width).copied())
+        .collect()
+}
+
+/// Undoes a columnar transposition with the given column reading order. Works on any
+/// symbols so attacks can decrypt letter codes without building strings.
+pub fn columnar_decrypt_symbols<T: Copy>(ciphertext: &[T], order: &[usize]) -> Vec<T> {
+    let width = order.len();
+    let full_rows = ciphertext.len() / width;
+    let remainder = ciphertext.len() % width;
+
+    let mut plaintext = ciphertext.to_vec();
+    let mut index = 0;
+    for &col in order {
+        let height = full_rows + usize::from(col < remainder);
+        for row in 0..height {
+            plaintext[row * width + col] = ciphertext[index];
+            index += 1;
+        }
+    }
+    plaintext
+}
+
+fn columnar_decrypt_by_order(ciphertext: &str, order: &[usize]) -> String {
+    let chars: Vec<char> = ciphertext.chars().collect();
+    columnar_decrypt_symbols(&chars, order)
+        .into_iter()
+        .collect()
+}
+
+fn check_columnar_key(key: &str) -> Result<Vec<usize>> {
+    let order = column_order(key);
+    if order.is_empty() {
+        bail!("Columnar key must contain at least one letter or digit");
+    }
+    Ok(order)
+}
+
+pub fn columnar_encrypt(plaintext: &str, key: &str) -> Result<String> {
+    let order = check_columnar_key(key)?;
+    Ok(columnar_by_order(plaintext, &order))
+}
+
+pub fn columnar_decrypt(ciphertext: &str, key: &str) -> Result<String> {
+    let order = check_columnar_key(key)?;
+    Ok(columnar_decrypt_by_order(ciphertext, &order))
+}
+
+pub fn double_columnar_encrypt(plaintext: &str, key1: &str, key2: &str) -> Result<String> {
+    columnar_encrypt(&columnar_encrypt(plaintext, key1)?, key2)
+}
+
+pub fn double_columnar_decrypt(ciphertext: &str, key1: &str, key2: &str) -> Result<String> {
+    columnar_decrypt(&columnar_decrypt(ciphertext, key2)?, key1)
+}
+
+/// Text written row by row into a grid of the given width.
+pub fn row_grid(text: &str, width: usize) -> Grid {
+    let chars: Vec<Option<char>> = text.chars().map(Some).collect();
+    chars
+        .chunks(width.max(1))
+        .map(|row| {
+            let mut row = row.to_vec();
+            row.resize(width.max(1), None);
+            row
+        })
+        .collect()
+}
+
+fn route_positions(rows: usize, cols: usize, path: RoutePath) -> Vec<usize> {
+    match path {
+        RoutePath::Snake => (0..cols)
+            .flat_map(|col| {
+                let down: Vec<usize> = (0..rows).map(|row| row * cols + col).collect();
+                if col % 2 == 0 {
+                    down
+                } else {
+                    down.into_iter().rev().collect()
+                }
+            })
+            .collect(),
+        RoutePath::Spiral => {
+            let mut positions = Vec::with_capacity(rows * cols);
+            let (mut top, mut bottom, mut left, mut right) = (0, rows, 0, cols);
+            while top < bottom && left < right {
+                positions.extend((left..right).map(|col| top * cols + col));
+                top += 1;
+                positions.extend((top..bottom).map(|row| row * cols + right - 1));
+                right -= 1;
+                if top < bottom {
+                    positions.extend((left..right).rev().map(|col| (bottom - 1) * cols + col));
+                    bottom -= 1;
+                }
+                if left < right {
+                    positions.extend((top..bottom).rev().map(|row| row * cols + left));
+                    left += 1;
+                }
+            }
+            positions
+        }
+    }
+}
+
+fn check_width(width: usize) -> Result<()> {
+    if width == 0 {
+        bail!("Grid width must be at least 1");
+    }
+    Ok(())
+}
+
+/// Pads `text` with `X` so it fills a whole number of rows of the given width.
+pub fn route_pad(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    let padding = (width - len % width) % width;
+    text.chars()
+        .chain(std::iter::repeat_n(ROUTE_PADDING, padding))
+        .collect()
+}
+
+pub fn route_encrypt(plaintext: &str, width: usize, path: RoutePath) -> Result<String> {
+    check_width(width)?;
+    let chars: Vec<char> = route_pad(plaintext, width).chars().collect();
+    let rows = chars.len() / width;
+
+    Ok(route_positions(rows, width, path)
+        .into_iter()
+        .map(|pos| chars[pos])
+        .collect())
+}
+
+pub fn route_decrypt(ciphertext: &str, width: usize, path: RoutePath) -> Result<String> {
+    check_width(width)?;
+    let chars: Vec<char> = ciphertext.chars().collect();
+    if !chars.len().is_multiple_of(width) {
+        bail!(
+            "Ciphertext length {} is not a multiple of the grid width {}",
+            chars.len(),
+            width
+        );
+    }
+    let rows = chars.len() / width;
+
+    let mut plaintext = vec![' '; chars.len()];
+    for (c, pos) in chars.iter().zip(route_positions(rows, width, path)) {
+        plaintext[pos] = *c;
+    }
+    Ok(plaintext.into_iter().collect())
+}
+
+/// Number of letters along the rod for a scytale with `diameter` letters around it.
+pub fn scytale_width(len: usize, diameter: usize) -> usize {
+    len.div_ceil(diameter).max(1)
+}
+
+/// Scytale: the text is written along the rod, one row per turn of the strip, and the
+/// unwound strip reads the grid column by column.
+pub fn scytale_encrypt(plaintext: &str, diameter: usize) -> Result<String> {
+    if diameter == 0 {
+        bail!("Scytale diameter must be at least 1");
+    }
+    let width = scytale_width(plaintext.chars().count(), diameter);
+    let order: Vec<usize> = (0..width).collect();
+    Ok(columnar_by_order(plaintext, &order))
+}
+
+pub fn scytale_decrypt(ciphertext: &str, diameter: usize) -> Result<String> {
+    if diameter == 0 {
+        bail!("Scytale diameter must be at least 1");
+    }
+    let width = scytale_width(ciphertext.chars().count(), diameter);
+    let order: Vec<usize> = (0..width).collect();
+    Ok(columnar_decrypt_by_order(ciphertext, &order))
+}
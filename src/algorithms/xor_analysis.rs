@@ -0,0 +1,293 @@
+//! Byte-level attacks on XOR ciphers: single-byte XOR and repeating-key XOR (the
+//! "Vigenère of bytes").
+//!
+//! The key size is estimated from the normalised Hamming distance between ciphertext
+//! blocks; each key byte is then recovered independently by scoring its column of
+//! ciphertext as text in the chosen language.
+
+use crate::algorithms::fitness::LanguageModel;
+use anyhow::{Result, anyhow, bail};
+use base64::{Engine as _, engine::general_purpose};
+
+/// Longest repeating key tried when estimating the key size.
+pub const MAX_XOR_KEY_SIZE: usize = 40;
+
+/// Key sizes with the lowest Hamming distance that get fully solved.
+const KEY_SIZES_TRIED: usize = 5;
+
+/// Decodes ciphertext given as hex or base64 text; whitespace is ignored.
+pub fn decode_ciphertext(text: &str) -> Result<Vec<u8>> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if compact.is_empty() {
+        bail!("Ciphertext is empty");
+    }
+    if compact.len().is_multiple_of(2) && compact.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(hex::decode(&compact)?);
+    }
+    general_purpose::STANDARD
+        .decode(&compact)
+        .map_err(|e| anyhow!("Ciphertext is neither hex nor base64: {}", e))
+}
+
+/// Reads ciphertext from a file: hex or base64 text is decoded, anything else is taken
+/// as raw bytes.
+pub fn read_ciphertext_file(path: &str) -> Result<Vec<u8>> {
+    let bytes = std::fs::read(path).map_err(|e| anyhow!("Could not read {}: {}", path, e))?;
+    match std::str::from_utf8(&bytes) {
+        Ok(text) => Ok(decode_ciphertext(text).unwrap_or(bytes)),
+        Err(_) => Ok(bytes),
+    }
+}
+
+pub fn hamming_distance(a: &[u8], b: &[u8]) -> u32 {
+    a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum()
+}
+
+/// XOR with `key` repeated over the whole message. Encrypts and decrypts alike.
+pub fn repeating_key_xor(data: &[u8], key: &[u8]) -> Vec<u8> {
+    if key.is_empty() {
+        return data.to_vec();
+    }
+    data.iter()
+        .zip(key.iter().cycle())
+        .map(|(d, k)| d ^ k)
+        .collect()
+}
+
+/// How much `bytes` look like text in `model`'s language: the average log10
+/// probability of each byte, with letters at the language's frequencies, spaces and
+/// punctuation at typical rates and control or non-ASCII bytes all but ruled out.
+pub fn text_score(bytes: &[u8], model: &LanguageModel) -> f64 {
+    if bytes.is_empty() {
+        return f64::NEG_INFINITY;
+    }
+    let frequencies = model.frequencies();
+    let total: f64 = bytes
+        .iter()
+        .map(|&b| match b {
+            b'a'..=b'z' => (0.75 * frequencies[(b - b'a') as usize]).log10(),
+            // Capitals are rarer than the same letter in lower case
+            b'A'..=b'Z' => (0.05 * frequencies[(b - b'A') as usize]).log10(),
+            b' ' => 0.15f64.log10(),
+            b'.' | b',' | b'\'' | b'\n' => 0.01f64.log10(),
+            b'0'..=b'9' | b'!'..=b'~' | b'\r' | b'\t' => 0.001f64.log10(),
+            _ => -8.0,
+        })
+        .sum();
+    total / bytes.len() as f64
+}
+
+#[derive(Clone, Debug)]
+pub struct SingleByteCandidate {
+    pub key: u8,
+    /// [`text_score`] of the decryption, higher is better
+    pub score: f64,
+    pub plaintext: Vec<u8>,
+}
+
+/// Tries all 256 keys and ranks the decryptions, most text-like first.
+pub fn crack_single_byte_xor(data: &[u8], model: &LanguageModel) -> Vec<SingleByteCandidate> {
+    let mut candidates: Vec<SingleByteCandidate> = (0..=255u8)
+        .map(|key| {
+            let plaintext: Vec<u8> = data.iter().map(|b| b ^ key).collect();
+            SingleByteCandidate {
+                key,
+                score: text_score(&plaintext, model),
+                plaintext,
+            }
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates
+}
+
+#[derive(Clone, Debug)]
+pub struct KeySizeCandidate {
+    pub size: usize,
+    /// Average Hamming distance between neighbouring blocks, in bits per key byte;
+    /// text XORed with the right key size gives about 2-3, random bytes about 4
+    pub distance: f64,
+}
+
+/// Scores every key size from 2 to `max_size` by the normalised Hamming distance
+/// between consecutive `size`-byte blocks, lowest (most likely) first. Sizes that
+/// leave fewer than two blocks are skipped.
+pub fn xor_key_sizes(data: &[u8], max_size: usize) -> Vec<KeySizeCandidate> {
+    let mut candidates: Vec<KeySizeCandidate> = (2..=max_size.min(data.len() / 2))
+        .map(|size| {
+            let blocks: Vec<&[u8]> = data.chunks_exact(size).collect();
+            let total: u32 = blocks
+                .windows(2)
+                .map(|pair| hamming_distance(pair[0], pair[1]))
+                .sum();
+            let pairs = (blocks.len() - 1) as f64;
+            KeySizeCandidate {
+                size,
+                distance: total as f64 / pairs / size as f64,
+            }
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    candidates
+}
+
+#[derive(Clone, Debug)]
+pub struct RepeatingXorSolution {
+    pub key: Vec<u8>,
+    /// Hamming distance the key size was picked with
+    pub distance: f64,
+    /// [`text_score`] of the whole decryption, higher is better
+    pub score: f64,
+    pub plaintext: Vec<u8>,
+}
+
+/// Solves each column of a repeating-key XOR ciphertext as single-byte XOR.
+pub fn xor_key_for_size(data: &[u8], size: usize, model: &LanguageModel) -> Vec<u8> {
+    (0..size)
+        .map(|column| {
+            let bytes: Vec<u8> = data.iter().skip(column).step_by(size).copied().collect();
+            crack_single_byte_xor(&bytes, model)
+                .first()
+                .map_or(0, |best| best.key)
+        })
+        .collect()
+}
+
+/// Whether `key` is `shorter` repeated, give or take a few columns that were solved
+/// wrongly: with few bytes per column, a multiple of the real key size overfits.
+fn repeats_shorter_key(key: &[u8], shorter: &[u8]) -> bool {
+    if shorter.len() >= key.len() || !key.len().is_multiple_of(shorter.len()) {
+        return false;
+    }
+    let matching = key
+        .iter()
+        .zip(shorter.iter().cycle())
+        .filter(|(a, b)| a == b)
+        .count();
+    matching * 4 >= key.len() * 3
+}
+
+/// Breaks repeating-key XOR: the most promising key sizes by Hamming distance, and
+/// their divisors, are each solved column by column. Keys that merely repeat a
+/// shorter solution are dropped and the rest ranked by how much the whole decryption
+/// looks like text.
+pub fn break_repeating_xor(
+    data: &[u8],
+    max_key_size: usize,
+    model: &LanguageModel,
+) -> Result<Vec<RepeatingXorSolution>> {
+    let sizes = xor_key_sizes(data, max_key_size);
+    if sizes.is_empty() {
+        bail!("Need at least four bytes of ciphertext");
+    }
+
+    // Multiples of the key size score as well as the key size itself, so try the
+    // divisors of every promising size too
+    let mut tried: Vec<(usize, f64)> = Vec::new();
+    for candidate in sizes.iter().take(KEY_SIZES_TRIED) {
+        for size in (1..=candidate.size).filter(|&d| candidate.size.is_multiple_of(d)) {
+            if !tried.iter().any(|&(s, _)| s == size) {
+                let distance = sizes
+                    .iter()
+                    .find(|c| c.size == size)
+                    .map_or(candidate.distance, |c| c.distance);
+                tried.push((size, distance));
+            }
+        }
+    }
+    tried.sort_by_key(|&(size, _)| size);
+
+    let mut solutions: Vec<RepeatingXorSolution> = Vec::new();
+    for (size, distance) in tried {
+        let key = xor_key_for_size(data, size, model);
+        if solutions.iter().any(|s| repeats_shorter_key(&key, &s.key)) {
+            continue;
+        }
+        let plaintext = repeating_key_xor(data, &key);
+        solutions.push(RepeatingXorSolution {
+            score: text_score(&plaintext, model),
+            distance,
+            key,
+            plaintext,
+        });
+    }
+    solutions.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(solutions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::fitness::Language;
+
+    #[test]
+    fn cryptopals_hex_to_base64() {
+        let bytes = decode_ciphertext(
+            "49276d206b696c6c696e6720796f757220627261696e206c696b65206120706f69736f6e6f7573206d757368726f6f6d",
+        )
+        .unwrap();
+        assert_eq!(
+            general_purpose::STANDARD.encode(&bytes),
+            "SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t"
+        );
+        assert_eq!(
+            decode_ciphertext("SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t")
+                .unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn cryptopals_fixed_xor() {
+        let data = decode_ciphertext("1c0111001f010100061a024b53535009181c").unwrap();
+        let key = decode_ciphertext("686974207468652062756c6c277320657965").unwrap();
+        assert_eq!(
+            hex::encode(repeating_key_xor(&data, &key)),
+            "746865206b696420646f6e277420706c6179"
+        );
+    }
+
+    #[test]
+    fn cryptopals_single_byte_xor() {
+        let data = decode_ciphertext(
+            "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736",
+        )
+        .unwrap();
+        let best = &crack_single_byte_xor(&data, &LanguageModel::built_in(Language::English))[0];
+        assert_eq!(best.key, 88);
+        assert_eq!(best.plaintext, b"Cooking MC's like a pound of bacon");
+    }
+
+    #[test]
+    fn cryptopals_repeating_key_xor() {
+        let plaintext =
+            b"Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal";
+        assert_eq!(
+            hex::encode(repeating_key_xor(plaintext, b"ICE")),
+            "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f"
+        );
+    }
+
+    #[test]
+    fn hamming_distance_counts_differing_bits() {
+        assert_eq!(hamming_distance(b"this is a test", b"wokka wokka!!!"), 37);
+    }
+
+    #[test]
+    fn breaks_repeating_key_round_trip() {
+        let plaintext = b"It was the best of times, it was the worst of times, it was the age of \
+            wisdom, it was the age of foolishness, it was the epoch of belief, it was the epoch \
+            of incredulity, it was the season of Light, it was the season of Darkness, it was \
+            the spring of hope, it was the winter of despair, we had everything before us, we \
+            had nothing before us, we were all going direct to Heaven, we were all going direct \
+            the other way - in short, the period was so far like the present period, that some \
+            of its noisiest authorities insisted on its being received, for good or for evil, \
+            in the superlative degree of comparison only.";
+        let key = b"Dickens1859";
+        let ciphertext = repeating_key_xor(plaintext, key);
+        let model = LanguageModel::built_in(Language::English);
+        let best = &break_repeating_xor(&ciphertext, MAX_XOR_KEY_SIZE, &model).unwrap()[0];
+        assert_eq!(best.key, key);
+        assert_eq!(best.plaintext, plaintext);
+    }
+}
@@ -1,4 +1,9 @@
-use crate::algorithms::aes_only::{decrypt_with_aes, encrypt_with_aes};
+use crate::algorithms::aead::AeadAlgorithm;
+use crate::algorithms::aes_only::{
+    decrypt_with_key_file, decrypt_with_passphrase, encrypt_with_key_file,
+    encrypt_with_key_file_stream, encrypt_with_passphrase,
+};
+use crate::algorithms::kdf::KdfAlgorithm;
 use crate::components::cipher_component::CipherComponent;
 use ratatui::crossterm::event::KeyCode;
 use ratatui::{
@@ -11,14 +16,23 @@ use ratatui::{
 pub enum AesMode {
     #[default]
     Encrypt,
+    /// Container wrapped in ASCII armor
+    EncryptArmored,
+    /// Segment by segment in constant memory, for files of any size
+    EncryptStream,
+    /// Takes every format, telling them apart by their first bytes
     Decrypt,
 }
 
 pub struct AesCipherComponent {
     input_path: String,
     key_path: String,
+    /// Used instead of the key file when not empty
+    passphrase: String,
     output_path: String,
     mode: AesMode,
+    algorithm: AeadAlgorithm,
+    kdf: KdfAlgorithm,
     status_message: String,
     current_field: usize,
 }
@@ -28,8 +42,11 @@ impl Default for AesCipherComponent {
         Self {
             input_path: String::new(),
             key_path: String::new(),
+            passphrase: String::new(),
             output_path: String::new(),
             mode: AesMode::Encrypt,
+            algorithm: AeadAlgorithm::Aes256Gcm,
+            kdf: KdfAlgorithm::Argon2id,
             status_message: String::new(),
             current_field: 0,
         }
@@ -38,28 +55,53 @@ impl Default for AesCipherComponent {
 
 impl CipherComponent for AesCipherComponent {
     fn title(&self) -> &'static str {
-        "AES Cipher"
+        "AES / ChaCha20 Cipher"
     }
 
     fn handle_event(&mut self, key: KeyCode) {
         match key {
             KeyCode::Tab => {
                 self.mode = match self.mode {
-                    AesMode::Encrypt => AesMode::Decrypt,
+                    AesMode::Encrypt => AesMode::EncryptArmored,
+                    AesMode::EncryptArmored => AesMode::EncryptStream,
+                    AesMode::EncryptStream => AesMode::Decrypt,
                     AesMode::Decrypt => AesMode::Encrypt,
                 };
             }
+            KeyCode::Left | KeyCode::Right => {
+                let count = AeadAlgorithm::ALL.len();
+                let index = AeadAlgorithm::ALL
+                    .iter()
+                    .position(|&a| a == self.algorithm)
+                    .unwrap_or(0);
+                let step = if key == KeyCode::Right { 1 } else { count - 1 };
+                self.algorithm = AeadAlgorithm::ALL[(index + step) % count];
+            }
+            KeyCode::PageDown | KeyCode::PageUp => {
+                let count = KdfAlgorithm::ALL.len();
+                let index = KdfAlgorithm::ALL
+                    .iter()
+                    .position(|&k| k == self.kdf)
+                    .unwrap_or(0);
+                let step = if key == KeyCode::PageDown {
+                    1
+                } else {
+                    count - 1
+                };
+                self.kdf = KdfAlgorithm::ALL[(index + step) % count];
+            }
             KeyCode::Up => {
-                self.current_field = (self.current_field + 2) % 3;
+                self.current_field = (self.current_field + 3) % 4;
             }
             KeyCode::Down => {
-                self.current_field = (self.current_field + 1) % 3;
+                self.current_field = (self.current_field + 1) % 4;
             }
             KeyCode::Char(c) => {
                 match self.current_field {
                     0 => self.input_path.push(c),
                     1 => self.key_path.push(c),
-                    2 => self.output_path.push(c),
+                    2 => self.passphrase.push(c),
+                    3 => self.output_path.push(c),
                     _ => unreachable!(),
                 }
             }
@@ -67,7 +109,8 @@ impl CipherComponent for AesCipherComponent {
                 match self.current_field {
                     0 => { self.input_path.pop(); }
                     1 => { self.key_path.pop(); }
-                    2 => { self.output_path.pop(); }
+                    2 => { self.passphrase.pop(); }
+                    3 => { self.output_path.pop(); }
                     _ => unreachable!(),
                 }
             }
@@ -84,18 +127,29 @@ impl CipherComponent for AesCipherComponent {
             Constraint::Length(3),
             Constraint::Length(3),
             Constraint::Length(3),
+            Constraint::Length(3),
             Constraint::Fill(1),
         ])
         .split(area);
 
         // Mode display
-        Paragraph::new(format!("Mode: {:?} (Tab to switch)", self.mode))
+        let mut mode_line = format!("Mode: {:?} (Tab to switch)", self.mode);
+        if !matches!(self.mode, AesMode::Decrypt) {
+            mode_line += &format!(
+                " | Cipher: {} (Left/Right to switch)",
+                self.algorithm.name()
+            );
+            if !self.passphrase.is_empty() {
+                mode_line += &format!(" | KDF: {} (PgUp/PgDn to switch)", self.kdf.name());
+            }
+        }
+        Paragraph::new(mode_line)
             .block(Block::default().title("Mode").borders(Borders::ALL))
             .render(layout[0], buf);
 
         // Input path
         let input_style = if self.current_field == 0 {
-            Block::default().title("Input File Path").borders(Borders::ALL)
+            Block::default().title(">> Input File Path").borders(Borders::ALL)
         } else {
             Block::default().title("Input File Path").borders(Borders::ALL)
         };
@@ -105,7 +159,7 @@ impl CipherComponent for AesCipherComponent {
 
         // Key path
         let key_style = if self.current_field == 1 {
-            Block::default().title("Key File Path").borders(Borders::ALL)
+            Block::default().title(">> Key File Path").borders(Borders::ALL)
         } else {
             Block::default().title("Key File Path").borders(Borders::ALL)
         };
@@ -113,32 +167,76 @@ impl CipherComponent for AesCipherComponent {
             .block(key_style)
             .render(layout[2], buf);
 
+        // Passphrase, masked
+        let passphrase_style = if self.current_field == 2 {
+            Block::default().title(">> Passphrase (instead of a key file)").borders(Borders::ALL)
+        } else {
+            Block::default().title("Passphrase (instead of a key file)").borders(Borders::ALL)
+        };
+        Paragraph::new("*".repeat(self.passphrase.chars().count()))
+            .block(passphrase_style)
+            .render(layout[3], buf);
+
         // Output path
-        let output_style = if self.current_field == 2 {
-            Block::default().title("Output File Path").borders(Borders::ALL)
+        let output_style = if self.current_field == 3 {
+            Block::default().title(">> Output File Path").borders(Borders::ALL)
         } else {
             Block::default().title("Output File Path").borders(Borders::ALL)
         };
         Paragraph::new(self.output_path.as_str())
             .block(output_style)
-            .render(layout[3], buf);
+            .render(layout[4], buf);
 
         // Status message
         Paragraph::new(self.status_message.as_str())
             .block(Block::default().title("Status").borders(Borders::ALL))
-            .render(layout[4], buf);
+            .render(layout[5], buf);
     }
 }
 
 impl AesCipherComponent {
     pub fn process_files(&mut self) {
+        if !self.passphrase.is_empty() {
+            self.process_with_passphrase();
+            return;
+        }
         match self.mode {
-            AesMode::Encrypt => {
-                match encrypt_with_aes(&self.input_path, &self.output_path, &self.key_path) {
+            AesMode::Encrypt | AesMode::EncryptArmored => {
+                let armored = matches!(self.mode, AesMode::EncryptArmored);
+                match encrypt_with_key_file(
+                    &self.input_path,
+                    &self.output_path,
+                    &self.key_path,
+                    self.algorithm,
+                    armored,
+                ) {
                     Ok(_) => {
                         self.status_message = format!(
-                            "Encrypted to: {} and Key is saved to: {}",
-                            self.output_path, self.key_path
+                            "Encrypted with {} to: {} and Key is saved to: {}",
+                            self.algorithm.name(),
+                            self.output_path,
+                            self.key_path
+                        );
+                    }
+                    Err(err) => {
+                        self.status_message = format!("Encryption failed: {err}");
+                    }
+                }
+            }
+
+            AesMode::EncryptStream => {
+                match encrypt_with_key_file_stream(
+                    &self.input_path,
+                    &self.output_path,
+                    &self.key_path,
+                    self.algorithm,
+                ) {
+                    Ok(_) => {
+                        self.status_message = format!(
+                            "Stream-encrypted with {} to: {} and Key is saved to: {}",
+                            self.algorithm.name(),
+                            self.output_path,
+                            self.key_path
                         );
                     }
                     Err(err) => {
@@ -148,7 +246,7 @@ impl AesCipherComponent {
             }
 
             AesMode::Decrypt => {
-                match decrypt_with_aes(&self.input_path, &self.output_path, &self.key_path) {
+                match decrypt_with_key_file(&self.input_path, &self.output_path, &self.key_path) {
                     Ok(_) => {
                         self.status_message =
                             format!("Decrypted file is stored at: {}", self.output_path);
@@ -160,4 +258,36 @@ impl AesCipherComponent {
             }
         }
     }
+
+    fn process_with_passphrase(&mut self) {
+        let result = match self.mode {
+            AesMode::Encrypt | AesMode::EncryptArmored | AesMode::EncryptStream => {
+                encrypt_with_passphrase(
+                    &self.input_path,
+                    &self.output_path,
+                    &self.passphrase,
+                    self.kdf,
+                    self.algorithm,
+                    matches!(self.mode, AesMode::EncryptArmored),
+                    matches!(self.mode, AesMode::EncryptStream),
+                )
+            }
+            AesMode::Decrypt => {
+                decrypt_with_passphrase(&self.input_path, &self.output_path, &self.passphrase)
+            }
+        };
+        self.status_message = match (result, &self.mode) {
+            (Ok(_), AesMode::Decrypt) => {
+                format!("Decrypted file is stored at: {}", self.output_path)
+            }
+            (Ok(_), _) => format!(
+                "Encrypted with {} to: {} with a key derived by {}",
+                self.algorithm.name(),
+                self.output_path,
+                self.kdf.name()
+            ),
+            (Err(err), AesMode::Decrypt) => format!("Decryption failed: {err}"),
+            (Err(err), _) => format!("Encryption failed: {err}"),
+        };
+    }
 }
@@ -0,0 +1,209 @@
+use crate::algorithms::classical_ciphers::{affine_brute_force, affine_decrypt, affine_encrypt};
+use crate::algorithms::fitness::{Language, LanguageModel};
+use crate::components::cipher_component::CipherComponent;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+use std::sync::Arc;
+
+const SHOWN_CANDIDATES: usize = 10;
+
+#[derive(Default, Debug)]
+pub enum AffineMode {
+    #[default]
+    Encrypt,
+    Decrypt,
+    BruteForce,
+}
+
+pub struct AffineCipherComponent {
+    input: String,
+    key_a: String,
+    key_b: String,
+    output: String,
+    mode: AffineMode,
+    current_field: usize,
+    /// Language the attack scores plaintext against
+    model: Arc<LanguageModel>,
+}
+
+impl Default for AffineCipherComponent {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            key_a: String::from("5"),
+            key_b: String::from("8"),
+            output: String::new(),
+            mode: AffineMode::Encrypt,
+            current_field: 0,
+            model: LanguageModel::built_in(Language::English),
+        }
+    }
+}
+
+impl AffineCipherComponent {
+    fn parse_keys(&self) -> Result<(i32, i32), String> {
+        let a = self
+            .key_a
+            .trim()
+            .parse::<i32>()
+            .map_err(|_| String::from("Key a must be a number"))?;
+        let b = self
+            .key_b
+            .trim()
+            .parse::<i32>()
+            .map_err(|_| String::from("Key b must be a number"))?;
+        Ok((a, b))
+    }
+
+    fn brute_force(&self) -> String {
+        affine_brute_force(&self.input, &self.model)
+            .iter()
+            .take(SHOWN_CANDIDATES)
+            .enumerate()
+            .map(|(i, c)| {
+                format!(
+                    "{:>2}. a={:<2} b={:<2} chi2={:>8.2}  {}",
+                    i + 1,
+                    c.a,
+                    c.b,
+                    c.score,
+                    c.plaintext
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn process(&mut self) {
+        let result = match (&self.mode, self.parse_keys()) {
+            (AffineMode::BruteForce, _) => Ok(self.brute_force()),
+            (_, Err(err)) => Err(err),
+            (AffineMode::Encrypt, Ok((a, b))) => {
+                affine_encrypt(self.input.clone(), a, b).map_err(|err| err.to_string())
+            }
+            (AffineMode::Decrypt, Ok((a, b))) => {
+                affine_decrypt(self.input.clone(), a, b).map_err(|err| err.to_string())
+            }
+        };
+        self.output = result.unwrap_or_else(|err| err);
+    }
+}
+
+impl CipherComponent for AffineCipherComponent {
+    fn title(&self) -> &'static str {
+        "Affine Cipher"
+    }
+
+    fn output(&self) -> Option<&str> {
+        Some(self.output.as_str())
+    }
+
+    fn set_language_model(&mut self, model: Arc<LanguageModel>) {
+        self.model = model;
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                self.mode = match self.mode {
+                    AffineMode::Encrypt => AffineMode::Decrypt,
+                    AffineMode::Decrypt => AffineMode::BruteForce,
+                    AffineMode::BruteForce => AffineMode::Encrypt,
+                };
+            }
+            KeyCode::Up => {
+                self.current_field = (self.current_field + 2) % 3;
+            }
+            KeyCode::Down => {
+                self.current_field = (self.current_field + 1) % 3;
+            }
+            KeyCode::Char(c) => match self.current_field {
+                0 => self.input.push(c),
+                1 => self.key_a.push(c),
+                2 => self.key_b.push(c),
+                _ => unreachable!(),
+            },
+            KeyCode::Backspace => match self.current_field {
+                0 => {
+                    self.input.pop();
+                }
+                1 => {
+                    self.key_a.pop();
+                }
+                2 => {
+                    self.key_b.pop();
+                }
+                _ => unreachable!(),
+            },
+            KeyCode::Enter => self.process(),
+            _ => {}
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        let mode = match self.mode {
+            AffineMode::BruteForce => "Try all 312 keys",
+            AffineMode::Encrypt => "Encrypt",
+            AffineMode::Decrypt => "Decrypt",
+        };
+        Paragraph::new(format!("Mode: {} (Tab to switch)", mode))
+            .block(Block::default().title("Mode").borders(Borders::ALL))
+            .render(layout[0], buf);
+
+        let input_style = if self.current_field == 0 {
+            Block::default()
+                .title(">> Input Text")
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title("Input Text").borders(Borders::ALL)
+        };
+        Paragraph::new(self.input.as_str())
+            .block(input_style)
+            .render(layout[1], buf);
+
+        let [a_area, b_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(layout[2]);
+
+        let a_style = if self.current_field == 1 {
+            Block::default()
+                .title(">> Key a (coprime with 26)")
+                .borders(Borders::ALL)
+        } else {
+            Block::default()
+                .title("Key a (coprime with 26)")
+                .borders(Borders::ALL)
+        };
+        Paragraph::new(self.key_a.as_str())
+            .block(a_style)
+            .render(a_area, buf);
+
+        let b_style = if self.current_field == 2 {
+            Block::default().title(">> Key b").borders(Borders::ALL)
+        } else {
+            Block::default().title("Key b").borders(Borders::ALL)
+        };
+        Paragraph::new(self.key_b.as_str())
+            .block(b_style)
+            .render(b_area, buf);
+
+        let output_title = match self.mode {
+            AffineMode::BruteForce => "Best Candidates (lowest chi-squared first)",
+            _ => "Output",
+        };
+        Paragraph::new(self.output.as_str())
+            .block(Block::default().title(output_title).borders(Borders::ALL))
+            .render(layout[3], buf);
+    }
+}
@@ -0,0 +1,74 @@
+//! Runs a long search (e.g. a hill-climbing solver) on a worker thread and shares its
+//! best result with the component that renders it.
+
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+pub struct BackgroundSearch<T> {
+    best: Arc<Mutex<Option<T>>>,
+    stop: Arc<AtomicBool>,
+    worker: Option<JoinHandle<Result<()>>>,
+}
+
+impl<T> Default for BackgroundSearch<T> {
+    fn default() -> Self {
+        Self {
+            best: Arc::new(Mutex::new(None)),
+            stop: Arc::new(AtomicBool::new(false)),
+            worker: None,
+        }
+    }
+}
+
+impl<T: Clone + Send + 'static> BackgroundSearch<T> {
+    pub fn is_running(&self) -> bool {
+        self.worker.as_ref().is_some_and(|w| !w.is_finished())
+    }
+
+    /// Latest result the search reported.
+    pub fn best(&self) -> Option<T> {
+        self.best.lock().ok()?.clone()
+    }
+
+    /// Spawns `search`, which should return once the stop flag is set and hand every
+    /// result worth showing to the callback.
+    pub fn start(
+        &mut self,
+        search: impl FnOnce(&AtomicBool, &mut dyn FnMut(&T)) -> Result<()> + Send + 'static,
+    ) {
+        let (best, stop) = (Arc::clone(&self.best), Arc::clone(&self.stop));
+        stop.store(false, Ordering::Relaxed);
+        self.worker = Some(thread::spawn(move || {
+            search(&stop, &mut |result: &T| {
+                if let Ok(mut best) = best.lock() {
+                    *best = Some(result.clone());
+                }
+            })
+        }));
+    }
+
+    /// Stops the search and returns its error, if it failed.
+    pub fn stop(&mut self) -> Result<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.worker.take() {
+            Some(worker) => worker.join().unwrap_or(Ok(())),
+            None => Ok(()),
+        }
+    }
+
+    /// Stops the search and forgets its result.
+    pub fn reset(&mut self) {
+        let _ = self.stop();
+        if let Ok(mut best) = self.best.lock() {
+            *best = None;
+        }
+    }
+}
+
+impl<T> Drop for BackgroundSearch<T> {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,409 @@
+use crate::algorithms::block_modes::{
+    BLOCK_LEN, BlockMode, BlockStep, Padding, decrypt_blocks, encrypt_blocks, encrypt_pixels,
+};
+use crate::components::cipher_component::CipherComponent;
+use anyhow::{Result, anyhow, bail};
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+use std::collections::HashMap;
+
+pub const TITLE: &str = "Block Cipher Modes";
+
+const FIELD_LABELS: [&str; 3] = [
+    "Key (hex, 16/24/32 bytes)",
+    "IV / initial counter (hex, 16 bytes)",
+    "Text",
+];
+
+/// The key and IV from NIST SP 800-38A's examples, so the trace can be checked
+/// against the published vectors straight away.
+const DEFAULT_KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+const DEFAULT_IV: &str = "000102030405060708090a0b0c0d0e0f";
+
+/// 24-bit bitmap whose rows are a whole number of AES blocks, so identical runs of
+/// pixels line up on block boundaries the way they do in the classic ECB penguin.
+const PENGUIN_BMP: &[u8] = include_bytes!("data/penguin.bmp");
+
+#[derive(Default, Debug)]
+pub enum BlockModesMode {
+    #[default]
+    Encrypt,
+    Decrypt,
+    Penguin,
+}
+
+/// Decoded 24-bit bitmap, top row first.
+struct Bitmap {
+    width: usize,
+    height: usize,
+    /// RGB triples, `width * height * 3` bytes
+    pixels: Vec<u8>,
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    match data.get(offset..offset + 4) {
+        Some(bytes) => Ok(u32::from_le_bytes(bytes.try_into()?)),
+        None => bail!("Bitmap header is truncated"),
+    }
+}
+
+/// Reads an uncompressed 24-bit BMP. Rows are stored bottom-up, BGR, padded to four
+/// bytes.
+fn decode_bmp(data: &[u8]) -> Result<Bitmap> {
+    if !data.starts_with(b"BM") {
+        bail!("Not a BMP file");
+    }
+    let offset = read_u32(data, 10)? as usize;
+    let width = read_u32(data, 18)? as usize;
+    let height = read_u32(data, 22)? as usize;
+    if data.get(28..30) != Some(&[24, 0]) {
+        bail!("Only 24-bit bitmaps are supported");
+    }
+    let stride = (width * 3).div_ceil(4) * 4;
+    if data.len() < offset + stride * height {
+        bail!("Bitmap pixel data is truncated");
+    }
+    let mut pixels = Vec::with_capacity(width * height * 3);
+    for row in (0..height).rev() {
+        let start = offset + row * stride;
+        for bgr in data[start..start + width * 3].chunks(3) {
+            pixels.extend_from_slice(&[bgr[2], bgr[1], bgr[0]]);
+        }
+    }
+    Ok(Bitmap {
+        width,
+        height,
+        pixels,
+    })
+}
+
+fn decode_hex(label: &str, text: &str) -> Result<Vec<u8>> {
+    let cleaned: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    hex::decode(&cleaned).map_err(|e| anyhow!("{} is not valid hex: {}", label, e))
+}
+
+fn hex_block(bytes: &[u8]) -> String {
+    format!("{:<width$}", hex::encode(bytes), width = BLOCK_LEN * 2)
+}
+
+/// Draws two pixel rows per terminal line with upper half blocks.
+fn image_lines(width: usize, height: usize, pixels: &[u8]) -> Vec<Line<'static>> {
+    let color = |x: usize, y: usize| {
+        let i = (y * width + x) * 3;
+        Color::Rgb(pixels[i], pixels[i + 1], pixels[i + 2])
+    };
+    (0..height)
+        .step_by(2)
+        .map(|y| {
+            Line::from(
+                (0..width)
+                    .map(|x| {
+                        let bottom = if y + 1 < height {
+                            color(x, y + 1)
+                        } else {
+                            Color::Reset
+                        };
+                        Span::styled("▀", Style::default().fg(color(x, y)).bg(bottom))
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect()
+}
+
+pub struct BlockModesComponent {
+    fields: [String; 3],
+    mode: BlockModesMode,
+    block_mode: BlockMode,
+    padding: Padding,
+    steps: Vec<BlockStep>,
+    output: String,
+    current_field: usize,
+}
+
+impl Default for BlockModesComponent {
+    fn default() -> Self {
+        Self {
+            fields: [
+                DEFAULT_KEY.to_string(),
+                DEFAULT_IV.to_string(),
+                String::new(),
+            ],
+            mode: BlockModesMode::Encrypt,
+            block_mode: BlockMode::Ecb,
+            padding: Padding::Pkcs7,
+            steps: Vec::new(),
+            output: String::new(),
+            current_field: 2,
+        }
+    }
+}
+
+impl BlockModesComponent {
+    fn key(&self) -> Result<Vec<u8>> {
+        decode_hex("Key", &self.fields[0])
+    }
+
+    fn iv(&self) -> Result<Vec<u8>> {
+        decode_hex("IV", &self.fields[1])
+    }
+
+    fn run(&mut self) -> Result<()> {
+        let key = self.key()?;
+        let iv = self.iv()?;
+        match self.mode {
+            BlockModesMode::Encrypt => {
+                let result = encrypt_blocks(
+                    self.block_mode,
+                    self.padding,
+                    &key,
+                    &iv,
+                    self.fields[2].as_bytes(),
+                )?;
+                self.output = hex::encode(&result.data);
+                self.steps = result.steps;
+            }
+            BlockModesMode::Decrypt => {
+                let ciphertext = decode_hex("Ciphertext", &self.fields[2])?;
+                let result = decrypt_blocks(self.block_mode, self.padding, &key, &iv, &ciphertext)?;
+                self.output = String::from_utf8_lossy(&result.data).into_owned();
+                self.steps = result.steps;
+            }
+            BlockModesMode::Penguin => {}
+        }
+        Ok(())
+    }
+
+    fn cycle_block_mode(&mut self, step: usize) {
+        let all = BlockMode::ALL;
+        let index = all.iter().position(|m| *m == self.block_mode).unwrap_or(0);
+        self.block_mode = all[(index + step) % all.len()];
+        self.steps.clear();
+        self.output.clear();
+    }
+
+    fn cycle_padding(&mut self, step: usize) {
+        let all = Padding::ALL;
+        let index = all.iter().position(|p| *p == self.padding).unwrap_or(0);
+        self.padding = all[(index + step) % all.len()];
+        self.steps.clear();
+        self.output.clear();
+    }
+
+    fn formula(&self) -> &'static str {
+        match self.mode {
+            BlockModesMode::Decrypt => self.block_mode.decrypt_formula(),
+            _ => self.block_mode.encrypt_formula(),
+        }
+    }
+
+    /// Each block as input, AES input, AES output and output. Output blocks seen more
+    /// than once are shown in red: that is the pattern ECB gives away.
+    fn trace_view(&self) -> Vec<Line<'_>> {
+        let decrypting = matches!(self.mode, BlockModesMode::Decrypt);
+        // ECB and CBC run the inverse cipher when decrypting
+        let op = if decrypting && self.block_mode.needs_padding() {
+            "D_K"
+        } else {
+            "E_K"
+        };
+        let (input_label, output_label) = if decrypting { ("C", "P") } else { ("P", "C") };
+
+        let mut seen: HashMap<&[u8], usize> = HashMap::new();
+        for step in &self.steps {
+            *seen.entry(step.output.as_slice()).or_default() += 1;
+        }
+
+        let mut lines = vec![Line::raw(self.formula()), Line::raw("")];
+        for (i, step) in self.steps.iter().enumerate() {
+            let repeated = seen[step.output.as_slice()] > 1;
+            let output_style = if repeated {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::Green)
+            };
+            lines.push(Line::from(vec![
+                Span::raw(format!(
+                    "#{:<3} {}_{} {}  {} in {}  {} out {}  ",
+                    i + 1,
+                    input_label,
+                    i + 1,
+                    hex_block(&step.input),
+                    op,
+                    hex::encode(step.cipher_input),
+                    op,
+                    hex::encode(step.cipher_output),
+                )),
+                Span::styled(
+                    format!("{}_{} {}", output_label, i + 1, hex::encode(&step.output)),
+                    output_style,
+                ),
+                Span::raw(if repeated { "  (repeated)" } else { "" }),
+            ]));
+        }
+        lines
+    }
+
+    fn render_penguin(&self, area: Rect, buf: &mut Buffer) {
+        // Compare ECB against whichever chaining mode is selected, CBC by default
+        let other = match self.block_mode {
+            BlockMode::Ecb => BlockMode::Cbc,
+            mode => mode,
+        };
+        let images = decode_bmp(PENGUIN_BMP).and_then(|bitmap| {
+            let key = self.key()?;
+            let iv = self.iv()?;
+            let ecb = encrypt_pixels(BlockMode::Ecb, &key, &iv, &bitmap.pixels)?;
+            let chained = encrypt_pixels(other, &key, &iv, &bitmap.pixels)?;
+            Ok((bitmap, ecb, chained))
+        });
+        let (bitmap, ecb, chained) = match images {
+            Ok(images) => images,
+            Err(err) => {
+                Paragraph::new(format!("Error: {err}"))
+                    .block(Block::default().title("Penguin").borders(Borders::ALL))
+                    .render(area, buf);
+                return;
+            }
+        };
+
+        let panels = Layout::horizontal([Constraint::Fill(1); 3]).split(area);
+        let views = [
+            ("Original".to_string(), &bitmap.pixels),
+            ("ECB".to_string(), &ecb),
+            (other.name().to_string(), &chained),
+        ];
+        for ((title, pixels), panel) in views.into_iter().zip(panels.iter()) {
+            Paragraph::new(image_lines(bitmap.width, bitmap.height, pixels))
+                .block(Block::default().title(title).borders(Borders::ALL))
+                .render(*panel, buf);
+        }
+    }
+}
+
+impl CipherComponent for BlockModesComponent {
+    fn title(&self) -> &'static str {
+        TITLE
+    }
+
+    fn output(&self) -> Option<&str> {
+        Some(self.output.as_str())
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                self.mode = match self.mode {
+                    BlockModesMode::Encrypt => BlockModesMode::Decrypt,
+                    BlockModesMode::Decrypt => BlockModesMode::Penguin,
+                    BlockModesMode::Penguin => BlockModesMode::Encrypt,
+                };
+                self.steps.clear();
+                self.output.clear();
+            }
+            KeyCode::Up => {
+                self.current_field = (self.current_field + 2) % 3;
+            }
+            KeyCode::Down => {
+                self.current_field = (self.current_field + 1) % 3;
+            }
+            KeyCode::Right => self.cycle_block_mode(1),
+            KeyCode::Left => self.cycle_block_mode(BlockMode::ALL.len() - 1),
+            KeyCode::PageDown => self.cycle_padding(1),
+            KeyCode::PageUp => self.cycle_padding(Padding::ALL.len() - 1),
+            KeyCode::Char(c) => self.fields[self.current_field].push(c),
+            KeyCode::Backspace => {
+                self.fields[self.current_field].pop();
+            }
+            KeyCode::Enter => {
+                if let Err(err) = self.run() {
+                    self.steps.clear();
+                    self.output = format!("Error: {err}");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(5),
+        ])
+        .split(area);
+
+        let mode = match self.mode {
+            BlockModesMode::Encrypt => "Encrypt",
+            BlockModesMode::Decrypt => "Decrypt",
+            BlockModesMode::Penguin => "ECB penguin",
+        };
+        let padding = if self.block_mode.needs_padding() {
+            self.padding.name()
+        } else {
+            "none needed"
+        };
+        Paragraph::new(format!(
+            "Mode: {} (Tab) | Cipher mode: {} (Left/Right) | Padding: {} (PgUp/PgDn) | Enter: run",
+            mode,
+            self.block_mode.name(),
+            padding
+        ))
+        .block(Block::default().title("Mode").borders(Borders::ALL))
+        .render(layout[0], buf);
+
+        for (i, label) in FIELD_LABELS.iter().enumerate() {
+            let label = match (i, &self.mode) {
+                (2, BlockModesMode::Encrypt) => "Plaintext",
+                (2, BlockModesMode::Decrypt) => "Ciphertext (hex)",
+                (2, BlockModesMode::Penguin) => "Text (unused by the penguin demo)",
+                _ => label,
+            };
+            let style = if self.current_field == i {
+                Block::default()
+                    .title(format!(">> {}", label))
+                    .borders(Borders::ALL)
+            } else {
+                Block::default().title(label).borders(Borders::ALL)
+            };
+            Paragraph::new(self.fields[i].as_str())
+                .block(style)
+                .render(layout[i + 1], buf);
+        }
+
+        if let BlockModesMode::Penguin = self.mode {
+            let rest = Rect {
+                height: layout[4].height + layout[5].height,
+                ..layout[4]
+            };
+            self.render_penguin(rest, buf);
+            return;
+        }
+
+        Paragraph::new(self.trace_view())
+            .block(
+                Block::default()
+                    .title("Block by Block")
+                    .borders(Borders::ALL),
+            )
+            .render(layout[4], buf);
+
+        let output_title = match self.mode {
+            BlockModesMode::Decrypt => "Plaintext",
+            _ => "Ciphertext (hex)",
+        };
+        Paragraph::new(self.output.as_str())
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title(output_title).borders(Borders::ALL))
+            .render(layout[5], buf);
+    }
+}
@@ -1,4 +1,6 @@
 use crate::algorithms::classical_ciphers::{caesar_cipher_decrypt, caesar_cipher_encrypt};
+use crate::algorithms::cryptanalysis::crack_caesar;
+use crate::algorithms::fitness::{Language, LanguageModel};
 use crate::components::cipher_component::CipherComponent;
 use ratatui::crossterm::event::KeyCode;
 use ratatui::{
@@ -6,19 +8,27 @@ use ratatui::{
     layout::{Constraint, Layout, Rect},
     widgets::{Block, Borders, Paragraph, Widget},
 };
+use std::sync::Arc;
+
+pub const TITLE: &str = "Caesar Cipher";
 
 #[derive(Default, Debug)]
 pub enum CaesarMode {
     #[default]
     Encrypt,
     Decrypt,
+    Crack,
 }
 
+const SHOWN_CANDIDATES: usize = 10;
+
 pub struct CaesarCipherComponent {
     input: String,
     key: i32,
     output: String,
     mode: CaesarMode,
+    /// Language the attack scores plaintext against
+    model: Arc<LanguageModel>,
 }
 
 impl Default for CaesarCipherComponent {
@@ -28,13 +38,47 @@ impl Default for CaesarCipherComponent {
             key: 3,
             output: String::new(),
             mode: CaesarMode::Encrypt,
+            model: LanguageModel::built_in(Language::English),
         }
     }
 }
 
+impl CaesarCipherComponent {
+    fn crack(&self) -> String {
+        crack_caesar(&self.input, &self.model)
+            .iter()
+            .take(SHOWN_CANDIDATES)
+            .enumerate()
+            .map(|(i, c)| {
+                format!(
+                    "{:>2}. shift={:<2} chi2={:>8.2} quadgram={:>6.2}  {}",
+                    i + 1,
+                    c.shift,
+                    c.chi_squared,
+                    c.quadgram,
+                    c.plaintext
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
 impl CipherComponent for CaesarCipherComponent {
     fn title(&self) -> &'static str {
-        "Caesar Cipher"
+        TITLE
+    }
+
+    fn output(&self) -> Option<&str> {
+        Some(self.output.as_str())
+    }
+
+    fn set_input(&mut self, text: &str) {
+        self.input = text.to_string();
+    }
+
+    fn set_language_model(&mut self, model: Arc<LanguageModel>) {
+        self.model = model;
     }
 
     fn handle_event(&mut self, key: KeyCode) {
@@ -46,13 +90,15 @@ impl CipherComponent for CaesarCipherComponent {
             KeyCode::Tab => {
                 self.mode = match self.mode {
                     CaesarMode::Encrypt => CaesarMode::Decrypt,
-                    CaesarMode::Decrypt => CaesarMode::Encrypt,
+                    CaesarMode::Decrypt => CaesarMode::Crack,
+                    CaesarMode::Crack => CaesarMode::Encrypt,
                 };
             }
             KeyCode::Enter => {
                 self.output = match self.mode {
                     CaesarMode::Encrypt => caesar_cipher_encrypt(self.input.clone(), self.key),
                     CaesarMode::Decrypt => caesar_cipher_decrypt(self.input.clone(), self.key),
+                    CaesarMode::Crack => self.crack(),
                 }
             }
             KeyCode::Up => {
@@ -86,8 +132,12 @@ impl CipherComponent for CaesarCipherComponent {
             .block(Block::default().title("Key").borders(Borders::ALL))
             .render(layout[2], buf);
 
+        let output_title = match self.mode {
+            CaesarMode::Crack => "Best Candidates (chi-squared and quadgram ranks combined)",
+            _ => "Output",
+        };
         Paragraph::new(self.output.as_str())
-            .block(Block::default().title("Output").borders(Borders::ALL))
+            .block(Block::default().title(output_title).borders(Borders::ALL))
             .render(layout[3], buf);
     }
 }
@@ -1,8 +1,34 @@
+use crate::algorithms::fitness::LanguageModel;
 use ratatui::crossterm::event::KeyCode;
 use ratatui::{buffer::Buffer, layout::Rect};
+use std::sync::Arc;
 
 pub trait CipherComponent {
     fn title(&self) -> &'static str;
     fn handle_event(&mut self, key: KeyCode);
     fn render(&self, area: Rect, buf: &mut Buffer);
+
+    /// Result of the last run, so a tab can hand it to another component.
+    fn output(&self) -> Option<&str> {
+        None
+    }
+
+    /// Replaces the input text. Components that do not take text ignore it.
+    fn set_input(&mut self, _text: &str) {}
+
+    /// Another component of the same tab to open on some text, as `(title, input)`.
+    /// The tab asks after every key press and switches over when there is one.
+    fn take_open_request(&mut self) -> Option<(&'static str, String)> {
+        None
+    }
+
+    /// Language the component's attacks score plaintext against. Components without
+    /// attacks ignore it.
+    fn set_language_model(&mut self, _model: Arc<LanguageModel>) {}
+
+    /// A language model chosen in this component, for the tab to hand to all the others.
+    /// Asked after every key press, like `take_open_request`.
+    fn take_language_model(&mut self) -> Option<Arc<LanguageModel>> {
+        None
+    }
 }
@@ -0,0 +1,236 @@
+use crate::algorithms::one_time_pad::{CribMatch, crib_drag, xor_ciphertexts};
+use crate::components::cipher_component::CipherComponent;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+const FIELD_LABELS: [&str; 3] = ["Ciphertext 1 (hex)", "Ciphertext 2 (hex)", "Crib"];
+
+/// Which message the crib is assumed to belong to.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CribTarget {
+    #[default]
+    Message1,
+    Message2,
+}
+
+pub struct CribDraggingComponent {
+    fields: [String; 3],
+    target: CribTarget,
+    matches: Vec<CribMatch>,
+    selected: usize,
+    /// Recovered bytes of both plaintexts, `None` where still unknown
+    recovered: [Vec<Option<u8>>; 2],
+    error: String,
+    current_field: usize,
+}
+
+impl Default for CribDraggingComponent {
+    fn default() -> Self {
+        Self {
+            fields: Default::default(),
+            target: CribTarget::Message1,
+            matches: Vec::new(),
+            selected: 0,
+            recovered: [Vec::new(), Vec::new()],
+            error: String::new(),
+            current_field: 0,
+        }
+    }
+}
+
+fn printable(bytes: impl IntoIterator<Item = Option<u8>>) -> String {
+    bytes
+        .into_iter()
+        .map(|b| match b {
+            Some(b) if b.is_ascii_graphic() || b == b' ' => b as char,
+            Some(_) => '.',
+            None => '_',
+        })
+        .collect()
+}
+
+impl CribDraggingComponent {
+    fn ciphertexts(&self) -> Result<(Vec<u8>, Vec<u8>), String> {
+        let parse = |value: &str, name: &str| {
+            let digits: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+            hex::decode(digits).map_err(|e| format!("{} is not valid hex: {}", name, e))
+        };
+        Ok((
+            parse(&self.fields[0], "Ciphertext 1")?,
+            parse(&self.fields[1], "Ciphertext 2")?,
+        ))
+    }
+
+    /// Re-runs the drag after any field changes. Recovered text survives as long as the
+    /// combined length of the ciphertexts stays the same.
+    fn refresh(&mut self) {
+        match self.ciphertexts() {
+            Ok((c1, c2)) => {
+                self.error.clear();
+                self.matches = crib_drag(&c1, &c2, self.fields[2].as_bytes());
+                self.selected = self.selected.min(self.matches.len().saturating_sub(1));
+                let len = xor_ciphertexts(&c1, &c2).len();
+                if self.recovered[0].len() != len {
+                    self.recovered = [vec![None; len], vec![None; len]];
+                }
+            }
+            Err(err) => {
+                self.error = err;
+                self.matches.clear();
+            }
+        }
+    }
+
+    /// Writes the crib and the fragment it implies into the recovered plaintexts.
+    fn accept(&mut self) {
+        let Some(m) = self.matches.get(self.selected) else {
+            return;
+        };
+        let (crib_index, other_index) = match self.target {
+            CribTarget::Message1 => (0, 1),
+            CribTarget::Message2 => (1, 0),
+        };
+        for (i, (&c, &f)) in self.fields[2]
+            .as_bytes()
+            .iter()
+            .zip(m.fragment.iter())
+            .enumerate()
+        {
+            self.recovered[crib_index][m.offset + i] = Some(c);
+            self.recovered[other_index][m.offset + i] = Some(f);
+        }
+    }
+
+    fn matches_view(&self, height: usize) -> Vec<Line<'_>> {
+        if !self.error.is_empty() {
+            return vec![Line::raw(self.error.as_str())];
+        }
+        let start = self.selected.saturating_sub(height / 2);
+        self.matches
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+            .map(|(i, m)| {
+                let text = format!(
+                    "{} {:>4}  {}",
+                    if m.plausible { '*' } else { ' ' },
+                    m.offset,
+                    printable(m.fragment.iter().copied().map(Some))
+                );
+                let style = if i == self.selected {
+                    Style::default().fg(Color::Black).bg(Color::Yellow)
+                } else if m.plausible {
+                    Style::default().add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                Line::styled(text, style)
+            })
+            .collect()
+    }
+}
+
+impl CipherComponent for CribDraggingComponent {
+    fn title(&self) -> &'static str {
+        "Two-Time Pad Lab"
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                self.target = match self.target {
+                    CribTarget::Message1 => CribTarget::Message2,
+                    CribTarget::Message2 => CribTarget::Message1,
+                };
+            }
+            KeyCode::Up => {
+                self.current_field = (self.current_field + 2) % 3;
+            }
+            KeyCode::Down => {
+                self.current_field = (self.current_field + 1) % 3;
+            }
+            KeyCode::PageDown | KeyCode::Right if !self.matches.is_empty() => {
+                self.selected = (self.selected + 1) % self.matches.len();
+            }
+            KeyCode::PageUp | KeyCode::Left if !self.matches.is_empty() => {
+                self.selected = (self.selected + self.matches.len() - 1) % self.matches.len();
+            }
+            KeyCode::Char(c) => {
+                self.fields[self.current_field].push(c);
+                self.refresh();
+            }
+            KeyCode::Backspace => {
+                self.fields[self.current_field].pop();
+                self.refresh();
+            }
+            KeyCode::Enter => self.accept(),
+            KeyCode::Delete => {
+                for message in self.recovered.iter_mut() {
+                    message.fill(None);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(6),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        Paragraph::new(format!(
+            "Crib belongs to: {:?} (Tab to switch) | Left/Right: pick offset | Enter: accept | Del: clear",
+            self.target
+        ))
+        .block(Block::default().title("Mode").borders(Borders::ALL))
+        .render(layout[0], buf);
+
+        for (i, label) in FIELD_LABELS.iter().enumerate() {
+            let style = if self.current_field == i {
+                Block::default()
+                    .title(format!(">> {}", label))
+                    .borders(Borders::ALL)
+            } else {
+                Block::default().title(*label).borders(Borders::ALL)
+            };
+            Paragraph::new(self.fields[i].as_str())
+                .block(style)
+                .render(layout[i + 1], buf);
+        }
+
+        Paragraph::new(format!(
+            "Message 1: {}\nMessage 2: {}",
+            printable(self.recovered[0].iter().copied()),
+            printable(self.recovered[1].iter().copied())
+        ))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title("Recovered Plaintexts")
+                .borders(Borders::ALL),
+        )
+        .render(layout[4], buf);
+
+        let height = layout[5].height.saturating_sub(2) as usize;
+        Paragraph::new(self.matches_view(height))
+            .block(
+                Block::default()
+                    .title("Crib Positions (* = printable result)")
+                    .borders(Borders::ALL),
+            )
+            .render(layout[5], buf);
+    }
+}
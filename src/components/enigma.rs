@@ -0,0 +1,249 @@
+use crate::algorithms::enigma::EnigmaMachine;
+use crate::components::cipher_component::CipherComponent;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+const LAMP_ROWS: [&str; 3] = ["QWERTZUIO", "ASDFGHJK", "PYXCVBNML"];
+const SETTING_LABELS: [&str; 5] = [
+    "Reflector",
+    "Rotors (left to right)",
+    "Ring settings",
+    "Start positions",
+    "Plugboard",
+];
+const KEYBOARD_FIELD: usize = 5;
+
+pub struct EnigmaComponent {
+    settings: [String; 5],
+    input: String,
+    output: String,
+    lamp: Option<char>,
+    machine: Option<EnigmaMachine>,
+    error: String,
+    current_field: usize,
+}
+
+impl Default for EnigmaComponent {
+    fn default() -> Self {
+        let mut component = Self {
+            settings: [
+                String::from("B"),
+                String::from("I II III"),
+                String::from("AAA"),
+                String::from("AAA"),
+                String::new(),
+            ],
+            input: String::new(),
+            output: String::new(),
+            lamp: None,
+            machine: None,
+            error: String::new(),
+            current_field: KEYBOARD_FIELD,
+        };
+        component.rebuild();
+        component
+    }
+}
+
+impl EnigmaComponent {
+    /// Sets the machine up from the current settings and replays everything typed so far,
+    /// so editing a setting or deleting a letter always shows a consistent machine state.
+    fn rebuild(&mut self) {
+        let [reflector, rotors, rings, positions, plugboard] = &self.settings;
+        match EnigmaMachine::from_settings(reflector, rotors, rings, positions, plugboard) {
+            Ok(mut machine) => {
+                self.output = machine.process(&self.input);
+                self.lamp = self
+                    .input
+                    .chars()
+                    .zip(self.output.chars())
+                    .filter(|(c, _)| c.is_ascii_alphabetic())
+                    .last()
+                    .map(|(_, lit)| lit);
+                self.machine = Some(machine);
+                self.error.clear();
+            }
+            Err(err) => {
+                self.machine = None;
+                self.lamp = None;
+                self.error = err.to_string();
+            }
+        }
+    }
+
+    fn press(&mut self, c: char) {
+        self.input.push(c);
+        match self.machine.as_mut() {
+            Some(machine) => match machine.press(c) {
+                Some(lit) => {
+                    self.output.push(lit);
+                    self.lamp = Some(lit);
+                }
+                None => self.output.push(c),
+            },
+            None => self.rebuild(),
+        }
+    }
+
+    fn settings_view(&self) -> Vec<Line<'_>> {
+        SETTING_LABELS
+            .iter()
+            .zip(self.settings.iter())
+            .enumerate()
+            .map(|(i, (label, value))| {
+                let marker = if i == self.current_field {
+                    ">> "
+                } else {
+                    "   "
+                };
+                Line::raw(format!("{}{:<24}{}", marker, label, value))
+            })
+            .collect()
+    }
+
+    fn rotor_view(&self) -> Vec<Line<'_>> {
+        let Some(machine) = &self.machine else {
+            return vec![Line::raw(format!("Invalid settings: {}", self.error))];
+        };
+
+        let names = machine
+            .rotors
+            .iter()
+            .map(|r| format!("{:^7}", r.name))
+            .collect::<String>();
+        let windows = machine
+            .rotors
+            .iter()
+            .map(|r| format!("  [{}]  ", r.window()))
+            .collect::<String>();
+        let rings = machine
+            .rotors
+            .iter()
+            .map(|r| format!("{:^7}", format!("{:02}", r.ring + 1)))
+            .collect::<String>();
+
+        vec![
+            Line::raw(format!(
+                "Reflector {}   Windows {}",
+                machine.reflector.name,
+                machine.windows()
+            )),
+            Line::raw(names),
+            Line::styled(windows, Style::default().add_modifier(Modifier::BOLD)),
+            Line::raw(rings),
+        ]
+    }
+
+    fn lamp_view(&self) -> Vec<Line<'_>> {
+        LAMP_ROWS
+            .iter()
+            .enumerate()
+            .map(|(row, letters)| {
+                let mut spans = vec![Span::raw(" ".repeat(row * 2))];
+                for c in letters.chars() {
+                    let style = if self.lamp == Some(c) {
+                        Style::default()
+                            .fg(Color::Black)
+                            .bg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    };
+                    spans.push(Span::styled(format!(" {} ", c), style));
+                    spans.push(Span::raw(" "));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    }
+}
+
+impl CipherComponent for EnigmaComponent {
+    fn title(&self) -> &'static str {
+        "Enigma Machine"
+    }
+
+    fn output(&self) -> Option<&str> {
+        Some(self.output.as_str())
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Up => {
+                self.current_field = (self.current_field + KEYBOARD_FIELD) % (KEYBOARD_FIELD + 1);
+            }
+            KeyCode::Down => {
+                self.current_field = (self.current_field + 1) % (KEYBOARD_FIELD + 1);
+            }
+            KeyCode::Char(c) => {
+                if self.current_field == KEYBOARD_FIELD {
+                    self.press(c);
+                } else {
+                    self.settings[self.current_field].push(c);
+                    self.rebuild();
+                }
+            }
+            KeyCode::Backspace => {
+                if self.current_field == KEYBOARD_FIELD {
+                    self.input.pop();
+                } else {
+                    self.settings[self.current_field].pop();
+                }
+                self.rebuild();
+            }
+            KeyCode::Tab => {
+                // Clear the message and return the rotors to their start positions
+                self.input.clear();
+                self.rebuild();
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(7),
+            Constraint::Length(6),
+            Constraint::Length(5),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        Paragraph::new(self.settings_view())
+            .block(
+                Block::default()
+                    .title("Settings (Up/Down to move, Tab to reset)")
+                    .borders(Borders::ALL),
+            )
+            .render(layout[0], buf);
+
+        Paragraph::new(self.rotor_view())
+            .block(Block::default().title("Rotors").borders(Borders::ALL))
+            .render(layout[1], buf);
+
+        Paragraph::new(self.lamp_view())
+            .block(Block::default().title("Lamp Board").borders(Borders::ALL))
+            .render(layout[2], buf);
+
+        let keyboard_style = if self.current_field == KEYBOARD_FIELD {
+            Block::default().title(">> Keyboard").borders(Borders::ALL)
+        } else {
+            Block::default().title("Keyboard").borders(Borders::ALL)
+        };
+        Paragraph::new(self.input.as_str())
+            .block(keyboard_style)
+            .render(layout[3], buf);
+
+        Paragraph::new(self.output.as_str())
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Output").borders(Borders::ALL))
+            .render(layout[4], buf);
+    }
+}
@@ -0,0 +1,380 @@
+use crate::algorithms::classical_ciphers::{
+    BaconVariant, Chaocipher, StraddlingCheckerboard, bacon_hide, bacon_reveal, baconian_decrypt,
+    baconian_encrypt, chaocipher_decrypt, chaocipher_encrypt, checkerboard_decrypt,
+    checkerboard_encrypt, fractionated_morse_decrypt, fractionated_morse_encrypt, morse_encode,
+    parse_checkerboard_blanks, pollux_decrypt, pollux_encrypt,
+};
+use crate::components::cipher_component::CipherComponent;
+use anyhow::Result;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+pub const TITLE: &str = "Baconian, Morse and Field Ciphers";
+
+#[derive(Default, Debug)]
+pub enum FieldCipherMode {
+    #[default]
+    Encrypt,
+    Decrypt,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldCipherVariant {
+    #[default]
+    Baconian24,
+    Baconian26,
+    FractionatedMorse,
+    Pollux,
+    Chaocipher,
+    Checkerboard,
+}
+
+impl FieldCipherVariant {
+    const ALL: [Self; 6] = [
+        Self::Baconian24,
+        Self::Baconian26,
+        Self::FractionatedMorse,
+        Self::Pollux,
+        Self::Chaocipher,
+        Self::Checkerboard,
+    ];
+
+    fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        let idx = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Baconian24 => "Baconian (24 letters)",
+            Self::Baconian26 => "Baconian (26 letters)",
+            Self::FractionatedMorse => "Fractionated Morse",
+            Self::Pollux => "Pollux",
+            Self::Chaocipher => "Chaocipher",
+            Self::Checkerboard => "VIC straddling checkerboard",
+        }
+    }
+
+    fn key_labels(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Baconian24 | Self::Baconian26 => {
+                ("Cover text (optional, case = typeface)", "(unused)")
+            }
+            Self::FractionatedMorse => ("Keyword", "(unused)"),
+            Self::Pollux => ("Digits 0-9 as . - x (e.g. ..-x.-x-x.)", "(unused)"),
+            Self::Chaocipher => ("Left (cipher) alphabet", "Right (plain) alphabet"),
+            Self::Checkerboard => ("Keyword (e.g. ESTONIAR)", "Blank columns (e.g. 2 6)"),
+        }
+    }
+
+    fn bacon(self) -> Option<BaconVariant> {
+        match self {
+            Self::Baconian24 => Some(BaconVariant::TwentyFour),
+            Self::Baconian26 => Some(BaconVariant::TwentySix),
+            _ => None,
+        }
+    }
+}
+
+pub struct FieldCiphersComponent {
+    input: String,
+    key1: String,
+    key2: String,
+    output: String,
+    mode: FieldCipherMode,
+    variant: FieldCipherVariant,
+    current_field: usize,
+}
+
+impl Default for FieldCiphersComponent {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            key1: String::new(),
+            key2: String::new(),
+            output: String::new(),
+            mode: FieldCipherMode::Encrypt,
+            variant: FieldCipherVariant::Baconian24,
+            current_field: 0,
+        }
+    }
+}
+
+/// Cover text with B letters (upper case) in bold and underlined, A letters plain.
+fn typeface_line(cover: &str) -> Line<'_> {
+    let spans = cover
+        .chars()
+        .map(|c| {
+            if c.is_ascii_uppercase() {
+                Span::styled(
+                    c.to_ascii_lowercase().to_string(),
+                    Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                )
+            } else {
+                Span::raw(c.to_string())
+            }
+        })
+        .collect::<Vec<_>>();
+    Line::from(spans)
+}
+
+fn format_checkerboard(board: &StraddlingCheckerboard) -> String {
+    let header = (0..10).map(|d| format!(" {}", d)).collect::<String>();
+    let labels = [
+        String::from(" "),
+        board.blanks.0.to_string(),
+        board.blanks.1.to_string(),
+    ];
+    let rows = board
+        .rows
+        .iter()
+        .zip(labels)
+        .map(|(row, label)| {
+            let cells = row
+                .iter()
+                .map(|c| format!(" {}", c.unwrap_or(' ')))
+                .collect::<String>();
+            format!("{} |{}", label, cells)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("   {}\n   {}\n{}", header, "-".repeat(header.len()), rows)
+}
+
+impl FieldCiphersComponent {
+    fn run(&self) -> Result<String> {
+        let (input, key1, key2) = (self.input.clone(), self.key1.clone(), self.key2.clone());
+        let encrypt = matches!(self.mode, FieldCipherMode::Encrypt);
+
+        match self.variant {
+            FieldCipherVariant::Baconian24 | FieldCipherVariant::Baconian26 => {
+                let variant = self.variant.bacon().unwrap_or_default();
+                if encrypt {
+                    let symbols = baconian_encrypt(input, variant);
+                    if key1.trim().is_empty() {
+                        Ok(symbols)
+                    } else {
+                        bacon_hide(&symbols, &key1)
+                    }
+                } else {
+                    // Anything but A/B means the message is hidden in a cover text
+                    let hidden = input
+                        .chars()
+                        .any(|c| c.is_ascii_alphabetic() && !"ABab".contains(c));
+                    let mut symbols = if hidden { bacon_reveal(&input) } else { input };
+                    symbols.retain(|c| "ABab".contains(c));
+                    symbols.truncate(symbols.len() - symbols.len() % 5);
+                    baconian_decrypt(symbols, variant)
+                }
+            }
+            FieldCipherVariant::FractionatedMorse if encrypt => {
+                fractionated_morse_encrypt(input, key1)
+            }
+            FieldCipherVariant::FractionatedMorse => fractionated_morse_decrypt(input, key1),
+            FieldCipherVariant::Pollux if encrypt => pollux_encrypt(input, key1),
+            FieldCipherVariant::Pollux => pollux_decrypt(input, key1),
+            FieldCipherVariant::Chaocipher if encrypt => chaocipher_encrypt(input, key1, key2),
+            FieldCipherVariant::Chaocipher => chaocipher_decrypt(input, key1, key2),
+            FieldCipherVariant::Checkerboard if encrypt => checkerboard_encrypt(input, key1, key2),
+            FieldCipherVariant::Checkerboard => checkerboard_decrypt(input, key1, key2),
+        }
+    }
+
+    /// The intermediate stage worth seeing for each cipher.
+    fn detail_view(&self) -> Vec<Line<'_>> {
+        let plaintext = match self.mode {
+            FieldCipherMode::Encrypt => self.input.as_str(),
+            FieldCipherMode::Decrypt => self.output.as_str(),
+        };
+
+        match self.variant {
+            FieldCipherVariant::Baconian24 | FieldCipherVariant::Baconian26 => {
+                let cover = match self.mode {
+                    FieldCipherMode::Encrypt => self.output.as_str(),
+                    FieldCipherMode::Decrypt => self.input.as_str(),
+                };
+                vec![
+                    Line::raw("Typeface B shown bold and underlined:"),
+                    typeface_line(cover),
+                ]
+            }
+            FieldCipherVariant::FractionatedMorse | FieldCipherVariant::Pollux => {
+                vec![Line::raw("Morse:"), Line::raw(morse_encode(plaintext))]
+            }
+            FieldCipherVariant::Chaocipher => {
+                let Ok(mut machine) = Chaocipher::new(&self.key1, &self.key2) else {
+                    return vec![Line::raw("Both alphabets must be permutations of A-Z")];
+                };
+                let mut lines = vec![
+                    Line::raw(format!(
+                        "Start  left: {}",
+                        machine.left.iter().collect::<String>()
+                    )),
+                    Line::raw(format!(
+                        "      right: {}",
+                        machine.right.iter().collect::<String>()
+                    )),
+                ];
+                for c in plaintext.chars().filter(|c| c.is_ascii_alphabetic()) {
+                    machine.encrypt_letter(c.to_ascii_uppercase());
+                }
+                lines.push(Line::raw(format!(
+                    "Now    left: {}",
+                    machine.left.iter().collect::<String>()
+                )));
+                lines.push(Line::raw(format!(
+                    "      right: {}",
+                    machine.right.iter().collect::<String>()
+                )));
+                lines
+            }
+            FieldCipherVariant::Checkerboard => {
+                let board = parse_checkerboard_blanks(&self.key2)
+                    .and_then(|blanks| StraddlingCheckerboard::new(&self.key1, blanks));
+                match board {
+                    Ok(board) => format_checkerboard(&board)
+                        .lines()
+                        .map(|l| Line::raw(l.to_string()))
+                        .collect(),
+                    Err(err) => vec![Line::raw(err.to_string())],
+                }
+            }
+        }
+    }
+}
+
+impl CipherComponent for FieldCiphersComponent {
+    fn title(&self) -> &'static str {
+        TITLE
+    }
+
+    fn output(&self) -> Option<&str> {
+        Some(self.output.as_str())
+    }
+
+    fn set_input(&mut self, text: &str) {
+        self.input = text.to_string();
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                self.mode = match self.mode {
+                    FieldCipherMode::Encrypt => FieldCipherMode::Decrypt,
+                    FieldCipherMode::Decrypt => FieldCipherMode::Encrypt,
+                };
+            }
+            KeyCode::Right => {
+                self.variant = self.variant.next();
+            }
+            KeyCode::Left => {
+                self.variant = self.variant.previous();
+            }
+            KeyCode::Up => {
+                self.current_field = (self.current_field + 2) % 3;
+            }
+            KeyCode::Down => {
+                self.current_field = (self.current_field + 1) % 3;
+            }
+            KeyCode::Char(c) => match self.current_field {
+                0 => self.input.push(c),
+                1 => self.key1.push(c),
+                2 => self.key2.push(c),
+                _ => unreachable!(),
+            },
+            KeyCode::Backspace => match self.current_field {
+                0 => {
+                    self.input.pop();
+                }
+                1 => {
+                    self.key1.pop();
+                }
+                2 => {
+                    self.key2.pop();
+                }
+                _ => unreachable!(),
+            },
+            KeyCode::Enter => {
+                self.output = self.run().unwrap_or_else(|err| format!("Error: {err}"));
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(4),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        Paragraph::new(format!(
+            "Mode: {:?} (Tab to switch) | Cipher: {} (Left/Right to switch)",
+            self.mode,
+            self.variant.name()
+        ))
+        .block(Block::default().title("Mode").borders(Borders::ALL))
+        .render(layout[0], buf);
+
+        let input_style = if self.current_field == 0 {
+            Block::default()
+                .title(">> Input Text")
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title("Input Text").borders(Borders::ALL)
+        };
+        Paragraph::new(self.input.as_str())
+            .block(input_style)
+            .render(layout[1], buf);
+
+        let [key1_area, key2_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(layout[2]);
+        let (key1_label, key2_label) = self.variant.key_labels();
+
+        let key1_style = if self.current_field == 1 {
+            Block::default()
+                .title(format!(">> {}", key1_label))
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title(key1_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.key1.as_str())
+            .block(key1_style)
+            .render(key1_area, buf);
+
+        let key2_style = if self.current_field == 2 {
+            Block::default()
+                .title(format!(">> {}", key2_label))
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title(key2_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.key2.as_str())
+            .block(key2_style)
+            .render(key2_area, buf);
+
+        Paragraph::new(self.output.as_str())
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Output").borders(Borders::ALL))
+            .render(layout[3], buf);
+
+        Paragraph::new(self.detail_view())
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Details").borders(Borders::ALL))
+            .render(layout[4], buf);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::algorithms::fitness::{Language, LanguageModel};
+use crate::algorithms::text_analysis::{NgramCount, TextAnalysis, analyze_text};
+use crate::components::cipher_component::CipherComponent;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Paragraph, Widget, Wrap},
+};
+use std::sync::Arc;
+
+pub const TITLE: &str = "Frequency Analysis";
+
+const SHOWN_NGRAMS: usize = 12;
+
+/// Letter statistics of whatever is typed in, recomputed on every key.
+pub struct FrequencyAnalysisComponent {
+    input: String,
+    analysis: TextAnalysis,
+    /// Language the letter shares are charted against
+    model: Arc<LanguageModel>,
+}
+
+impl Default for FrequencyAnalysisComponent {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            analysis: TextAnalysis::default(),
+            model: LanguageModel::built_in(Language::English),
+        }
+    }
+}
+
+fn ngram_lines<'a>(title: &'a str, ngrams: &[NgramCount]) -> Vec<Line<'a>> {
+    let mut lines = vec![Line::raw(title)];
+    lines.extend(
+        ngrams
+            .iter()
+            .take(SHOWN_NGRAMS)
+            .map(|n| Line::raw(format!("{:<4}{:>5}", n.ngram, n.count))),
+    );
+    lines
+}
+
+impl FrequencyAnalysisComponent {
+    /// One group per letter: observed share next to the language's share, in tenths of a
+    /// percent.
+    fn letter_groups(&self) -> Vec<BarGroup<'static>> {
+        let total = self.analysis.letters.max(1) as f64;
+        let expected = self.model.frequencies();
+        (0..26)
+            .map(|i| {
+                let observed = self.analysis.monograms[i] as f64 / total;
+                BarGroup::default()
+                    .label(Line::raw(((b'A' + i as u8) as char).to_string()))
+                    .bars(&[
+                        Bar::default()
+                            .value((observed * 1000.0).round() as u64)
+                            .text_value(String::new())
+                            .style(Style::default().fg(Color::Yellow)),
+                        Bar::default()
+                            .value((expected[i] * 1000.0).round() as u64)
+                            .text_value(String::new())
+                            .style(Style::default().fg(Color::DarkGray)),
+                    ])
+            })
+            .collect()
+    }
+
+    fn summary(&self) -> String {
+        let analysis = &self.analysis;
+        let chi = analysis
+            .chi_squared
+            .iter()
+            .map(|(language, chi)| format!("{} {:.1}", language, chi))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "Letters: {} | IoC: {:.4} ({} {:.4}, random 0.0385) | Entropy: {:.2} bits (English 4.18, random 4.70)\nChi-squared: {}",
+            analysis.letters,
+            analysis.ioc,
+            self.model.name(),
+            self.model.expected_ioc(),
+            analysis.entropy,
+            chi
+        )
+    }
+}
+
+impl CipherComponent for FrequencyAnalysisComponent {
+    fn title(&self) -> &'static str {
+        TITLE
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Delete => self.input.clear(),
+            _ => return,
+        }
+        self.analysis = analyze_text(&self.input);
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        Paragraph::new(
+            "Type or paste text, or press F2 in any classical cipher to analyse its output | Del: clear",
+        )
+        .block(Block::default().title("Mode").borders(Borders::ALL))
+        .render(layout[0], buf);
+
+        Paragraph::new(self.input.as_str())
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .title(">> Input Text")
+                    .borders(Borders::ALL),
+            )
+            .render(layout[1], buf);
+
+        Paragraph::new(self.summary())
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Statistics").borders(Borders::ALL))
+            .render(layout[2], buf);
+
+        let [chart_area, bigram_area, trigram_area] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(13),
+            Constraint::Length(13),
+        ])
+        .areas(layout[3]);
+
+        let mut chart = BarChart::default()
+            .block(
+                Block::default()
+                    .title(format!(
+                        "Letters: observed (yellow) vs {} (grey), per mille",
+                        self.model.name()
+                    ))
+                    .borders(Borders::ALL),
+            )
+            .bar_width(1)
+            .bar_gap(0)
+            .group_gap(1);
+        for group in self.letter_groups() {
+            chart = chart.data(group);
+        }
+        chart.render(chart_area, buf);
+
+        Paragraph::new(ngram_lines("Bigrams", &self.analysis.bigrams))
+            .block(Block::default().borders(Borders::ALL))
+            .render(bigram_area, buf);
+        Paragraph::new(ngram_lines("Trigrams", &self.analysis.trigrams))
+            .block(Block::default().borders(Borders::ALL))
+            .render(trigram_area, buf);
+    }
+
+    fn set_input(&mut self, text: &str) {
+        self.input = text.to_string();
+        self.analysis = analyze_text(&self.input);
+    }
+
+    fn set_language_model(&mut self, model: Arc<LanguageModel>) {
+        self.model = model;
+    }
+}
@@ -7,20 +7,15 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Widget},
 };
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
 enum Algo {
     Md5,
     Sha1,
+    #[default]
     Sha256,
     Sha512,
 }
 
-impl Default for Algo {
-    fn default() -> Self {
-        Self::Sha256
-    }
-}
-
 impl Algo {
     fn next(self) -> Self {
         match self {
@@ -0,0 +1,227 @@
+use crate::algorithms::classical_ciphers::{
+    hill_cipher_decrypt, hill_cipher_encrypt, hill_known_plaintext_attack, parse_hill_key,
+};
+use crate::algorithms::math::determinant_mod;
+use crate::components::cipher_component::CipherComponent;
+use anyhow::Result;
+use nalgebra::DMatrix;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+pub const TITLE: &str = "Hill Cipher";
+
+#[derive(Default, Debug)]
+pub enum HillMode {
+    #[default]
+    Encrypt,
+    Decrypt,
+    /// Recover the key from known plaintext and its ciphertext
+    Attack,
+}
+
+/// Largest key the known-plaintext attack offers.
+const MAX_ATTACK_SIZE: usize = 6;
+
+fn matrix_rows(matrix: &DMatrix<i32>) -> String {
+    matrix
+        .row_iter()
+        .map(|row| {
+            row.iter()
+                .map(|v| format!("{:>3}", v.rem_euclid(26)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub struct HillCipherComponent {
+    input: String,
+    key: String,
+    output: String,
+    mode: HillMode,
+    /// Key size assumed by the known-plaintext attack
+    attack_size: usize,
+    current_field: usize,
+}
+
+impl Default for HillCipherComponent {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            key: String::new(),
+            output: String::new(),
+            mode: HillMode::Encrypt,
+            attack_size: 2,
+            current_field: 0,
+        }
+    }
+}
+
+impl HillCipherComponent {
+    fn key_preview(&self) -> String {
+        if let HillMode::Attack = self.mode {
+            return format!(
+                "Needs at least {} known letters; more help when some blocks are not invertible.\nThe recovered key appears in the output.",
+                self.attack_size * self.attack_size
+            );
+        }
+        if self.key.trim().is_empty() {
+            return String::from("Type numbers (e.g. 3 3; 2 5) or a keyword of n*n letters");
+        }
+
+        match parse_hill_key(&self.key) {
+            Ok(matrix) => format!(
+                "{}\n\n{}x{} matrix, det mod 26 = {}",
+                matrix_rows(&matrix),
+                matrix.nrows(),
+                matrix.ncols(),
+                determinant_mod(&matrix, 26)
+            ),
+            Err(err) => format!("Invalid key: {err}"),
+        }
+    }
+
+    /// Recovers the key from the known plaintext and decrypts the whole ciphertext with it.
+    fn attack(&self) -> Result<String> {
+        let key =
+            hill_known_plaintext_attack(self.input.clone(), self.key.clone(), self.attack_size)?;
+        let plaintext = hill_cipher_decrypt(self.key.clone(), key.clone())?;
+        Ok(format!(
+            "Recovered key:\n{}\n\nDecryption:\n{}",
+            matrix_rows(&key),
+            plaintext
+        ))
+    }
+}
+
+impl CipherComponent for HillCipherComponent {
+    fn title(&self) -> &'static str {
+        TITLE
+    }
+
+    fn output(&self) -> Option<&str> {
+        Some(self.output.as_str())
+    }
+
+    fn set_input(&mut self, text: &str) {
+        self.input = text.to_string();
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                self.mode = match self.mode {
+                    HillMode::Encrypt => HillMode::Decrypt,
+                    HillMode::Decrypt => HillMode::Attack,
+                    HillMode::Attack => HillMode::Encrypt,
+                };
+            }
+            KeyCode::Right => {
+                self.attack_size = self.attack_size % MAX_ATTACK_SIZE + 1;
+            }
+            KeyCode::Left => {
+                self.attack_size = (self.attack_size + MAX_ATTACK_SIZE - 2) % MAX_ATTACK_SIZE + 1;
+            }
+            KeyCode::Enter if matches!(self.mode, HillMode::Attack) => {
+                self.output = self.attack().unwrap_or_else(|err| format!("Error: {err}"));
+            }
+            KeyCode::Up => {
+                self.current_field = (self.current_field + 1) % 2;
+            }
+            KeyCode::Down => {
+                self.current_field = (self.current_field + 1) % 2;
+            }
+            KeyCode::Char(c) => match self.current_field {
+                0 => self.input.push(c),
+                1 => self.key.push(c),
+                _ => unreachable!(),
+            },
+            KeyCode::Backspace => match self.current_field {
+                0 => {
+                    self.input.pop();
+                }
+                1 => {
+                    self.key.pop();
+                }
+                _ => unreachable!(),
+            },
+            KeyCode::Enter => {
+                self.output = match parse_hill_key(&self.key) {
+                    Ok(matrix) => match self.mode {
+                        HillMode::Encrypt => hill_cipher_encrypt(self.input.clone(), matrix)
+                            .unwrap_or_else(|| String::from("Key must be a square matrix")),
+                        HillMode::Decrypt => hill_cipher_decrypt(self.input.clone(), matrix)
+                            .unwrap_or_else(|err| format!("Decryption failed: {err}")),
+                        HillMode::Attack => unreachable!(),
+                    },
+                    Err(err) => format!("Invalid key: {err}"),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(6),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        let mode_line = match self.mode {
+            HillMode::Attack => format!(
+                "Mode: Known plaintext (Tab to switch) | Key size: {0}x{0} (Left/Right to change)",
+                self.attack_size
+            ),
+            _ => format!("Mode: {:?} (Tab to switch)", self.mode),
+        };
+        Paragraph::new(mode_line)
+            .block(Block::default().title("Mode").borders(Borders::ALL))
+            .render(layout[0], buf);
+
+        let (input_label, key_label) = match self.mode {
+            HillMode::Attack => (
+                "Known plaintext (from the start of the message)",
+                "Ciphertext",
+            ),
+            _ => ("Input Text", "Key (matrix or keyword)"),
+        };
+        let input_style = if self.current_field == 0 {
+            Block::default()
+                .title(format!(">> {}", input_label))
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title(input_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.input.as_str())
+            .block(input_style)
+            .render(layout[1], buf);
+
+        let key_style = if self.current_field == 1 {
+            Block::default()
+                .title(format!(">> {}", key_label))
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title(key_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.key.as_str())
+            .block(key_style)
+            .render(layout[2], buf);
+
+        Paragraph::new(self.key_preview())
+            .block(Block::default().title("Key Matrix").borders(Borders::ALL))
+            .render(layout[3], buf);
+
+        Paragraph::new(self.output.as_str())
+            .block(Block::default().title("Output").borders(Borders::ALL))
+            .render(layout[4], buf);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::algorithms::fitness::{Language, LanguageModel};
+use crate::algorithms::identify::{CipherFamily, CipherGuess, identify_cipher};
+use crate::components::cipher_component::CipherComponent;
+use crate::components::{
+    caesar, field_ciphers, hill, playfair, polybius, substitution, transposition, vigenere,
+};
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+use std::sync::Arc;
+
+/// Component of the Classical tab that handles a family, if there is one.
+fn component_for(family: CipherFamily) -> Option<&'static str> {
+    match family {
+        CipherFamily::Transposition => Some(transposition::TITLE),
+        CipherFamily::Caesar => Some(caesar::TITLE),
+        CipherFamily::Monoalphabetic => Some(substitution::TITLE),
+        CipherFamily::Polyalphabetic => Some(vigenere::TITLE),
+        CipherFamily::Playfair => Some(playfair::TITLE),
+        CipherFamily::Hill => Some(hill::TITLE),
+        CipherFamily::Bifid | CipherFamily::Adfgvx | CipherFamily::PolybiusDigits => {
+            Some(polybius::TITLE)
+        }
+        CipherFamily::StraddlingCheckerboard | CipherFamily::Baconian | CipherFamily::Morse => {
+            Some(field_ciphers::TITLE)
+        }
+        CipherFamily::Hex | CipherFamily::Base64 => None,
+    }
+}
+
+/// Ranks likely cipher families for the input as it is typed.
+pub struct CipherIdentifierComponent {
+    input: String,
+    guesses: Vec<CipherGuess>,
+    selected: usize,
+    open_request: Option<(&'static str, String)>,
+    /// Language the plaintext is assumed to be in
+    model: Arc<LanguageModel>,
+}
+
+impl Default for CipherIdentifierComponent {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            guesses: Vec::new(),
+            selected: 0,
+            open_request: None,
+            model: LanguageModel::built_in(Language::English),
+        }
+    }
+}
+
+impl CipherIdentifierComponent {
+    fn refresh(&mut self) {
+        self.guesses = identify_cipher(&self.input, &self.model);
+        self.selected = 0;
+    }
+
+    fn guess_lines(&self) -> Vec<Line<'_>> {
+        if self.guesses.is_empty() {
+            return vec![Line::raw("No guesses yet: type or paste some ciphertext")];
+        }
+        self.guesses
+            .iter()
+            .enumerate()
+            .map(|(i, guess)| {
+                let text = format!(
+                    "{:>3.0}%  {:<42} {}",
+                    guess.score * 100.0,
+                    guess.family.name(),
+                    component_for(guess.family).unwrap_or("(no component)")
+                );
+                if i == self.selected {
+                    Line::styled(text, Style::default().fg(Color::Black).bg(Color::Yellow))
+                } else {
+                    Line::raw(text)
+                }
+            })
+            .collect()
+    }
+
+    fn details(&self) -> String {
+        self.guesses
+            .get(self.selected)
+            .map(|guess| {
+                guess
+                    .reasons
+                    .iter()
+                    .map(|reason| format!("- {}", reason))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl CipherComponent for CipherIdentifierComponent {
+    fn title(&self) -> &'static str {
+        "Cipher Identifier"
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.refresh();
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.refresh();
+            }
+            KeyCode::Delete => {
+                self.input.clear();
+                self.refresh();
+            }
+            KeyCode::Down if !self.guesses.is_empty() => {
+                self.selected = (self.selected + 1) % self.guesses.len();
+            }
+            KeyCode::Up if !self.guesses.is_empty() => {
+                self.selected = (self.selected + self.guesses.len() - 1) % self.guesses.len();
+            }
+            KeyCode::Enter => {
+                if let Some(title) = self
+                    .guesses
+                    .get(self.selected)
+                    .and_then(|guess| component_for(guess.family))
+                {
+                    self.open_request = Some((title, self.input.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(5),
+            Constraint::Fill(1),
+            Constraint::Length(6),
+        ])
+        .split(area);
+
+        Paragraph::new(
+            "Up/Down: pick a guess | Enter: open its cipher with this text | Del: clear",
+        )
+        .block(Block::default().title("Mode").borders(Borders::ALL))
+        .render(layout[0], buf);
+
+        Paragraph::new(self.input.as_str())
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .title(">> Ciphertext")
+                    .borders(Borders::ALL),
+            )
+            .render(layout[1], buf);
+
+        Paragraph::new(self.guess_lines())
+            .block(
+                Block::default()
+                    .title("Likely Cipher Families")
+                    .borders(Borders::ALL),
+            )
+            .render(layout[2], buf);
+
+        Paragraph::new(self.details())
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Evidence").borders(Borders::ALL))
+            .render(layout[3], buf);
+    }
+
+    fn set_input(&mut self, text: &str) {
+        self.input = text.to_string();
+        self.refresh();
+    }
+
+    fn take_open_request(&mut self) -> Option<(&'static str, String)> {
+        self.open_request.take()
+    }
+
+    fn set_language_model(&mut self, model: Arc<LanguageModel>) {
+        self.model = model;
+        self.refresh();
+    }
+}
@@ -0,0 +1,202 @@
+use crate::algorithms::fitness::{Language, LanguageModel, read_ngram_table};
+use crate::components::cipher_component::CipherComponent;
+use anyhow::{Result, anyhow};
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+use std::path::Path;
+use std::sync::Arc;
+
+pub const TITLE: &str = "Language Model";
+
+/// Letters listed in the model summary.
+const SHOWN_LETTERS: usize = 12;
+
+/// Chooses the language every attack in the tab scores plaintext against: one of the
+/// built-in models, or one built from a text file.
+pub struct LanguageModelComponent {
+    corpus_path: String,
+    table_path: String,
+    current_field: usize,
+    /// Built-in language last picked with Left/Right
+    language: Language,
+    model: Arc<LanguageModel>,
+    /// Model to hand to the other components on the next key press
+    pending: Option<Arc<LanguageModel>>,
+    status: String,
+}
+
+impl Default for LanguageModelComponent {
+    fn default() -> Self {
+        Self {
+            corpus_path: String::new(),
+            table_path: String::new(),
+            current_field: 0,
+            language: Language::English,
+            model: LanguageModel::built_in(Language::English),
+            pending: None,
+            status: String::new(),
+        }
+    }
+}
+
+impl LanguageModelComponent {
+    fn activate(&mut self, model: Arc<LanguageModel>) {
+        self.status = format!("All attacks now score against {}", model.name());
+        self.pending = Some(Arc::clone(&model));
+        self.model = model;
+    }
+
+    fn cycle_language(&mut self, step: usize) {
+        let count = Language::ALL.len();
+        let index = Language::ALL
+            .iter()
+            .position(|&l| l == self.language)
+            .unwrap_or(0);
+        self.language = Language::ALL[(index + step) % count];
+        self.activate(LanguageModel::built_in(self.language));
+    }
+
+    /// Builds a model from the corpus (or table) file and saves its table if asked to.
+    fn load(&mut self) -> Result<()> {
+        let path = self.corpus_path.trim();
+        if path.is_empty() {
+            return Err(anyhow!("Enter the path of a text file or n-gram table"));
+        }
+        let table = read_ngram_table(path)?;
+        let name = Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string());
+        let model = LanguageModel::from_table(&name, &table)?;
+
+        let table_path = self.table_path.trim().to_string();
+        if !table_path.is_empty() {
+            std::fs::write(&table_path, &table)
+                .map_err(|e| anyhow!("Could not write {}: {}", table_path, e))?;
+        }
+        self.activate(Arc::new(model));
+        if !table_path.is_empty() {
+            self.status = format!("{} | Table saved to {}", self.status, table_path);
+        }
+        Ok(())
+    }
+
+    fn summary(&self) -> String {
+        let frequencies = self.model.frequencies();
+        let mut letters: Vec<usize> = (0..26).collect();
+        letters.sort_by(|&a, &b| frequencies[b].total_cmp(&frequencies[a]));
+        let common = letters
+            .iter()
+            .take(SHOWN_LETTERS)
+            .map(|&l| {
+                format!(
+                    "{} {:.1}%",
+                    (b'A' + l as u8) as char,
+                    frequencies[l] * 100.0
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        format!(
+            "Active model: {}\nExpected IoC: {:.4} (random 0.0385)\nMost common letters: {}",
+            self.model.name(),
+            self.model.expected_ioc(),
+            common
+        )
+    }
+}
+
+impl CipherComponent for LanguageModelComponent {
+    fn title(&self) -> &'static str {
+        TITLE
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Right => self.cycle_language(1),
+            KeyCode::Left => self.cycle_language(Language::ALL.len() - 1),
+            KeyCode::Up | KeyCode::Down => {
+                self.current_field = (self.current_field + 1) % 2;
+            }
+            KeyCode::Char(c) => match self.current_field {
+                0 => self.corpus_path.push(c),
+                1 => self.table_path.push(c),
+                _ => unreachable!(),
+            },
+            KeyCode::Backspace => match self.current_field {
+                0 => {
+                    self.corpus_path.pop();
+                }
+                1 => {
+                    self.table_path.pop();
+                }
+                _ => unreachable!(),
+            },
+            KeyCode::Enter => {
+                if let Err(err) = self.load() {
+                    self.status = format!("Error: {err}");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        Paragraph::new(format!(
+            "Built-in: {} (Left/Right to select) | Enter: build a model from the file below",
+            self.language.name()
+        ))
+        .block(Block::default().title("Mode").borders(Borders::ALL))
+        .render(layout[0], buf);
+
+        let corpus_label = "Text file or n-gram table";
+        let corpus_style = if self.current_field == 0 {
+            Block::default()
+                .title(format!(">> {}", corpus_label))
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title(corpus_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.corpus_path.as_str())
+            .block(corpus_style)
+            .render(layout[1], buf);
+
+        let table_label = "Save the n-gram table to (optional)";
+        let table_style = if self.current_field == 1 {
+            Block::default()
+                .title(format!(">> {}", table_label))
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title(table_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.table_path.as_str())
+            .block(table_style)
+            .render(layout[2], buf);
+
+        Paragraph::new(self.status.as_str())
+            .block(Block::default().title("Status").borders(Borders::ALL))
+            .render(layout[3], buf);
+
+        Paragraph::new(self.summary())
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Model").borders(Borders::ALL))
+            .render(layout[4], buf);
+    }
+
+    fn take_language_model(&mut self) -> Option<Arc<LanguageModel>> {
+        self.pending.take()
+    }
+}
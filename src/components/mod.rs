@@ -11,11 +11,48 @@
 //!
 //! Contents:
 //! - `cipher_component.rs`: trait defining the component interface
-//! - `caesar.rs`, `vigenere.rs`, `playfair.rs`: classical cipher UIs
+//! - `background.rs`: worker thread for long-running solvers, sharing the best result
+//! - `caesar.rs`, `vigenere.rs`, `hill.rs`, `affine.rs`: classical cipher UIs
+//! - `playfair.rs`: Playfair, Four-square and Two-square UI with digraph rule highlighting
+//!   and a simulated-annealing Playfair solver
+//! - `substitution.rs`: keyword, full-alphabet and Atbash substitution UI with the
+//!   plain and cipher alphabets lined up, and a background hill-climbing solver
+//! - `polybius.rs`: ADFGX, ADFGVX, Bifid, Trifid and Nihilist UI showing the square
+//! - `field_ciphers.rs`: Baconian, fractionated Morse, Pollux, Chaocipher and VIC
+//!   checkerboard UI
+//! - `frequency.rs`: letter, bigram and trigram statistics with a bar chart against
+//!   English; F2 in the Classical tab sends a cipher's output here
+//! - `identifier.rs`: ranks likely cipher families for unknown ciphertext and opens the
+//!   matching cipher on it
+//! - `language.rs`: picks the language model the classical attacks and the XOR breaker
+//!   score against, or builds one from a text file
+//! - `enigma.rs`: Enigma simulator with rotor windows and lamp board
+//! - `transposition.rs`: rail fence, columnar, route and scytale UI with grid view
 //! - `aes.rs`: AES file encrypt/decrypt UI
+//! - `one_time_pad.rs`: one-time pad on letters or bytes with pad generation
+//! - `crib_drag.rs`: two-time pad lab with interactive crib dragging
+//! - `xor_breaker.rs`: single-byte and repeating-key XOR breaker for hex, base64 or file
+//!   input, with the key-size ranking
+//! - `block_modes.rs`: ECB/CBC/CFB/OFB/CTR traced block by block, and the ECB penguin
+//!   drawn from the bitmap in `data/`
 pub mod aes;
+pub mod affine;
+pub mod background;
+pub mod block_modes;
 pub mod caesar;
 pub mod cipher_component;
+pub mod crib_drag;
+pub mod enigma;
+pub mod field_ciphers;
+pub mod frequency;
 pub mod hash;
+pub mod hill;
+pub mod identifier;
+pub mod language;
+pub mod one_time_pad;
 pub mod playfair;
+pub mod polybius;
+pub mod substitution;
+pub mod transposition;
 pub mod vigenere;
+pub mod xor_breaker;
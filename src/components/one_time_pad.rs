@@ -0,0 +1,214 @@
+use crate::algorithms::one_time_pad::{
+    generate_byte_pad, generate_letter_pad, otp_letters_decrypt, otp_letters_encrypt, otp_xor,
+};
+use crate::components::cipher_component::CipherComponent;
+use anyhow::{Result, anyhow};
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+#[derive(Default, Debug)]
+pub enum OneTimePadMode {
+    #[default]
+    Encrypt,
+    Decrypt,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PadAlphabet {
+    /// Letters A-Z, added mod 26
+    #[default]
+    Letters,
+    /// Raw bytes, XORed (Vernam)
+    Bytes,
+}
+
+impl PadAlphabet {
+    fn toggle(self) -> Self {
+        match self {
+            Self::Letters => Self::Bytes,
+            Self::Bytes => Self::Letters,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Letters => "Letters (mod 26)",
+            Self::Bytes => "Bytes (XOR)",
+        }
+    }
+}
+
+pub struct OneTimePadComponent {
+    input: String,
+    pad: String,
+    output: String,
+    status: String,
+    mode: OneTimePadMode,
+    alphabet: PadAlphabet,
+    current_field: usize,
+}
+
+impl Default for OneTimePadComponent {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            pad: String::new(),
+            output: String::new(),
+            status: String::new(),
+            mode: OneTimePadMode::Encrypt,
+            alphabet: PadAlphabet::Letters,
+            current_field: 0,
+        }
+    }
+}
+
+fn parse_hex(value: &str, name: &str) -> Result<Vec<u8>> {
+    let digits: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    hex::decode(digits).map_err(|e| anyhow!("{} is not valid hex: {}", name, e))
+}
+
+impl OneTimePadComponent {
+    /// Length of the pad the current input needs, in letters or bytes.
+    fn needed_pad_length(&self) -> usize {
+        match self.alphabet {
+            PadAlphabet::Letters => self
+                .input
+                .chars()
+                .filter(|c| c.is_ascii_alphabetic())
+                .count(),
+            PadAlphabet::Bytes => self.input.len(),
+        }
+    }
+
+    fn run(&mut self) -> Result<String> {
+        let encrypt = matches!(self.mode, OneTimePadMode::Encrypt);
+        if encrypt && self.pad.trim().is_empty() {
+            let len = self.needed_pad_length();
+            self.pad = match self.alphabet {
+                PadAlphabet::Letters => generate_letter_pad(len),
+                PadAlphabet::Bytes => hex::encode(generate_byte_pad(len)),
+            };
+            self.status = format!("Generated a fresh {}-symbol pad. Never use it twice.", len);
+        }
+
+        match self.alphabet {
+            PadAlphabet::Letters if encrypt => otp_letters_encrypt(&self.input, &self.pad),
+            PadAlphabet::Letters => otp_letters_decrypt(&self.input, &self.pad),
+            PadAlphabet::Bytes => {
+                let pad = parse_hex(&self.pad, "Pad")?;
+                if encrypt {
+                    Ok(hex::encode(otp_xor(self.input.as_bytes(), &pad)?))
+                } else {
+                    let ciphertext = parse_hex(&self.input, "Ciphertext")?;
+                    let plaintext = otp_xor(&ciphertext, &pad)?;
+                    Ok(String::from_utf8_lossy(&plaintext).into_owned())
+                }
+            }
+        }
+    }
+}
+
+impl CipherComponent for OneTimePadComponent {
+    fn title(&self) -> &'static str {
+        "One-Time Pad"
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                self.mode = match self.mode {
+                    OneTimePadMode::Encrypt => OneTimePadMode::Decrypt,
+                    OneTimePadMode::Decrypt => OneTimePadMode::Encrypt,
+                };
+            }
+            KeyCode::Left | KeyCode::Right => {
+                self.alphabet = self.alphabet.toggle();
+            }
+            KeyCode::Up | KeyCode::Down => {
+                self.current_field = 1 - self.current_field;
+            }
+            KeyCode::Char(c) => match self.current_field {
+                0 => self.input.push(c),
+                1 => self.pad.push(c),
+                _ => unreachable!(),
+            },
+            KeyCode::Backspace => match self.current_field {
+                0 => {
+                    self.input.pop();
+                }
+                1 => {
+                    self.pad.pop();
+                }
+                _ => unreachable!(),
+            },
+            KeyCode::Enter => {
+                self.status.clear();
+                self.output = self.run().unwrap_or_else(|err| format!("Error: {err}"));
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(4),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        Paragraph::new(format!(
+            "Mode: {:?} (Tab to switch) | Alphabet: {} (Left/Right to switch)",
+            self.mode,
+            self.alphabet.name()
+        ))
+        .block(Block::default().title("Mode").borders(Borders::ALL))
+        .render(layout[0], buf);
+
+        let input_label = match (&self.mode, self.alphabet) {
+            (OneTimePadMode::Decrypt, PadAlphabet::Bytes) => "Ciphertext (hex)",
+            _ => "Input Text",
+        };
+        let input_style = if self.current_field == 0 {
+            Block::default()
+                .title(format!(">> {}", input_label))
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title(input_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.input.as_str())
+            .block(input_style)
+            .render(layout[1], buf);
+
+        let pad_label = match self.alphabet {
+            PadAlphabet::Letters => "Pad (letters, empty = generate when encrypting)",
+            PadAlphabet::Bytes => "Pad (hex, empty = generate when encrypting)",
+        };
+        let pad_style = if self.current_field == 1 {
+            Block::default()
+                .title(format!(">> {}", pad_label))
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title(pad_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.pad.as_str())
+            .wrap(Wrap { trim: false })
+            .block(pad_style)
+            .render(layout[2], buf);
+
+        Paragraph::new(self.status.as_str())
+            .block(Block::default().title("Status").borders(Borders::ALL))
+            .render(layout[3], buf);
+
+        Paragraph::new(self.output.as_str())
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Output").borders(Borders::ALL))
+            .render(layout[4], buf);
+    }
+}
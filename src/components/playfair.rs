@@ -1,25 +1,115 @@
-use crate::algorithms::classical_ciphers::{playfair_decrypt, playfair_encrypt};
+use crate::algorithms::classical_ciphers::{
+    DigraphOptions, PlayfairRule, PlayfairStep, SquareMerge, TwoSquareLayout, four_square_decrypt,
+    four_square_encrypt, playfair_decrypt, playfair_encrypt, playfair_steps, two_square_decrypt,
+    two_square_encrypt,
+};
+use crate::algorithms::cryptanalysis::{AnnealingSettings, PlayfairSolution, solve_playfair};
+use crate::algorithms::fitness::{Language, LanguageModel, text_letters};
+use crate::components::background::BackgroundSearch;
 use crate::components::cipher_component::CipherComponent;
+use anyhow::{Context, Result, bail};
 use ratatui::crossterm::event::KeyCode;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
-    widgets::{Block, Borders, Paragraph, Widget},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
 };
+use std::sync::Arc;
+
+pub const TITLE: &str = "Playfair, Four-square and Two-square";
 
 #[derive(Default, Debug)]
 pub enum PlayfairMode {
     #[default]
     Encrypt,
     Decrypt,
+    /// Search for the Playfair square by simulated annealing
+    Crack,
+}
+
+/// Annealing rounds per search; each starts from a fresh random square.
+const SOLVER_ROUNDS: usize = 20;
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DigraphVariant {
+    #[default]
+    Playfair,
+    FourSquare,
+    TwoSquareVertical,
+    TwoSquareHorizontal,
+}
+
+impl DigraphVariant {
+    const ALL: [Self; 4] = [
+        Self::Playfair,
+        Self::FourSquare,
+        Self::TwoSquareVertical,
+        Self::TwoSquareHorizontal,
+    ];
+
+    fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        let idx = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Playfair => "Playfair",
+            Self::FourSquare => "Four-square",
+            Self::TwoSquareVertical => "Two-square (vertical)",
+            Self::TwoSquareHorizontal => "Two-square (horizontal)",
+        }
+    }
+
+    fn key_labels(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Playfair => ("Key (letters only)", "(unused)"),
+            Self::FourSquare => ("Top-right square key", "Bottom-left square key"),
+            Self::TwoSquareVertical => ("Top square key", "Bottom square key"),
+            Self::TwoSquareHorizontal => ("Left square key", "Right square key"),
+        }
+    }
 }
 
+/// Merge and padding combinations offered in the options field.
+const OPTION_PRESETS: [DigraphOptions; 3] = [
+    DigraphOptions {
+        merge: SquareMerge::JIntoI,
+        padding: 'X',
+    },
+    DigraphOptions {
+        merge: SquareMerge::JIntoI,
+        padding: 'Q',
+    },
+    DigraphOptions {
+        merge: SquareMerge::OmitQ,
+        padding: 'X',
+    },
+];
+
+const OPTIONS_FIELD: usize = 3;
+
 pub struct PlayfairCipherComponent {
     input: String,
     key: String,
+    key2: String,
     output: String,
     mode: PlayfairMode,
+    variant: DigraphVariant,
+    preset: usize,
+    steps: Vec<PlayfairStep>,
+    selected_step: usize,
+    solver: BackgroundSearch<PlayfairSolution>,
     current_field: usize,
+    /// Language the attack scores plaintext against
+    model: Arc<LanguageModel>,
 }
 
 impl Default for PlayfairCipherComponent {
@@ -27,16 +117,278 @@ impl Default for PlayfairCipherComponent {
         Self {
             input: String::new(),
             key: String::new(),
+            key2: String::new(),
             output: String::new(),
             mode: PlayfairMode::Encrypt,
+            variant: DigraphVariant::Playfair,
+            preset: 0,
+            steps: Vec::new(),
+            selected_step: 0,
+            solver: BackgroundSearch::default(),
             current_field: 0,
+            model: LanguageModel::built_in(Language::English),
+        }
+    }
+}
+
+fn options_name(options: &DigraphOptions) -> String {
+    let merge = match options.merge {
+        SquareMerge::JIntoI => "J merged into I",
+        SquareMerge::OmitQ => "Q omitted",
+    };
+    format!("{}, padding {}", merge, options.padding)
+}
+
+fn rule_name(rule: PlayfairRule) -> &'static str {
+    match rule {
+        PlayfairRule::Row => "row",
+        PlayfairRule::Column => "column",
+        PlayfairRule::Rectangle => "rectangle",
+    }
+}
+
+fn format_square(label: &str, cells: &[char]) -> Vec<String> {
+    let mut lines = vec![format!("{:<10}", label)];
+    lines.extend(cells.chunks(5).map(|row| {
+        row.iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }));
+    lines
+}
+
+/// Puts blocks of lines next to each other with a gap between them.
+fn side_by_side(blocks: &[Vec<String>]) -> Vec<String> {
+    let height = blocks.iter().map(Vec::len).max().unwrap_or(0);
+    (0..height)
+        .map(|i| {
+            blocks
+                .iter()
+                .map(|b| format!("{:<10}", b.get(i).map(String::as_str).unwrap_or("")))
+                .collect::<Vec<_>>()
+                .join("    ")
+        })
+        .collect()
+}
+
+impl PlayfairCipherComponent {
+    fn options(&self) -> DigraphOptions {
+        OPTION_PRESETS[self.preset]
+    }
+
+    fn run(&mut self) -> Result<String> {
+        let encrypt = matches!(self.mode, PlayfairMode::Encrypt);
+        let (input, key1, key2) = (self.input.clone(), self.key.clone(), self.key2.clone());
+        let options = self.options();
+        self.steps.clear();
+        self.selected_step = 0;
+
+        match self.variant {
+            DigraphVariant::Playfair => {
+                self.steps = playfair_steps(&input, &key1, options, encrypt)?;
+                if encrypt {
+                    playfair_encrypt(input, key1, options)
+                } else {
+                    playfair_decrypt(input, key1, options)
+                }
+            }
+            DigraphVariant::FourSquare if encrypt => {
+                four_square_encrypt(input, key1, key2, options)
+            }
+            DigraphVariant::FourSquare => four_square_decrypt(input, key1, key2, options),
+            DigraphVariant::TwoSquareVertical | DigraphVariant::TwoSquareHorizontal => {
+                let layout = if self.variant == DigraphVariant::TwoSquareVertical {
+                    TwoSquareLayout::Vertical
+                } else {
+                    TwoSquareLayout::Horizontal
+                };
+                if encrypt {
+                    two_square_encrypt(input, key1, key2, layout, options)
+                } else {
+                    two_square_decrypt(input, key1, key2, layout, options)
+                }
+            }
+        }
+    }
+
+    /// Annealing settings from the two key fields, defaulting from the ciphertext length.
+    fn annealing_settings(&self) -> Result<AnnealingSettings> {
+        let mut settings = AnnealingSettings::for_text(text_letters(&self.input).len());
+        settings.rounds = SOLVER_ROUNDS;
+        if !self.key.trim().is_empty() {
+            settings.temperature = self
+                .key
+                .trim()
+                .parse()
+                .context("Temperature must be a number")?;
+        }
+        if !self.key2.trim().is_empty() {
+            settings.iterations = self
+                .key2
+                .trim()
+                .parse()
+                .context("Iterations must be a whole number")?;
+        }
+        Ok(settings)
+    }
+
+    /// Enter in Crack mode: stops a running search, otherwise starts a new one.
+    fn toggle_search(&mut self) -> Result<()> {
+        if self.solver.is_running() {
+            return self.solver.stop();
+        }
+        self.solver.stop()?;
+        if self.variant != DigraphVariant::Playfair {
+            bail!("The solver only attacks Playfair");
+        }
+        let settings = self.annealing_settings()?;
+        let (ciphertext, options) = (self.input.clone(), self.options());
+        let model = Arc::clone(&self.model);
+        self.solver.reset();
+        self.solver.start(move |stop, report| {
+            solve_playfair(&ciphertext, options, settings, &model, stop, report)
+        });
+        Ok(())
+    }
+
+    fn solver_view(&self) -> Vec<Line<'_>> {
+        let state = if self.solver.is_running() {
+            "Searching..."
+        } else {
+            "Stopped"
+        };
+        let Some(best) = self.solver.best() else {
+            return vec![Line::raw(format!("{} | Enter starts a search", state))];
+        };
+        let cells: Vec<char> = best.square.chars().collect();
+        let mut lines = format_square("Best square", &cells);
+        lines.push(String::new());
+        lines.push(format!(
+            "{} round {}, iteration {}",
+            state, best.round, best.iteration
+        ));
+        lines.push(format!("Fitness {:.3} per quadgram", best.fitness));
+        lines.into_iter().map(Line::raw).collect()
+    }
+
+    /// The Playfair square with the selected digraph's input letters and result letters
+    /// highlighted.
+    fn playfair_grid(&self) -> Vec<Line<'_>> {
+        let square = self.options().square(&self.key);
+        let step = self.steps.get(self.selected_step);
+        let mut lines: Vec<Line> = square
+            .rows()
+            .into_iter()
+            .map(|row| {
+                let spans = row
+                    .into_iter()
+                    .map(|c| {
+                        let style = match step {
+                            Some(s) if c == s.input.0 || c == s.input.1 => Style::default()
+                                .fg(Color::Black)
+                                .bg(Color::Yellow)
+                                .add_modifier(Modifier::BOLD),
+                            Some(s) if c == s.output.0 || c == s.output.1 => Style::default()
+                                .fg(Color::Black)
+                                .bg(Color::Green)
+                                .add_modifier(Modifier::BOLD),
+                            _ => Style::default(),
+                        };
+                        Span::styled(format!(" {} ", c), style)
+                    })
+                    .collect::<Vec<_>>();
+                Line::from(spans)
+            })
+            .collect();
+
+        lines.push(Line::raw(""));
+        if let Some(s) = step {
+            lines.push(Line::raw(format!(
+                "Pair {}/{}: {}{} -> {}{} ({} rule)",
+                self.selected_step + 1,
+                self.steps.len(),
+                s.input.0,
+                s.input.1,
+                s.output.0,
+                s.output.1,
+                rule_name(s.rule)
+            )));
+            lines.push(Line::raw("PageUp/PageDown to step through pairs"));
+        }
+        lines
+    }
+
+    fn squares_view(&self) -> Vec<Line<'_>> {
+        let options = self.options();
+        let plain = options.square("").cells;
+        let first = options.square(&self.key).cells;
+        let second = options.square(&self.key2).cells;
+
+        if let PlayfairMode::Crack = self.mode {
+            return self.solver_view();
         }
+        let lines = match self.variant {
+            DigraphVariant::Playfair => return self.playfair_grid(),
+            DigraphVariant::FourSquare => {
+                let mut lines = side_by_side(&[
+                    format_square("Plain", &plain),
+                    format_square("Key 1", &first),
+                ]);
+                lines.push(String::new());
+                lines.extend(side_by_side(&[
+                    format_square("Key 2", &second),
+                    format_square("Plain", &plain),
+                ]));
+                lines
+            }
+            DigraphVariant::TwoSquareVertical => {
+                let mut lines = format_square("Key 1", &first);
+                lines.push(String::new());
+                lines.extend(format_square("Key 2", &second));
+                lines
+            }
+            DigraphVariant::TwoSquareHorizontal => side_by_side(&[
+                format_square("Key 1", &first),
+                format_square("Key 2", &second),
+            ]),
+        };
+        lines.into_iter().map(Line::raw).collect()
+    }
+
+    fn steps_view(&self) -> String {
+        self.steps
+            .iter()
+            .map(|s| {
+                format!(
+                    "{}{} -> {}{}  {}",
+                    s.input.0,
+                    s.input.1,
+                    s.output.0,
+                    s.output.1,
+                    rule_name(s.rule)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 }
 
 impl CipherComponent for PlayfairCipherComponent {
     fn title(&self) -> &'static str {
-        "Playfair Cipher"
+        TITLE
+    }
+
+    fn output(&self) -> Option<&str> {
+        Some(self.output.as_str())
+    }
+
+    fn set_input(&mut self, text: &str) {
+        self.input = text.to_string();
+    }
+
+    fn set_language_model(&mut self, model: Arc<LanguageModel>) {
+        self.model = model;
     }
 
     fn handle_event(&mut self, key: KeyCode) {
@@ -44,41 +396,81 @@ impl CipherComponent for PlayfairCipherComponent {
             KeyCode::Tab => {
                 self.mode = match self.mode {
                     PlayfairMode::Encrypt => PlayfairMode::Decrypt,
-                    PlayfairMode::Decrypt => PlayfairMode::Encrypt,
+                    PlayfairMode::Decrypt => PlayfairMode::Crack,
+                    PlayfairMode::Crack => PlayfairMode::Encrypt,
                 };
             }
+            KeyCode::Right if self.current_field == OPTIONS_FIELD => {
+                self.preset = (self.preset + 1) % OPTION_PRESETS.len();
+            }
+            KeyCode::Left if self.current_field == OPTIONS_FIELD => {
+                self.preset = (self.preset + OPTION_PRESETS.len() - 1) % OPTION_PRESETS.len();
+            }
+            KeyCode::Right => {
+                self.variant = self.variant.next();
+                self.steps.clear();
+            }
+            KeyCode::Left => {
+                self.variant = self.variant.previous();
+                self.steps.clear();
+            }
             KeyCode::Up => {
-                self.current_field = (self.current_field + 1) % 2;
+                self.current_field = (self.current_field + OPTIONS_FIELD) % (OPTIONS_FIELD + 1);
             }
             KeyCode::Down => {
-                self.current_field = (self.current_field + 1) % 2;
+                self.current_field = (self.current_field + 1) % (OPTIONS_FIELD + 1);
+            }
+            KeyCode::PageDown if !self.steps.is_empty() => {
+                self.selected_step = (self.selected_step + 1) % self.steps.len();
+            }
+            KeyCode::PageUp if !self.steps.is_empty() => {
+                self.selected_step = (self.selected_step + self.steps.len() - 1) % self.steps.len();
             }
             KeyCode::Char(c) => match self.current_field {
                 0 => self.input.push(c),
                 1 => self.key.push(c),
-                _ => unreachable!(),
+                2 => self.key2.push(c),
+                _ => {}
             },
             KeyCode::Backspace => match self.current_field {
                 0 => {
                     self.input.pop();
                 }
                 1 => {
-                    self.input.pop();
+                    self.key.pop();
+                }
+                2 => {
+                    self.key2.pop();
                 }
-                _ => unreachable!(),
+                _ => {}
             },
-            KeyCode::Enter => {
-                self.output = match self.mode {
-                    PlayfairMode::Encrypt => playfair_encrypt(self.input.clone(), self.key.clone()),
-                    PlayfairMode::Decrypt => playfair_decrypt(self.input.clone(), self.key.clone()),
+            KeyCode::Enter if matches!(self.mode, PlayfairMode::Crack) => {
+                if let Err(err) = self.toggle_search() {
+                    self.output = format!("Error: {err}");
+                    return;
                 }
             }
+            KeyCode::Enter => {
+                self.output = self.run().unwrap_or_else(|err| format!("Error: {err}"));
+            }
             _ => {}
         }
+        if !matches!(self.mode, PlayfairMode::Crack) {
+            return;
+        }
+        if !self.solver.is_running()
+            && let Err(err) = self.solver.stop()
+        {
+            self.output = format!("Error: {err}");
+        } else if let Some(best) = self.solver.best() {
+            self.output = best.plaintext;
+        }
     }
 
     fn render(&self, area: Rect, buf: &mut Buffer) {
         let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
             Constraint::Length(3),
             Constraint::Length(3),
             Constraint::Length(3),
@@ -86,7 +478,19 @@ impl CipherComponent for PlayfairCipherComponent {
         ])
         .split(area);
 
-        Paragraph::new(format!("Mode: {:?} (Tab to switch)", self.mode))
+        let mode_line = match self.mode {
+            PlayfairMode::Crack => format!(
+                "Mode: {:?} (Tab to switch) | Cipher: {} | Enter: start/stop simulated annealing",
+                self.mode,
+                self.variant.name()
+            ),
+            _ => format!(
+                "Mode: {:?} (Tab to switch) | Cipher: {} (Left/Right to switch)",
+                self.mode,
+                self.variant.name()
+            ),
+        };
+        Paragraph::new(mode_line)
             .block(Block::default().title("Mode").borders(Borders::ALL))
             .render(layout[0], buf);
 
@@ -101,21 +505,73 @@ impl CipherComponent for PlayfairCipherComponent {
             .block(input_style)
             .render(layout[1], buf);
 
-        let key_style = if self.current_field == 1 {
+        let [key1_area, key2_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(layout[2]);
+        let (key1_label, key2_label) = match self.mode {
+            PlayfairMode::Crack => (
+                "Start temperature (empty = from text length)",
+                "Iterations per round (empty = 500000)",
+            ),
+            _ => self.variant.key_labels(),
+        };
+
+        let key1_style = if self.current_field == 1 {
             Block::default()
-                .title(">> Key (letters only)")
+                .title(format!(">> {}", key1_label))
                 .borders(Borders::ALL)
         } else {
+            Block::default().title(key1_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.key.as_str())
+            .block(key1_style)
+            .render(key1_area, buf);
+
+        let key2_style = if self.current_field == 2 {
             Block::default()
-                .title("Key (letters only)")
+                .title(format!(">> {}", key2_label))
                 .borders(Borders::ALL)
+        } else {
+            Block::default().title(key2_label).borders(Borders::ALL)
         };
-        Paragraph::new(self.key.as_str())
-            .block(key_style)
-            .render(layout[2], buf);
+        Paragraph::new(self.key2.as_str())
+            .block(key2_style)
+            .render(key2_area, buf);
 
-        Paragraph::new(self.output.as_str())
-            .block(Block::default().title("Output").borders(Borders::ALL))
+        let options_style = if self.current_field == OPTIONS_FIELD {
+            Block::default()
+                .title(">> Square options (Left/Right to change)")
+                .borders(Borders::ALL)
+        } else {
+            Block::default()
+                .title("Square options")
+                .borders(Borders::ALL)
+        };
+        Paragraph::new(options_name(&self.options()))
+            .block(options_style)
             .render(layout[3], buf);
+
+        let output = match (&self.mode, self.solver.best()) {
+            (PlayfairMode::Crack, Some(best)) => best.plaintext,
+            _ => self.output.clone(),
+        };
+        Paragraph::new(output)
+            .block(Block::default().title("Output").borders(Borders::ALL))
+            .render(layout[4], buf);
+
+        let [squares_area, steps_area] =
+            Layout::horizontal([Constraint::Length(48), Constraint::Fill(1)]).areas(layout[5]);
+
+        Paragraph::new(self.squares_view())
+            .block(Block::default().title("Squares").borders(Borders::ALL))
+            .render(squares_area, buf);
+
+        Paragraph::new(self.steps_view())
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .title("Digraph Rules")
+                    .borders(Borders::ALL),
+            )
+            .render(steps_area, buf);
     }
 }
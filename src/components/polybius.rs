@@ -0,0 +1,311 @@
+use crate::algorithms::classical_ciphers::{
+    PolybiusSquare, adfgvx_decrypt, adfgvx_encrypt, adfgx_decrypt, adfgx_encrypt, bifid_decrypt,
+    bifid_encrypt, nihilist_decrypt, nihilist_encrypt, trifid_cube, trifid_decrypt, trifid_encrypt,
+};
+use crate::components::cipher_component::CipherComponent;
+use anyhow::{Result, anyhow};
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+pub const TITLE: &str = "Polybius Square Ciphers";
+
+#[derive(Default, Debug)]
+pub enum PolybiusMode {
+    #[default]
+    Encrypt,
+    Decrypt,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolybiusVariant {
+    #[default]
+    Adfgx,
+    Adfgvx,
+    Bifid,
+    Trifid,
+    Nihilist,
+}
+
+impl PolybiusVariant {
+    const ALL: [Self; 5] = [
+        Self::Adfgx,
+        Self::Adfgvx,
+        Self::Bifid,
+        Self::Trifid,
+        Self::Nihilist,
+    ];
+
+    fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        let idx = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Adfgx => "ADFGX",
+            Self::Adfgvx => "ADFGVX",
+            Self::Bifid => "Bifid",
+            Self::Trifid => "Trifid",
+            Self::Nihilist => "Nihilist",
+        }
+    }
+
+    fn param_label(self) -> &'static str {
+        match self {
+            Self::Adfgx | Self::Adfgvx => "Transposition keyword",
+            Self::Bifid | Self::Trifid => "Period (0 = whole message)",
+            Self::Nihilist => "Additive keyword",
+        }
+    }
+}
+
+pub struct PolybiusComponent {
+    input: String,
+    square_key: String,
+    param: String,
+    output: String,
+    mode: PolybiusMode,
+    variant: PolybiusVariant,
+    current_field: usize,
+}
+
+impl Default for PolybiusComponent {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            square_key: String::new(),
+            param: String::new(),
+            output: String::new(),
+            mode: PolybiusMode::Encrypt,
+            variant: PolybiusVariant::Adfgx,
+            current_field: 0,
+        }
+    }
+}
+
+fn format_square(square: &PolybiusSquare, labels: &[char]) -> String {
+    let header = labels.iter().map(|l| format!(" {}", l)).collect::<String>();
+    let rows = square
+        .rows()
+        .iter()
+        .zip(labels.iter())
+        .map(|(row, label)| {
+            let cells = row.iter().map(|c| format!(" {}", c)).collect::<String>();
+            format!("{} |{}", label, cells)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("   {}\n   {}\n{}", header, "-".repeat(header.len()), rows)
+}
+
+fn format_cube(cube: &PolybiusSquare) -> String {
+    let layers: Vec<&[char]> = cube.cells.chunks(9).collect();
+    let mut lines = vec![String::from("Layer 1   Layer 2   Layer 3")];
+    for row in 0..3 {
+        let line = layers
+            .iter()
+            .map(|layer| {
+                layer[row * 3..row * 3 + 3]
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("     ");
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+impl PolybiusComponent {
+    fn period(&self) -> Result<usize> {
+        let param = self.param.trim();
+        if param.is_empty() {
+            return Ok(0);
+        }
+        param
+            .parse::<usize>()
+            .map_err(|_| anyhow!("Period must be a whole number"))
+    }
+
+    fn run(&self) -> Result<String> {
+        let (input, square_key, param) = (
+            self.input.clone(),
+            self.square_key.clone(),
+            self.param.clone(),
+        );
+        let encrypt = matches!(self.mode, PolybiusMode::Encrypt);
+
+        match self.variant {
+            PolybiusVariant::Adfgx if encrypt => adfgx_encrypt(input, square_key, param),
+            PolybiusVariant::Adfgx => adfgx_decrypt(input, square_key, param),
+            PolybiusVariant::Adfgvx if encrypt => adfgvx_encrypt(input, square_key, param),
+            PolybiusVariant::Adfgvx => adfgvx_decrypt(input, square_key, param),
+            PolybiusVariant::Bifid if encrypt => {
+                Ok(bifid_encrypt(input, square_key, self.period()?))
+            }
+            PolybiusVariant::Bifid => Ok(bifid_decrypt(input, square_key, self.period()?)),
+            PolybiusVariant::Trifid if encrypt => {
+                Ok(trifid_encrypt(input, square_key, self.period()?))
+            }
+            PolybiusVariant::Trifid => Ok(trifid_decrypt(input, square_key, self.period()?)),
+            PolybiusVariant::Nihilist if encrypt => nihilist_encrypt(input, square_key, param),
+            PolybiusVariant::Nihilist => nihilist_decrypt(input, square_key, param),
+        }
+    }
+
+    fn square_view(&self) -> String {
+        match self.variant {
+            PolybiusVariant::Adfgx => format_square(
+                &PolybiusSquare::square_5x5(&self.square_key),
+                &['A', 'D', 'F', 'G', 'X'],
+            ),
+            PolybiusVariant::Adfgvx => format_square(
+                &PolybiusSquare::square_6x6(&self.square_key),
+                &['A', 'D', 'F', 'G', 'V', 'X'],
+            ),
+            PolybiusVariant::Bifid | PolybiusVariant::Nihilist => format_square(
+                &PolybiusSquare::square_5x5(&self.square_key),
+                &['1', '2', '3', '4', '5'],
+            ),
+            PolybiusVariant::Trifid => format_cube(&trifid_cube(&self.square_key)),
+        }
+    }
+}
+
+impl CipherComponent for PolybiusComponent {
+    fn title(&self) -> &'static str {
+        TITLE
+    }
+
+    fn output(&self) -> Option<&str> {
+        Some(self.output.as_str())
+    }
+
+    fn set_input(&mut self, text: &str) {
+        self.input = text.to_string();
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                self.mode = match self.mode {
+                    PolybiusMode::Encrypt => PolybiusMode::Decrypt,
+                    PolybiusMode::Decrypt => PolybiusMode::Encrypt,
+                };
+            }
+            KeyCode::Right => {
+                self.variant = self.variant.next();
+            }
+            KeyCode::Left => {
+                self.variant = self.variant.previous();
+            }
+            KeyCode::Up => {
+                self.current_field = (self.current_field + 2) % 3;
+            }
+            KeyCode::Down => {
+                self.current_field = (self.current_field + 1) % 3;
+            }
+            KeyCode::Char(c) => match self.current_field {
+                0 => self.input.push(c),
+                1 => self.square_key.push(c),
+                2 => self.param.push(c),
+                _ => unreachable!(),
+            },
+            KeyCode::Backspace => match self.current_field {
+                0 => {
+                    self.input.pop();
+                }
+                1 => {
+                    self.square_key.pop();
+                }
+                2 => {
+                    self.param.pop();
+                }
+                _ => unreachable!(),
+            },
+            KeyCode::Enter => {
+                self.output = self.run().unwrap_or_else(|err| format!("Error: {err}"));
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        Paragraph::new(format!(
+            "Mode: {:?} (Tab to switch) | Variant: {} (Left/Right to switch)",
+            self.mode,
+            self.variant.name()
+        ))
+        .block(Block::default().title("Mode").borders(Borders::ALL))
+        .render(layout[0], buf);
+
+        let input_style = if self.current_field == 0 {
+            Block::default()
+                .title(">> Input Text")
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title("Input Text").borders(Borders::ALL)
+        };
+        Paragraph::new(self.input.as_str())
+            .block(input_style)
+            .render(layout[1], buf);
+
+        let [square_key_area, param_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(layout[2]);
+
+        let square_key_style = if self.current_field == 1 {
+            Block::default()
+                .title(">> Square keyword")
+                .borders(Borders::ALL)
+        } else {
+            Block::default()
+                .title("Square keyword")
+                .borders(Borders::ALL)
+        };
+        Paragraph::new(self.square_key.as_str())
+            .block(square_key_style)
+            .render(square_key_area, buf);
+
+        let param_label = self.variant.param_label();
+        let param_style = if self.current_field == 2 {
+            Block::default()
+                .title(format!(">> {}", param_label))
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title(param_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.param.as_str())
+            .block(param_style)
+            .render(param_area, buf);
+
+        let [square_area, output_area] =
+            Layout::horizontal([Constraint::Length(32), Constraint::Fill(1)]).areas(layout[3]);
+
+        Paragraph::new(self.square_view())
+            .block(Block::default().title("Square").borders(Borders::ALL))
+            .render(square_area, buf);
+
+        Paragraph::new(self.output.as_str())
+            .block(Block::default().title("Output").borders(Borders::ALL))
+            .render(output_area, buf);
+    }
+}
@@ -0,0 +1,337 @@
+use crate::algorithms::classical_ciphers::{
+    ATBASH_ALPHABET, PLAIN_ALPHABET, check_substitution_alphabet, keyword_alphabet,
+    substitution_decrypt, substitution_encrypt,
+};
+use crate::algorithms::cryptanalysis::{
+    SubstitutionSolution, parse_locked_mappings, solve_substitution,
+};
+use crate::algorithms::fitness::{Language, LanguageModel};
+use crate::components::background::BackgroundSearch;
+use crate::components::cipher_component::CipherComponent;
+use anyhow::Result;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+use std::sync::Arc;
+
+pub const TITLE: &str = "Substitution Cipher";
+
+#[derive(Default, Debug)]
+pub enum SubstitutionMode {
+    #[default]
+    Encrypt,
+    Decrypt,
+    /// Search for the key with quadgram hill-climbing
+    Crack,
+}
+
+/// Random restarts per search; Enter continues from the best key afterwards.
+const SOLVER_RESTARTS: usize = 500;
+
+/// Where the cipher alphabet comes from.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlphabetSource {
+    #[default]
+    Keyword,
+    Permutation,
+    Atbash,
+}
+
+impl AlphabetSource {
+    const ALL: [Self; 3] = [Self::Keyword, Self::Permutation, Self::Atbash];
+
+    fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        let idx = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Keyword => "Keyword",
+            Self::Permutation => "Full alphabet",
+            Self::Atbash => "Atbash",
+        }
+    }
+
+    fn key_label(self) -> &'static str {
+        match self {
+            Self::Keyword => "Keyword",
+            Self::Permutation => "Cipher alphabet (26 letters, each once)",
+            Self::Atbash => "(not used by Atbash)",
+        }
+    }
+}
+
+pub struct SubstitutionCipherComponent {
+    input: String,
+    key: String,
+    output: String,
+    mode: SubstitutionMode,
+    source: AlphabetSource,
+    solver: BackgroundSearch<SubstitutionSolution>,
+    current_field: usize,
+    /// Language the attack scores plaintext against
+    model: Arc<LanguageModel>,
+}
+
+impl Default for SubstitutionCipherComponent {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            key: String::new(),
+            output: String::new(),
+            mode: SubstitutionMode::Encrypt,
+            source: AlphabetSource::Keyword,
+            solver: BackgroundSearch::default(),
+            current_field: 0,
+            model: LanguageModel::built_in(Language::English),
+        }
+    }
+}
+
+fn spaced(letters: &str) -> String {
+    letters
+        .chars()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl SubstitutionCipherComponent {
+    fn cipher_alphabet(&self) -> String {
+        match self.source {
+            AlphabetSource::Keyword => keyword_alphabet(&self.key),
+            AlphabetSource::Permutation => self
+                .key
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| c.to_ascii_uppercase())
+                .collect(),
+            AlphabetSource::Atbash => String::from(ATBASH_ALPHABET),
+        }
+    }
+
+    /// Plain and cipher alphabets lined up, with `_` for letters not entered yet.
+    fn alphabet_view(&self) -> String {
+        let alphabet = self.cipher_alphabet();
+        let cipher: String = alphabet
+            .chars()
+            .chain(std::iter::repeat('_'))
+            .take(26)
+            .collect();
+        let status = match check_substitution_alphabet(&alphabet) {
+            Ok(_) => String::from("Valid permutation"),
+            Err(err) => err.to_string(),
+        };
+
+        format!(
+            "Plain:  {}\nCipher: {}\n\n{}",
+            spaced(PLAIN_ALPHABET),
+            spaced(&cipher),
+            status
+        )
+    }
+
+    fn solver_view(&self) -> String {
+        let state = if self.solver.is_running() {
+            "Searching..."
+        } else {
+            "Stopped"
+        };
+        match self.solver.best() {
+            Some(best) => format!(
+                "Plain:  {}\nCipher: {}\n\n{} restart {} | fitness {:.3} per quadgram",
+                spaced(PLAIN_ALPHABET),
+                spaced(&best.alphabet),
+                state,
+                best.restarts,
+                best.fitness
+            ),
+            None => format!("{} | Enter starts a search", state),
+        }
+    }
+
+    /// Enter in Crack mode: stops a running search, otherwise starts or continues one.
+    fn toggle_search(&mut self) -> Result<()> {
+        if self.solver.is_running() {
+            self.solver.stop()
+        } else {
+            self.solver.stop()?;
+            let locks = parse_locked_mappings(&self.key)?;
+            let start = self.solver.best().map(|solution| solution.alphabet);
+            let (ciphertext, model) = (self.input.clone(), Arc::clone(&self.model));
+            self.solver.start(move |stop, report| {
+                solve_substitution(
+                    &ciphertext,
+                    &locks,
+                    start.as_deref(),
+                    SOLVER_RESTARTS,
+                    &model,
+                    stop,
+                    report,
+                )
+            });
+            Ok(())
+        }
+    }
+}
+
+impl CipherComponent for SubstitutionCipherComponent {
+    fn title(&self) -> &'static str {
+        TITLE
+    }
+
+    fn output(&self) -> Option<&str> {
+        Some(self.output.as_str())
+    }
+
+    fn set_input(&mut self, text: &str) {
+        self.input = text.to_string();
+    }
+
+    fn set_language_model(&mut self, model: Arc<LanguageModel>) {
+        self.model = model;
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                self.mode = match self.mode {
+                    SubstitutionMode::Encrypt => SubstitutionMode::Decrypt,
+                    SubstitutionMode::Decrypt => SubstitutionMode::Crack,
+                    SubstitutionMode::Crack => SubstitutionMode::Encrypt,
+                };
+            }
+            KeyCode::Right => {
+                self.source = self.source.next();
+            }
+            KeyCode::Left => {
+                self.source = self.source.previous();
+            }
+            KeyCode::Up | KeyCode::Down => {
+                self.current_field = 1 - self.current_field;
+            }
+            KeyCode::Char(c) => match self.current_field {
+                0 => self.input.push(c),
+                1 => self.key.push(c),
+                _ => unreachable!(),
+            },
+            KeyCode::Backspace => match self.current_field {
+                0 => {
+                    self.input.pop();
+                }
+                1 => {
+                    self.key.pop();
+                }
+                _ => unreachable!(),
+            },
+            KeyCode::Enter if matches!(self.mode, SubstitutionMode::Crack) => {
+                if let Err(err) = self.toggle_search() {
+                    self.output = format!("Error: {err}");
+                    return;
+                }
+            }
+            KeyCode::Delete if matches!(self.mode, SubstitutionMode::Crack) => {
+                self.solver.reset();
+                self.output.clear();
+            }
+            KeyCode::Enter => {
+                let alphabet = self.cipher_alphabet();
+                let result = match self.mode {
+                    SubstitutionMode::Encrypt => substitution_encrypt(self.input.clone(), alphabet),
+                    SubstitutionMode::Decrypt => substitution_decrypt(self.input.clone(), alphabet),
+                    SubstitutionMode::Crack => unreachable!(),
+                };
+                self.output = result.unwrap_or_else(|err| format!("Error: {err}"));
+            }
+            _ => {}
+        }
+        if !matches!(self.mode, SubstitutionMode::Crack) {
+            return;
+        }
+        if !self.solver.is_running()
+            && let Err(err) = self.solver.stop()
+        {
+            self.output = format!("Error: {err}");
+        } else if let Some(best) = self.solver.best() {
+            self.output = best.plaintext;
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(6),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        let mode_line = match self.mode {
+            SubstitutionMode::Crack => format!(
+                "Mode: {:?} (Tab to switch) | Enter: start/stop search | Del: forget best key",
+                self.mode
+            ),
+            _ => format!(
+                "Mode: {:?} (Tab to switch) | Alphabet: {} (Left/Right to switch)",
+                self.mode,
+                self.source.name()
+            ),
+        };
+        Paragraph::new(mode_line)
+            .block(Block::default().title("Mode").borders(Borders::ALL))
+            .render(layout[0], buf);
+
+        let input_style = if self.current_field == 0 {
+            Block::default()
+                .title(">> Input Text")
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title("Input Text").borders(Borders::ALL)
+        };
+        Paragraph::new(self.input.as_str())
+            .block(input_style)
+            .render(layout[1], buf);
+
+        let key_label = match self.mode {
+            SubstitutionMode::Crack => "Locked mappings (cipher/plain pairs, e.g. QE XT)",
+            _ => self.source.key_label(),
+        };
+        let key_style = if self.current_field == 1 {
+            Block::default()
+                .title(format!(">> {}", key_label))
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title(key_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.key.as_str())
+            .block(key_style)
+            .render(layout[2], buf);
+
+        let alphabets = match self.mode {
+            SubstitutionMode::Crack => self.solver_view(),
+            _ => self.alphabet_view(),
+        };
+        Paragraph::new(alphabets)
+            .block(Block::default().title("Alphabets").borders(Borders::ALL))
+            .render(layout[3], buf);
+
+        let output = match (&self.mode, self.solver.best()) {
+            (SubstitutionMode::Crack, Some(best)) => best.plaintext,
+            _ => self.output.clone(),
+        };
+        Paragraph::new(output)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Output").borders(Borders::ALL))
+            .render(layout[4], buf);
+    }
+}
@@ -0,0 +1,574 @@
+use crate::algorithms::cryptanalysis::{
+    ColumnarCandidate, MAX_COLUMNAR_WIDTH, MAX_RAIL_FENCE_RAILS, NgramFitness, RailFenceCandidate,
+    crack_rail_fence, solve_columnar,
+};
+use crate::algorithms::fitness::{Language, LanguageModel};
+use crate::algorithms::transposition::{
+    Grid, RoutePath, column_order, columnar_decrypt, columnar_encrypt, double_columnar_decrypt,
+    double_columnar_encrypt, rail_fence_decrypt, rail_fence_encrypt, rail_fence_grid,
+    route_decrypt, route_encrypt, route_pad, row_grid, scytale_decrypt, scytale_encrypt,
+    scytale_width,
+};
+use crate::components::background::BackgroundSearch;
+use crate::components::cipher_component::CipherComponent;
+use anyhow::{Result, anyhow, bail};
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+use std::sync::Arc;
+
+pub const TITLE: &str = "Transposition Ciphers";
+
+#[derive(Default, Debug)]
+pub enum TranspositionMode {
+    #[default]
+    Encrypt,
+    Decrypt,
+    /// Rank the keys of a rail fence or columnar ciphertext
+    Crack,
+}
+
+/// Candidates listed in Crack mode.
+const SHOWN_CANDIDATES: usize = 10;
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TranspositionVariant {
+    #[default]
+    RailFence,
+    Columnar,
+    DoubleColumnar,
+    RouteSpiral,
+    RouteSnake,
+    Scytale,
+}
+
+impl TranspositionVariant {
+    const ALL: [Self; 6] = [
+        Self::RailFence,
+        Self::Columnar,
+        Self::DoubleColumnar,
+        Self::RouteSpiral,
+        Self::RouteSnake,
+        Self::Scytale,
+    ];
+
+    fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        let idx = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::RailFence => "Rail Fence",
+            Self::Columnar => "Columnar",
+            Self::DoubleColumnar => "Double Columnar",
+            Self::RouteSpiral => "Route (spiral)",
+            Self::RouteSnake => "Route (snake)",
+            Self::Scytale => "Scytale",
+        }
+    }
+
+    fn key_labels(self) -> (&'static str, Option<&'static str>) {
+        match self {
+            Self::RailFence => ("Rails", Some("Offset")),
+            Self::Columnar => ("Keyword", None),
+            Self::DoubleColumnar => ("First keyword", Some("Second keyword")),
+            Self::RouteSpiral | Self::RouteSnake => ("Grid width", None),
+            Self::Scytale => ("Diameter (letters around the rod)", None),
+        }
+    }
+}
+
+pub struct TranspositionCipherComponent {
+    input: String,
+    key1: String,
+    key2: String,
+    output: String,
+    mode: TranspositionMode,
+    variant: TranspositionVariant,
+    current_field: usize,
+    fitness: NgramFitness,
+    rail_candidates: Vec<RailFenceCandidate>,
+    columnar_solver: BackgroundSearch<Vec<ColumnarCandidate>>,
+    /// Language the attack scores plaintext against
+    model: Arc<LanguageModel>,
+}
+
+impl Default for TranspositionCipherComponent {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            key1: String::from("3"),
+            key2: String::from("0"),
+            output: String::new(),
+            mode: TranspositionMode::Encrypt,
+            variant: TranspositionVariant::RailFence,
+            current_field: 0,
+            fitness: NgramFitness::default(),
+            rail_candidates: Vec::new(),
+            columnar_solver: BackgroundSearch::default(),
+            model: LanguageModel::built_in(Language::English),
+        }
+    }
+}
+
+fn parse_number(value: &str, name: &str) -> Result<usize> {
+    value
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| anyhow!("{} must be a whole number", name))
+}
+
+fn format_grid(grid: &Grid, empty: char) -> String {
+    grid.iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| cell.unwrap_or(empty).to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Grid with the key written above it and each column's reading position below the key.
+fn format_keyed_grid(text: &str, key: &str) -> String {
+    let letters: Vec<char> = key
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if letters.is_empty() {
+        return String::new();
+    }
+
+    let mut rank = vec![0; letters.len()];
+    for (position, col) in column_order(key).into_iter().enumerate() {
+        rank[col] = position + 1;
+    }
+
+    let header = letters
+        .iter()
+        .map(|c| format!("{:>2}", c))
+        .collect::<String>();
+    let ranks = rank.iter().map(|r| format!("{:>2}", r)).collect::<String>();
+    let rows = row_grid(text, letters.len())
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| format!("{:>2}", cell.unwrap_or(' ')))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "{}\n{}\n{}\n{}",
+        header,
+        ranks,
+        "-".repeat(header.len()),
+        rows
+    )
+}
+
+impl TranspositionCipherComponent {
+    fn run(&self, text: &str, encrypt: bool) -> Result<String> {
+        match self.variant {
+            TranspositionVariant::RailFence => {
+                let rails = parse_number(&self.key1, "Rails")?;
+                let offset = parse_number(&self.key2, "Offset")?;
+                if encrypt {
+                    rail_fence_encrypt(text, rails, offset)
+                } else {
+                    rail_fence_decrypt(text, rails, offset)
+                }
+            }
+            TranspositionVariant::Columnar => {
+                if encrypt {
+                    columnar_encrypt(text, &self.key1)
+                } else {
+                    columnar_decrypt(text, &self.key1)
+                }
+            }
+            TranspositionVariant::DoubleColumnar => {
+                if encrypt {
+                    double_columnar_encrypt(text, &self.key1, &self.key2)
+                } else {
+                    double_columnar_decrypt(text, &self.key1, &self.key2)
+                }
+            }
+            TranspositionVariant::RouteSpiral | TranspositionVariant::RouteSnake => {
+                let width = parse_number(&self.key1, "Grid width")?;
+                let path = self.route_path();
+                if encrypt {
+                    route_encrypt(text, width, path)
+                } else {
+                    route_decrypt(text, width, path)
+                }
+            }
+            TranspositionVariant::Scytale => {
+                let diameter = parse_number(&self.key1, "Diameter")?;
+                if encrypt {
+                    scytale_encrypt(text, diameter)
+                } else {
+                    scytale_decrypt(text, diameter)
+                }
+            }
+        }
+    }
+
+    fn route_path(&self) -> RoutePath {
+        if self.variant == TranspositionVariant::RouteSnake {
+            RoutePath::Snake
+        } else {
+            RoutePath::Spiral
+        }
+    }
+
+    /// The plaintext side of the current operation, i.e. what gets laid out in the grid.
+    fn plaintext(&self) -> Option<String> {
+        match self.mode {
+            TranspositionMode::Encrypt => Some(self.input.clone()),
+            TranspositionMode::Decrypt | TranspositionMode::Crack => {
+                self.run(&self.input, false).ok()
+            }
+        }
+    }
+
+    /// Enter in Crack mode. Rail fence is ranked at once and its best key copied into the
+    /// key fields; columnar starts (or stops) a background search.
+    fn crack(&mut self) -> Result<()> {
+        match self.variant {
+            TranspositionVariant::RailFence => {
+                self.rail_candidates =
+                    crack_rail_fence(&self.input, MAX_RAIL_FENCE_RAILS, self.fitness, &self.model)?;
+                if let Some(best) = self.rail_candidates.first() {
+                    self.key1 = best.rails.to_string();
+                    self.key2 = best.offset.to_string();
+                    self.output = best.plaintext.clone();
+                }
+                Ok(())
+            }
+            TranspositionVariant::Columnar => {
+                if self.columnar_solver.is_running() {
+                    return self.columnar_solver.stop();
+                }
+                let (ciphertext, fitness) = (self.input.clone(), self.fitness);
+                let model = Arc::clone(&self.model);
+                self.columnar_solver.reset();
+                self.columnar_solver.start(move |stop, report| {
+                    solve_columnar(
+                        &ciphertext,
+                        MAX_COLUMNAR_WIDTH,
+                        fitness,
+                        &model,
+                        stop,
+                        report,
+                    )
+                });
+                Ok(())
+            }
+            _ => bail!("Only rail fence and columnar ciphertexts can be attacked"),
+        }
+    }
+
+    fn candidates_view(&self) -> String {
+        let lines: Vec<String> = match self.variant {
+            TranspositionVariant::RailFence => self
+                .rail_candidates
+                .iter()
+                .take(SHOWN_CANDIDATES)
+                .enumerate()
+                .map(|(i, c)| {
+                    format!(
+                        "{:>2}. rails {:>2}, offset {:>2}  {:>7.3}  {}",
+                        i + 1,
+                        c.rails,
+                        c.offset,
+                        c.fitness,
+                        c.plaintext
+                    )
+                })
+                .collect(),
+            TranspositionVariant::Columnar => {
+                let state = if self.columnar_solver.is_running() {
+                    String::from("Searching... (Enter to stop)")
+                } else {
+                    format!("Stopped | Enter searches key widths 2-{MAX_COLUMNAR_WIDTH}")
+                };
+                let mut lines = vec![state];
+                lines.extend(
+                    self.columnar_solver
+                        .best()
+                        .unwrap_or_default()
+                        .iter()
+                        .take(SHOWN_CANDIDATES)
+                        .enumerate()
+                        .map(|(i, c)| {
+                            format!(
+                                "{:>2}. width {:>2}, key {:<12}  {:>7.3}  {}",
+                                i + 1,
+                                c.width,
+                                c.key,
+                                c.fitness,
+                                c.plaintext
+                            )
+                        }),
+                );
+                lines
+            }
+            _ => vec![String::from(
+                "Choose Rail Fence or Columnar (Left/Right) to attack a ciphertext",
+            )],
+        };
+        lines.join("\n")
+    }
+
+    fn grid_view(&self) -> String {
+        let Some(plaintext) = self.plaintext().filter(|p| !p.is_empty()) else {
+            return String::new();
+        };
+
+        match self.variant {
+            TranspositionVariant::RailFence => match parse_number(&self.key1, "Rails") {
+                Ok(rails) => {
+                    let offset = parse_number(&self.key2, "Offset").unwrap_or(0);
+                    format_grid(&rail_fence_grid(&plaintext, rails, offset), '.')
+                }
+                Err(err) => err.to_string(),
+            },
+            TranspositionVariant::Columnar => format_keyed_grid(&plaintext, &self.key1),
+            TranspositionVariant::DoubleColumnar => {
+                let middle = columnar_encrypt(&plaintext, &self.key1).unwrap_or_default();
+                format!(
+                    "First pass:\n{}\n\nSecond pass:\n{}",
+                    format_keyed_grid(&plaintext, &self.key1),
+                    format_keyed_grid(&middle, &self.key2)
+                )
+            }
+            TranspositionVariant::RouteSpiral | TranspositionVariant::RouteSnake => {
+                match parse_number(&self.key1, "Grid width") {
+                    Ok(width) if width > 0 => {
+                        let route = match self.route_path() {
+                            RoutePath::Spiral => "clockwise spiral from the top-left corner",
+                            RoutePath::Snake => "down column 1, up column 2, and so on",
+                        };
+                        format!(
+                            "{}\n\nRead {}",
+                            format_grid(&row_grid(&route_pad(&plaintext, width), width), '.'),
+                            route
+                        )
+                    }
+                    Ok(_) => String::from("Grid width must be at least 1"),
+                    Err(err) => err.to_string(),
+                }
+            }
+            TranspositionVariant::Scytale => match parse_number(&self.key1, "Diameter") {
+                Ok(diameter) if diameter > 0 => {
+                    let width = scytale_width(plaintext.chars().count(), diameter);
+                    format!(
+                        "{}\n\nOne row per turn around the rod, read column by column",
+                        format_grid(&row_grid(&plaintext, width), '.')
+                    )
+                }
+                Ok(_) => String::from("Diameter must be at least 1"),
+                Err(err) => err.to_string(),
+            },
+        }
+    }
+}
+
+impl CipherComponent for TranspositionCipherComponent {
+    fn title(&self) -> &'static str {
+        TITLE
+    }
+
+    fn output(&self) -> Option<&str> {
+        Some(self.output.as_str())
+    }
+
+    fn set_input(&mut self, text: &str) {
+        self.input = text.to_string();
+    }
+
+    fn set_language_model(&mut self, model: Arc<LanguageModel>) {
+        self.model = model;
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                self.mode = match self.mode {
+                    TranspositionMode::Encrypt => TranspositionMode::Decrypt,
+                    TranspositionMode::Decrypt => TranspositionMode::Crack,
+                    TranspositionMode::Crack => TranspositionMode::Encrypt,
+                };
+            }
+            KeyCode::Right => {
+                self.variant = self.variant.next();
+            }
+            KeyCode::Left => {
+                self.variant = self.variant.previous();
+            }
+            KeyCode::PageUp | KeyCode::PageDown
+                if matches!(self.mode, TranspositionMode::Crack) =>
+            {
+                self.fitness = self.fitness.toggled();
+            }
+            KeyCode::Up => {
+                self.current_field = (self.current_field + 2) % 3;
+            }
+            KeyCode::Down => {
+                self.current_field = (self.current_field + 1) % 3;
+            }
+            KeyCode::Char(c) => match self.current_field {
+                0 => self.input.push(c),
+                1 => self.key1.push(c),
+                2 => self.key2.push(c),
+                _ => unreachable!(),
+            },
+            KeyCode::Backspace => match self.current_field {
+                0 => {
+                    self.input.pop();
+                }
+                1 => {
+                    self.key1.pop();
+                }
+                2 => {
+                    self.key2.pop();
+                }
+                _ => unreachable!(),
+            },
+            KeyCode::Enter if matches!(self.mode, TranspositionMode::Crack) => {
+                if let Err(err) = self.crack() {
+                    self.output = format!("Error: {err}");
+                    return;
+                }
+            }
+            KeyCode::Enter => {
+                let encrypt = matches!(self.mode, TranspositionMode::Encrypt);
+                self.output = self
+                    .run(&self.input, encrypt)
+                    .unwrap_or_else(|err| format!("Error: {err}"));
+            }
+            _ => {}
+        }
+        if !matches!(self.mode, TranspositionMode::Crack) {
+            return;
+        }
+        if !self.columnar_solver.is_running()
+            && let Err(err) = self.columnar_solver.stop()
+        {
+            self.output = format!("Error: {err}");
+        } else if let Some(best) = self
+            .columnar_solver
+            .best()
+            .and_then(|c| c.into_iter().next())
+            && self.variant == TranspositionVariant::Columnar
+        {
+            self.key1 = best.key;
+            self.output = best.plaintext;
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        let mode_line = match self.mode {
+            TranspositionMode::Crack => format!(
+                "Mode: {:?} (Tab) | Variant: {} (Left/Right) | Fitness: {} (PageUp/PageDown) | Enter: attack",
+                self.mode,
+                self.variant.name(),
+                self.fitness.name()
+            ),
+            _ => format!(
+                "Mode: {:?} (Tab to switch) | Variant: {} (Left/Right to switch)",
+                self.mode,
+                self.variant.name()
+            ),
+        };
+        Paragraph::new(mode_line)
+            .block(Block::default().title("Mode").borders(Borders::ALL))
+            .render(layout[0], buf);
+
+        let input_style = if self.current_field == 0 {
+            Block::default()
+                .title(">> Input Text")
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title("Input Text").borders(Borders::ALL)
+        };
+        Paragraph::new(self.input.as_str())
+            .block(input_style)
+            .render(layout[1], buf);
+
+        let [key1_area, key2_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(layout[2]);
+        let (key1_label, key2_label) = self.variant.key_labels();
+
+        let key1_style = if self.current_field == 1 {
+            Block::default()
+                .title(format!(">> {}", key1_label))
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title(key1_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.key1.as_str())
+            .block(key1_style)
+            .render(key1_area, buf);
+
+        let key2_label = key2_label.unwrap_or("(unused)");
+        let key2_style = if self.current_field == 2 {
+            Block::default()
+                .title(format!(">> {}", key2_label))
+                .borders(Borders::ALL)
+        } else {
+            Block::default().title(key2_label).borders(Borders::ALL)
+        };
+        Paragraph::new(self.key2.as_str())
+            .block(key2_style)
+            .render(key2_area, buf);
+
+        let output = match (&self.mode, self.columnar_solver.best()) {
+            (TranspositionMode::Crack, Some(best))
+                if self.variant == TranspositionVariant::Columnar && !best.is_empty() =>
+            {
+                best[0].plaintext.clone()
+            }
+            _ => self.output.clone(),
+        };
+        Paragraph::new(output)
+            .block(Block::default().title("Output").borders(Borders::ALL))
+            .render(layout[3], buf);
+
+        if let TranspositionMode::Crack = self.mode {
+            Paragraph::new(self.candidates_view())
+                .block(
+                    Block::default()
+                        .title("Ranked Candidates (fitness per n-gram, higher is better)")
+                        .borders(Borders::ALL),
+                )
+                .render(layout[4], buf);
+            return;
+        }
+
+        Paragraph::new(self.grid_view())
+            .block(Block::default().title("Grid Layout").borders(Borders::ALL))
+            .render(layout[4], buf);
+    }
+}
@@ -1,25 +1,101 @@
-use crate::algorithms::classical_ciphers::{vigenere_cipher_decrypt, vigenere_cipher_encrypt};
+use crate::algorithms::classical_ciphers::{
+    autokey_decrypt, autokey_encrypt, beaufort_cipher, ciphertext_autokey_decrypt,
+    ciphertext_autokey_encrypt, gronsfeld_decrypt, gronsfeld_encrypt, load_running_key,
+    porta_cipher, running_key_decrypt, running_key_encrypt, variant_beaufort_decrypt,
+    variant_beaufort_encrypt, vigenere_cipher_decrypt, vigenere_cipher_encrypt,
+};
+use crate::algorithms::cryptanalysis::{VigenereAnalysis, break_vigenere};
+use crate::algorithms::fitness::{Language, LanguageModel};
 use crate::components::cipher_component::CipherComponent;
+use anyhow::{Context, Result, bail};
 use ratatui::crossterm::event::KeyCode;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
-    widgets::{Block, Borders, Paragraph, Widget},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
 };
+use std::sync::Arc;
+
+pub const TITLE: &str = "Vigenere Cipher";
 
 #[derive(Default, Debug)]
 pub enum VigenereMode {
     #[default]
     Encrypt,
     Decrypt,
+    /// Recover the key of a standard Vigenère ciphertext
+    Crack,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VigenereVariant {
+    #[default]
+    Standard,
+    Autokey,
+    CiphertextAutokey,
+    Beaufort,
+    VariantBeaufort,
+    Gronsfeld,
+    Porta,
+    RunningKey,
+}
+
+impl VigenereVariant {
+    const ALL: [Self; 8] = [
+        Self::Standard,
+        Self::Autokey,
+        Self::CiphertextAutokey,
+        Self::Beaufort,
+        Self::VariantBeaufort,
+        Self::Gronsfeld,
+        Self::Porta,
+        Self::RunningKey,
+    ];
+
+    fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        let idx = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Standard => "Vigenere",
+            Self::Autokey => "Autokey (plaintext)",
+            Self::CiphertextAutokey => "Autokey (ciphertext)",
+            Self::Beaufort => "Beaufort",
+            Self::VariantBeaufort => "Variant Beaufort",
+            Self::Gronsfeld => "Gronsfeld",
+            Self::Porta => "Porta",
+            Self::RunningKey => "Running key",
+        }
+    }
+
+    fn key_label(self) -> &'static str {
+        match self {
+            Self::Gronsfeld => "Key (digits only)",
+            Self::RunningKey => "Key text file path",
+            _ => "Key (letters only)",
+        }
+    }
 }
 
 pub struct VigenereCipherComponent {
     input: String,
     key: String,
     output: String,
+    analysis: Option<VigenereAnalysis>,
     mode: VigenereMode,
+    variant: VigenereVariant,
     current_field: usize,
+    /// Language the attack scores plaintext against
+    model: Arc<LanguageModel>,
 }
 
 impl Default for VigenereCipherComponent {
@@ -28,15 +104,101 @@ impl Default for VigenereCipherComponent {
             input: String::new(),
             key: String::new(),
             output: String::new(),
+            analysis: None,
             mode: VigenereMode::Encrypt,
+            variant: VigenereVariant::Standard,
             current_field: 0,
+            model: LanguageModel::built_in(Language::English),
+        }
+    }
+}
+
+impl VigenereCipherComponent {
+    fn run(&self) -> Result<String> {
+        let (input, key) = (self.input.clone(), self.key.clone());
+        let encrypt = matches!(self.mode, VigenereMode::Encrypt);
+
+        match self.variant {
+            VigenereVariant::Standard => {
+                if !key.chars().any(|c| c.is_ascii_alphabetic()) {
+                    bail!("Key must contain at least one letter");
+                }
+                if encrypt {
+                    Ok(vigenere_cipher_encrypt(input, key))
+                } else {
+                    Ok(vigenere_cipher_decrypt(input, key))
+                }
+            }
+            VigenereVariant::Autokey if encrypt => autokey_encrypt(input, key),
+            VigenereVariant::Autokey => autokey_decrypt(input, key),
+            VigenereVariant::CiphertextAutokey if encrypt => ciphertext_autokey_encrypt(input, key),
+            VigenereVariant::CiphertextAutokey => ciphertext_autokey_decrypt(input, key),
+            VigenereVariant::Beaufort => beaufort_cipher(input, key),
+            VigenereVariant::VariantBeaufort if encrypt => variant_beaufort_encrypt(input, key),
+            VigenereVariant::VariantBeaufort => variant_beaufort_decrypt(input, key),
+            VigenereVariant::Gronsfeld if encrypt => gronsfeld_encrypt(input, key),
+            VigenereVariant::Gronsfeld => gronsfeld_decrypt(input, key),
+            VigenereVariant::Porta => porta_cipher(input, key),
+            VigenereVariant::RunningKey => {
+                let key_text = load_running_key(key.trim())?;
+                if encrypt {
+                    running_key_encrypt(input, key_text)
+                } else {
+                    running_key_decrypt(input, key_text)
+                }
+            }
         }
     }
+
+    fn crack(&mut self) -> Result<String> {
+        self.analysis = None;
+        let length = match self.key.trim() {
+            "" => None,
+            length => Some(
+                length
+                    .parse()
+                    .context("Key length must be a whole number")?,
+            ),
+        };
+        let analysis = break_vigenere(&self.input, length, &self.model)?;
+        let output = analysis.plaintext.clone();
+        self.analysis = Some(analysis);
+        Ok(output)
+    }
+
+    fn key_length_lines(&self, analysis: &VigenereAnalysis) -> Vec<Line<'_>> {
+        let mut lines = vec![Line::raw(format!(
+            "Len  Avg IoC  Kasiski   ({} {:.3})",
+            self.model.name(),
+            self.model.expected_ioc()
+        ))];
+        lines.extend(analysis.key_lengths.iter().map(|c| {
+            let text = format!("{:>3}  {:.4}  {:>7}", c.length, c.ioc, c.kasiski);
+            if c.length == analysis.length {
+                Line::styled(text, Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                Line::raw(text)
+            }
+        }));
+        lines
+    }
 }
 
 impl CipherComponent for VigenereCipherComponent {
     fn title(&self) -> &'static str {
-        "Vigenere Cipher"
+        TITLE
+    }
+
+    fn output(&self) -> Option<&str> {
+        Some(self.output.as_str())
+    }
+
+    fn set_input(&mut self, text: &str) {
+        self.input = text.to_string();
+    }
+
+    fn set_language_model(&mut self, model: Arc<LanguageModel>) {
+        self.model = model;
     }
 
     fn handle_event(&mut self, key: KeyCode) {
@@ -44,7 +206,8 @@ impl CipherComponent for VigenereCipherComponent {
             KeyCode::Tab => {
                 self.mode = match self.mode {
                     VigenereMode::Encrypt => VigenereMode::Decrypt,
-                    VigenereMode::Decrypt => VigenereMode::Encrypt,
+                    VigenereMode::Decrypt => VigenereMode::Crack,
+                    VigenereMode::Crack => VigenereMode::Encrypt,
                 };
             }
             KeyCode::Up => {
@@ -67,15 +230,18 @@ impl CipherComponent for VigenereCipherComponent {
                 }
                 _ => unreachable!(),
             },
+            KeyCode::Right => {
+                self.variant = self.variant.next();
+            }
+            KeyCode::Left => {
+                self.variant = self.variant.previous();
+            }
             KeyCode::Enter => {
-                self.output = match self.mode {
-                    VigenereMode::Encrypt => {
-                        vigenere_cipher_encrypt(self.input.clone(), self.key.clone())
-                    }
-                    VigenereMode::Decrypt => {
-                        vigenere_cipher_decrypt(self.input.clone(), self.key.clone())
-                    }
-                }
+                let result = match self.mode {
+                    VigenereMode::Crack => self.crack(),
+                    _ => self.run(),
+                };
+                self.output = result.unwrap_or_else(|err| format!("Error: {err}"));
             }
             _ => {}
         }
@@ -90,9 +256,13 @@ impl CipherComponent for VigenereCipherComponent {
         ])
         .split(area);
 
-        Paragraph::new(format!("Mode: {:?} (Tab to switch)", self.mode))
-            .block(Block::default().title("Mode").borders(Borders::ALL))
-            .render(layout[0], buf);
+        Paragraph::new(format!(
+            "Mode: {:?} (Tab to switch) | Variant: {} (Left/Right to switch)",
+            self.mode,
+            self.variant.name()
+        ))
+        .block(Block::default().title("Mode").borders(Borders::ALL))
+        .render(layout[0], buf);
 
         let input_style = if self.current_field == 0 {
             Block::default()
@@ -105,21 +275,52 @@ impl CipherComponent for VigenereCipherComponent {
             .block(input_style)
             .render(layout[1], buf);
 
+        let key_label = match self.mode {
+            VigenereMode::Crack => "Key length (empty = best guess)",
+            _ => self.variant.key_label(),
+        };
         let key_style = if self.current_field == 1 {
             Block::default()
-                .title(">> Key (letters only)")
+                .title(format!(">> {}", key_label))
                 .borders(Borders::ALL)
         } else {
-            Block::default()
-                .title("Key (letters only)")
-                .borders(Borders::ALL)
+            Block::default().title(key_label).borders(Borders::ALL)
         };
         Paragraph::new(self.key.as_str())
             .block(key_style)
             .render(layout[2], buf);
 
+        let (VigenereMode::Crack, Some(analysis)) = (&self.mode, &self.analysis) else {
+            Paragraph::new(self.output.as_str())
+                .block(Block::default().title("Output").borders(Borders::ALL))
+                .render(layout[3], buf);
+            return;
+        };
+
+        let columns =
+            Layout::horizontal([Constraint::Length(40), Constraint::Fill(1)]).split(layout[3]);
+        Paragraph::new(self.key_length_lines(analysis))
+            .block(
+                Block::default()
+                    .title("Key Lengths (IoC per column)")
+                    .borders(Borders::ALL),
+            )
+            .render(columns[0], buf);
+
+        let rows = Layout::vertical([Constraint::Length(4), Constraint::Fill(1)]).split(columns[1]);
+        Paragraph::new(format!(
+            "Chi-squared: {}\nRefined:     {}",
+            analysis.chi_squared_key, analysis.refined_key
+        ))
+        .block(
+            Block::default()
+                .title(format!("Recovered Key (length {})", analysis.length))
+                .borders(Borders::ALL),
+        )
+        .render(rows[0], buf);
         Paragraph::new(self.output.as_str())
-            .block(Block::default().title("Output").borders(Borders::ALL))
-            .render(layout[3], buf);
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Plaintext").borders(Borders::ALL))
+            .render(rows[1], buf);
     }
 }
@@ -0,0 +1,285 @@
+use crate::algorithms::fitness::{Language, LanguageModel};
+use crate::algorithms::xor_analysis::{
+    MAX_XOR_KEY_SIZE, break_repeating_xor, crack_single_byte_xor, decode_ciphertext,
+    read_ciphertext_file, xor_key_sizes,
+};
+use crate::components::cipher_component::CipherComponent;
+use anyhow::Result;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+use std::sync::Arc;
+
+pub const TITLE: &str = "XOR Breaker";
+
+const FIELD_LABELS: [&str; 2] = [
+    "Ciphertext (hex or base64)",
+    "Or read it from a file (hex, base64 or raw bytes)",
+];
+
+/// Candidates listed for each mode.
+const SHOWN_CANDIDATES: usize = 8;
+
+#[derive(Default, Debug)]
+pub enum XorMode {
+    #[default]
+    RepeatingKey,
+    SingleByte,
+}
+
+/// A ranked key with its decryption.
+struct XorCandidate {
+    key: Vec<u8>,
+    score: f64,
+    /// Hamming distance of the key size, for repeating keys
+    distance: Option<f64>,
+    plaintext: Vec<u8>,
+}
+
+pub struct XorBreakerComponent {
+    fields: [String; 2],
+    mode: XorMode,
+    model: Arc<LanguageModel>,
+    candidates: Vec<XorCandidate>,
+    /// Most likely key sizes, as `(size, distance)`
+    key_sizes: Vec<(usize, f64)>,
+    selected: usize,
+    output: String,
+    error: String,
+    current_field: usize,
+}
+
+impl Default for XorBreakerComponent {
+    fn default() -> Self {
+        Self {
+            fields: Default::default(),
+            mode: XorMode::RepeatingKey,
+            model: LanguageModel::built_in(Language::English),
+            candidates: Vec::new(),
+            key_sizes: Vec::new(),
+            selected: 0,
+            output: String::new(),
+            error: String::new(),
+            current_field: 0,
+        }
+    }
+}
+
+fn printable(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+impl XorBreakerComponent {
+    /// The file when a path is given, the pasted text otherwise.
+    fn ciphertext(&self) -> Result<Vec<u8>> {
+        let path = self.fields[1].trim();
+        if path.is_empty() {
+            decode_ciphertext(&self.fields[0])
+        } else {
+            read_ciphertext_file(path)
+        }
+    }
+
+    fn run(&mut self) -> Result<()> {
+        let data = self.ciphertext()?;
+        match self.mode {
+            XorMode::RepeatingKey => {
+                self.key_sizes = xor_key_sizes(&data, MAX_XOR_KEY_SIZE)
+                    .into_iter()
+                    .take(SHOWN_CANDIDATES)
+                    .map(|c| (c.size, c.distance))
+                    .collect();
+                self.candidates = break_repeating_xor(&data, MAX_XOR_KEY_SIZE, &self.model)?
+                    .into_iter()
+                    .map(|s| XorCandidate {
+                        key: s.key,
+                        score: s.score,
+                        distance: Some(s.distance),
+                        plaintext: s.plaintext,
+                    })
+                    .collect();
+            }
+            XorMode::SingleByte => {
+                self.key_sizes.clear();
+                self.candidates = crack_single_byte_xor(&data, &self.model)
+                    .into_iter()
+                    .map(|c| XorCandidate {
+                        key: vec![c.key],
+                        score: c.score,
+                        distance: None,
+                        plaintext: c.plaintext,
+                    })
+                    .collect();
+            }
+        }
+        self.select(0);
+        Ok(())
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index;
+        self.output = self
+            .candidates
+            .get(index)
+            .map(|c| String::from_utf8_lossy(&c.plaintext).into_owned())
+            .unwrap_or_default();
+    }
+
+    fn candidates_view(&self) -> Vec<Line<'_>> {
+        if !self.error.is_empty() {
+            return vec![Line::raw(self.error.as_str())];
+        }
+        let mut lines: Vec<Line> = self
+            .candidates
+            .iter()
+            .enumerate()
+            .take(SHOWN_CANDIDATES)
+            .map(|(i, c)| {
+                let distance = c
+                    .distance
+                    .map(|d| format!("  distance {:.2}", d))
+                    .unwrap_or_default();
+                let text = format!(
+                    "{:>2}. score {:>6.2}{}  key {} \"{}\"",
+                    i + 1,
+                    c.score,
+                    distance,
+                    hex::encode(&c.key),
+                    printable(&c.key)
+                );
+                if i == self.selected {
+                    Line::styled(text, Style::default().fg(Color::Black).bg(Color::Yellow))
+                } else {
+                    Line::raw(text)
+                }
+            })
+            .collect();
+        if !self.key_sizes.is_empty() {
+            let sizes = self
+                .key_sizes
+                .iter()
+                .map(|(size, distance)| format!("{} ({:.2})", size, distance))
+                .collect::<Vec<_>>()
+                .join("  ");
+            lines.push(Line::raw(""));
+            lines.push(Line::raw(format!("Likely key sizes: {}", sizes)));
+        }
+        lines
+    }
+}
+
+impl CipherComponent for XorBreakerComponent {
+    fn title(&self) -> &'static str {
+        TITLE
+    }
+
+    fn output(&self) -> Option<&str> {
+        Some(self.output.as_str())
+    }
+
+    fn set_language_model(&mut self, model: Arc<LanguageModel>) {
+        self.model = model;
+    }
+
+    fn handle_event(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                self.mode = match self.mode {
+                    XorMode::RepeatingKey => XorMode::SingleByte,
+                    XorMode::SingleByte => XorMode::RepeatingKey,
+                };
+                self.candidates.clear();
+                self.key_sizes.clear();
+                self.output.clear();
+            }
+            KeyCode::Up | KeyCode::Down => {
+                self.current_field = (self.current_field + 1) % 2;
+            }
+            KeyCode::PageDown | KeyCode::Right if !self.candidates.is_empty() => {
+                let count = self.candidates.len().min(SHOWN_CANDIDATES);
+                self.select((self.selected + 1) % count);
+            }
+            KeyCode::PageUp | KeyCode::Left if !self.candidates.is_empty() => {
+                let count = self.candidates.len().min(SHOWN_CANDIDATES);
+                self.select((self.selected + count - 1) % count);
+            }
+            KeyCode::Char(c) => self.fields[self.current_field].push(c),
+            KeyCode::Backspace => {
+                self.fields[self.current_field].pop();
+            }
+            KeyCode::Enter => {
+                self.error.clear();
+                if let Err(err) = self.run() {
+                    self.candidates.clear();
+                    self.key_sizes.clear();
+                    self.output.clear();
+                    self.error = format!("Error: {err}");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(SHOWN_CANDIDATES as u16 + 4),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+        let mode = match self.mode {
+            XorMode::RepeatingKey => "Repeating key",
+            XorMode::SingleByte => "Single byte",
+        };
+        Paragraph::new(format!(
+            "Mode: {} (Tab to switch) | Enter: break | Left/Right: pick key | Scoring: {}",
+            mode,
+            self.model.name()
+        ))
+        .block(Block::default().title("Mode").borders(Borders::ALL))
+        .render(layout[0], buf);
+
+        for (i, label) in FIELD_LABELS.iter().enumerate() {
+            let style = if self.current_field == i {
+                Block::default()
+                    .title(format!(">> {}", label))
+                    .borders(Borders::ALL)
+            } else {
+                Block::default().title(*label).borders(Borders::ALL)
+            };
+            Paragraph::new(self.fields[i].as_str())
+                .block(style)
+                .render(layout[i + 1], buf);
+        }
+
+        Paragraph::new(self.candidates_view())
+            .block(
+                Block::default()
+                    .title("Ranked Keys (hex and printable)")
+                    .borders(Borders::ALL),
+            )
+            .render(layout[3], buf);
+
+        Paragraph::new(self.output.as_str())
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Plaintext").borders(Borders::ALL))
+            .render(layout[4], buf);
+    }
+}
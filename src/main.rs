@@ -14,16 +14,40 @@ use ratatui::{
     widgets::{Block, Padding, Paragraph, Tabs, Widget},
 };
 use std::error::Error;
+use std::time::Duration;
 use strum::{Display, EnumIter, FromRepr, IntoEnumIterator};
 use tabs::symmetric::SymmetricTab;
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("build-ngrams") {
+        return build_ngrams(&args[1..]);
+    }
+
     let terminal = ratatui::init();
     let app_result = App::default().run(terminal);
     ratatui::restore();
     app_result
 }
 
+/// `build-ngrams CORPUS...`: prints the n-gram table the Language Model panel would
+/// build from the given text files, without starting the UI. Used to build tables
+/// for other languages (see `src/algorithms/data/README.md`).
+fn build_ngrams(paths: &[String]) -> Result<(), Box<dyn Error>> {
+    if paths.is_empty() {
+        return Err("usage: crypto-swiss-knife build-ngrams CORPUS_FILE...".into());
+    }
+    let mut corpus = String::new();
+    for path in paths {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path, e))?;
+        corpus.push_str(&text);
+        corpus.push('\n');
+    }
+    print!("{}", algorithms::fitness::build_ngram_table(&corpus)?);
+    Ok(())
+}
+
 #[derive(Default)]
 struct App {
     state: AppState,
@@ -63,10 +87,14 @@ impl App {
     }
 
     fn handle_events(&mut self) -> std::io::Result<()> {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                self.handle_key_press(key.code);
-            }
+        // Wake up regularly so progress from background searches gets drawn
+        if !event::poll(Duration::from_millis(100))? {
+            return Ok(());
+        }
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            self.handle_key_press(key.code);
         }
         Ok(())
     }
@@ -86,6 +114,9 @@ impl App {
                 self.handle_global_events(key_code);
             }
             self.classical_tab.handle_event(key_code);
+            if let Some(model) = self.classical_tab.take_language_model() {
+                self.symmetric_tab.set_language_model(model);
+            }
         } else {
             self.handle_global_events(key_code);
         }
@@ -1,4 +1,15 @@
+use crate::algorithms::fitness::LanguageModel;
+use crate::components::affine::AffineCipherComponent;
+use crate::components::enigma::EnigmaComponent;
+use crate::components::field_ciphers::FieldCiphersComponent;
+use crate::components::frequency::{self, FrequencyAnalysisComponent};
+use crate::components::hill::HillCipherComponent;
+use crate::components::identifier::CipherIdentifierComponent;
+use crate::components::language::LanguageModelComponent;
 use crate::components::playfair::PlayfairCipherComponent;
+use crate::components::polybius::PolybiusComponent;
+use crate::components::substitution::SubstitutionCipherComponent;
+use crate::components::transposition::TranspositionCipherComponent;
 use crate::components::vigenere::VigenereCipherComponent;
 use crate::components::{caesar::CaesarCipherComponent, cipher_component::CipherComponent};
 use ratatui::crossterm::event::KeyCode;
@@ -9,11 +20,14 @@ use ratatui::{
     style::{Color, Style, Modifier},
     widgets::{Block, Borders},
 };
+use std::sync::Arc;
 
 pub struct ClassicalTab {
     selected: usize,
     mode: ClassicalMode,
     components: Vec<Box<dyn CipherComponent>>,
+    /// Model picked in the Language Model panel, for the other tabs to pick up
+    language_model: Option<Arc<LanguageModel>>,
 }
 
 impl Default for ClassicalTab {
@@ -23,9 +37,20 @@ impl Default for ClassicalTab {
             mode: ClassicalMode::Selecting,
             components: vec![
                 Box::new(CaesarCipherComponent::default()),
+                Box::new(AffineCipherComponent::default()),
+                Box::new(SubstitutionCipherComponent::default()),
                 Box::new(VigenereCipherComponent::default()),
                 Box::new(PlayfairCipherComponent::default()),
+                Box::new(HillCipherComponent::default()),
+                Box::new(PolybiusComponent::default()),
+                Box::new(FieldCiphersComponent::default()),
+                Box::new(TranspositionCipherComponent::default()),
+                Box::new(EnigmaComponent::default()),
+                Box::new(FrequencyAnalysisComponent::default()),
+                Box::new(CipherIdentifierComponent::default()),
+                Box::new(LanguageModelComponent::default()),
             ],
+            language_model: None,
         }
     }
 }
@@ -138,12 +163,43 @@ impl ClassicalTab {
             KeyCode::Esc => {
                 self.mode = ClassicalMode::Selecting;
             }
+            KeyCode::F(2) => self.analyse_output(),
             _ => {
                 self.components[self.selected].handle_event(key);
+                if let Some((title, text)) = self.components[self.selected].take_open_request() {
+                    self.open_with_input(title, &text);
+                }
+                if let Some(model) = self.components[self.selected].take_language_model() {
+                    for component in &mut self.components {
+                        component.set_language_model(Arc::clone(&model));
+                    }
+                    self.language_model = Some(model);
+                }
             }
         }
     }
 
+    /// Opens the frequency analysis panel on the current component's output.
+    fn analyse_output(&mut self) {
+        if let Some(output) = self.components[self.selected].output() {
+            let output = output.to_string();
+            self.open_with_input(frequency::TITLE, &output);
+        }
+    }
+
+    /// Switches to the component called `title` with `text` as its input.
+    fn open_with_input(&mut self, title: &str, text: &str) {
+        if let Some(index) = self.components.iter().position(|c| c.title() == title) {
+            self.components[index].set_input(text);
+            self.selected = index;
+        }
+    }
+
+    /// The language model picked since the last call, if any.
+    pub fn take_language_model(&mut self) -> Option<Arc<LanguageModel>> {
+        self.language_model.take()
+    }
+
     pub fn current_title(&self) -> &'static str {
         self.components[self.selected].title()
     }
@@ -10,8 +10,10 @@
 //! - Keep per-tab state local to the tab struct.
 //!
 //! Contents:
-//! - `classical.rs`: Caesar, Vigenère, Playfair group
-//! - `symmetric.rs`: AES group (and future symmetric ciphers)
+//! - `classical.rs`: Caesar, Affine, substitution, Vigenère, Playfair family, Hill,
+//!   Polybius-square, field ciphers, transposition, Enigma group, frequency analysis,
+//!   cipher identifier
+//! - `symmetric.rs`: AES, one-time pad and two-time pad lab (and future symmetric ciphers)
 pub mod classical;
 pub mod misc;
 pub mod symmetric;
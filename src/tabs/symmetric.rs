@@ -1,3 +1,8 @@
+use crate::algorithms::fitness::LanguageModel;
+use crate::components::block_modes::BlockModesComponent;
+use crate::components::crib_drag::CribDraggingComponent;
+use crate::components::one_time_pad::OneTimePadComponent;
+use crate::components::xor_breaker::XorBreakerComponent;
 use crate::components::{aes::AesCipherComponent, cipher_component::CipherComponent};
 use ratatui::crossterm::event::KeyCode;
 use ratatui::widgets::{Paragraph, Widget};
@@ -7,6 +12,7 @@ use ratatui::{
     style::{Color, Style, Modifier},
     widgets::{Block, Borders},
 };
+use std::sync::Arc;
 
 pub struct SymmetricTab {
     selected: usize,
@@ -26,7 +32,13 @@ impl Default for SymmetricTab {
         Self {
             selected: 0,
             mode: SymmetricMode::Selecting,
-            components: vec![Box::new(AesCipherComponent::default())],
+            components: vec![
+                Box::new(AesCipherComponent::default()),
+                Box::new(OneTimePadComponent::default()),
+                Box::new(CribDraggingComponent::default()),
+                Box::new(XorBreakerComponent::default()),
+                Box::new(BlockModesComponent::default()),
+            ],
         }
     }
 }
@@ -134,6 +146,14 @@ impl SymmetricTab {
         }
     }
 
+    /// Language the XOR breaker scores plaintext against, picked in the Classical tab's
+    /// Language Model panel.
+    pub fn set_language_model(&mut self, model: Arc<LanguageModel>) {
+        for component in &mut self.components {
+            component.set_language_model(Arc::clone(&model));
+        }
+    }
+
     pub fn current_title(&self) -> &'static str {
         self.components[self.selected].title()
     }